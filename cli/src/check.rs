@@ -0,0 +1,57 @@
+use clap::Parser;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::process;
+
+use standard_knowledge::{NetcdfHeader, StandardsLibrary};
+
+use crate::OutputFormat;
+
+#[derive(Parser)]
+pub struct CheckArgs {
+    /// NetCDF-3 classic or 64-bit offset file, or a CDL header as from `ncdump -h`
+    path: PathBuf,
+}
+
+pub fn execute(check_args: &CheckArgs, library: &StandardsLibrary, output: Option<OutputFormat>) {
+    let file = match File::open(&check_args.path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Unable to read {}: {e}", check_args.path.display());
+            process::exit(1);
+        }
+    };
+
+    // Only the header of a NetCDF file is read, not its data
+    let header = match NetcdfHeader::read_from(BufReader::new(file)) {
+        Ok(header) => header,
+        Err(e) => crate::exit_with_error(&e),
+    };
+    let checks = library.check_header(&header);
+
+    match output {
+        Some(output) => crate::print_serialized(&checks, output),
+        None => {
+            for check in &checks {
+                let standard_name = check
+                    .standard_name
+                    .as_ref()
+                    .map(|name| format!(" ({name})"))
+                    .unwrap_or_default();
+                if check.findings.is_empty() {
+                    println!("{}{standard_name}: ok", check.variable);
+                    continue;
+                }
+                println!("{}{standard_name}:", check.variable);
+                for finding in &check.findings {
+                    println!("  - {}: {finding}", finding.severity());
+                }
+            }
+        }
+    }
+
+    if checks.iter().any(|check| check.has_errors()) {
+        process::exit(2);
+    }
+}
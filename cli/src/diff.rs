@@ -0,0 +1,58 @@
+use clap::{Parser, ValueEnum};
+use standard_knowledge::StandardsLibrary;
+
+use crate::OutputFormat;
+
+#[derive(Parser)]
+pub struct DiffArgs {
+    /// CF standard name table to compare against. Defaults to the same table as the loaded library.
+    #[arg(long = "cf-table", value_name = "PATH")]
+    cf_table: Option<String>,
+
+    /// Knowledge sources to compare against. Defaults to the same sources as the loaded library.
+    #[arg(short = 'k', long = "knowledge", value_name = "SOURCE")]
+    knowledge_sources: Vec<String>,
+
+    /// Format to display in
+    #[arg(short, long, value_enum, default_value_t = DiffFormat::Text)]
+    format: DiffFormat,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum DiffFormat {
+    /// Human readable report
+    Text,
+    /// JSON report
+    Json,
+}
+
+pub fn execute(
+    diff_args: &DiffArgs,
+    library: &StandardsLibrary,
+    cf_table: Option<&String>,
+    knowledge_sources: &[String],
+    output: Option<OutputFormat>,
+) {
+    let other_cf_table = diff_args.cf_table.as_ref().or(cf_table);
+    let other_knowledge_sources = if diff_args.knowledge_sources.is_empty() {
+        knowledge_sources
+    } else {
+        &diff_args.knowledge_sources
+    };
+
+    let other = crate::load_library(other_cf_table, other_knowledge_sources);
+    let diff = library.diff(&other);
+
+    if let Some(output) = output {
+        crate::print_serialized(&diff, output);
+        return;
+    }
+
+    match diff_args.format {
+        DiffFormat::Text => println!("{diff}"),
+        DiffFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&diff).expect("Failed to serialize diff to JSON")
+        ),
+    }
+}
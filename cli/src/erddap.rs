@@ -0,0 +1,142 @@
+use clap::{Parser, ValueEnum};
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use standard_knowledge::{DatasetMetadata, ErddapDatasetType, StandardsLibrary};
+
+#[derive(Parser)]
+pub struct ErddapArgs {
+    /// Column or variable names in the source files
+    #[arg(required_unless_present = "csv", conflicts_with = "csv")]
+    columns: Vec<String>,
+
+    /// CSV file to read the column names from the header row of
+    #[arg(long, value_name = "PATH")]
+    csv: Option<PathBuf>,
+
+    /// ERDDAP datasetID
+    #[arg(long)]
+    dataset_id: String,
+
+    /// Kind of files the dataset reads
+    #[arg(short = 't', long = "type", value_enum, default_value_t = DatasetType::Ascii)]
+    dataset_type: DatasetType,
+
+    #[arg(long)]
+    title: Option<String>,
+
+    #[arg(long)]
+    summary: Option<String>,
+
+    #[arg(long)]
+    institution: Option<String>,
+
+    /// Web page about the dataset
+    #[arg(long)]
+    info_url: Option<String>,
+
+    /// Defaults to ERDDAP's [standard] license
+    #[arg(long)]
+    license: Option<String>,
+
+    #[arg(long)]
+    creator_name: Option<String>,
+
+    #[arg(long)]
+    creator_email: Option<String>,
+
+    #[arg(long)]
+    creator_url: Option<String>,
+
+    /// Defaults to Other
+    #[arg(long)]
+    cdm_data_type: Option<String>,
+
+    /// Directory ERDDAP reads the files from
+    #[arg(long)]
+    file_dir: Option<String>,
+
+    /// Defaults to .*\.csv or .*\.nc depending on the type
+    #[arg(long)]
+    file_name_regex: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum DatasetType {
+    /// EDDTableFromAsciiFiles
+    Ascii,
+    /// EDDTableFromNcFiles
+    Nc,
+}
+
+impl From<DatasetType> for ErddapDatasetType {
+    fn from(dataset_type: DatasetType) -> Self {
+        match dataset_type {
+            DatasetType::Ascii => ErddapDatasetType::AsciiFiles,
+            DatasetType::Nc => ErddapDatasetType::NcFiles,
+        }
+    }
+}
+
+/// Column names from the header row of a CSV file
+fn csv_columns(path: &PathBuf) -> Vec<String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Unable to read {}: {e}", path.display());
+            process::exit(1);
+        }
+    };
+    contents
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|column| column.trim().trim_matches('"').to_string())
+        .filter(|column| !column.is_empty())
+        .collect()
+}
+
+pub fn execute(erddap_args: &ErddapArgs, library: &StandardsLibrary) {
+    let columns = match &erddap_args.csv {
+        Some(path) => csv_columns(path),
+        None => erddap_args.columns.clone(),
+    };
+
+    let metadata = DatasetMetadata {
+        title: erddap_args.title.clone(),
+        summary: erddap_args.summary.clone(),
+        institution: erddap_args.institution.clone(),
+        info_url: erddap_args.info_url.clone(),
+        license: erddap_args.license.clone(),
+        creator_name: erddap_args.creator_name.clone(),
+        creator_email: erddap_args.creator_email.clone(),
+        creator_url: erddap_args.creator_url.clone(),
+        cdm_data_type: erddap_args.cdm_data_type.clone(),
+        file_dir: erddap_args.file_dir.clone(),
+        file_name_regex: erddap_args.file_name_regex.clone(),
+        ..DatasetMetadata::new(&erddap_args.dataset_id)
+    };
+
+    let dataset = match library.erddap_dataset(&columns, erddap_args.dataset_type.into(), metadata)
+    {
+        Ok(dataset) => dataset,
+        Err(error) => crate::exit_with_error(&error),
+    };
+
+    for column in &dataset.columns {
+        match &column.standard {
+            None => eprintln!("No standard found for column {}", column.source_name),
+            Some(standard) if !column.alternatives.is_empty() => eprintln!(
+                "Column {} matched {}, but could also be: {}",
+                column.source_name,
+                standard.name,
+                column.alternatives.join(", ")
+            ),
+            Some(_) => {}
+        }
+    }
+
+    println!("{}", dataset.to_xml());
+}
@@ -1,6 +1,10 @@
 use clap::{Parser, ValueEnum};
-use standard_knowledge::{Standard, StandardsLibrary};
+use standard_knowledge::qartod::types::QartodTestTypes;
+use standard_knowledge::{SortKey, Standard, StandardsFilter, StandardsLibrary};
 use std::process;
+use std::sync::Arc;
+
+use crate::OutputFormat;
 
 #[derive(Parser)]
 pub struct FilterArgs {
@@ -10,12 +14,77 @@ pub struct FilterArgs {
     /// Filter by IOOS category
     #[arg(short, long)]
     ioos_category: Option<String>,
+    /// Filter by IOOS category, inferring it from the name and unit for standards without one
+    #[arg(long, value_name = "CATEGORY")]
+    inferred_category: Option<String>,
+    /// How confident an inferred category has to be, from 0 to 1
+    #[arg(long, default_value_t = 0.5, requires = "inferred_category")]
+    min_confidence: f64,
     /// Filter by unit
     #[arg(short, long)]
     unit: Option<String>,
-    /// Search by string across multiple fields
+    /// Search by string across multiple fields, most relevant first
+    ///
+    /// Shows the 100 most relevant standards, unless --offset or --limit is given to page through every match.
     #[arg(short, long)]
     search: Option<String>,
+    /// Filter by a type of QARTOD test, like gross_range or spike
+    #[arg(long)]
+    test_type: Option<QartodTestTypes>,
+    /// Filter by an extra attribute, with an optional value, like `positive` or `positive=down`
+    #[arg(long, value_name = "KEY[=VALUE]")]
+    attr: Option<String>,
+    /// Filter to standards that list a standard as a sibling
+    #[arg(long, value_name = "STANDARD")]
+    sibling: Option<String>,
+    /// Filter to standards that list a standard as related
+    #[arg(long, value_name = "STANDARD")]
+    related: Option<String>,
+    /// Filter to standards that have IOOS knowledge
+    #[arg(long)]
+    has_knowledge: bool,
+    /// Filter by alias, without matching standard names
+    #[arg(long)]
+    alias: Option<String>,
+    /// Filter to standard names starting with a prefix
+    #[arg(long)]
+    prefix: Option<String>,
+    /// Filter to standard names ending with a suffix
+    #[arg(long)]
+    suffix: Option<String>,
+    /// Filter to standard names matching a regular expression
+    #[arg(long)]
+    regex: Option<String>,
+    /// Filter with a query, like `category:Temperature AND unit:K AND NOT name:*_at_sea_floor`
+    ///
+    /// Terms are `field:value` for name, alias, category, inferred_category, unit, var,
+    /// long_name, description, and comments, `has:` qartod, knowledge, aliases, long_name, category, or comments,
+    /// or words to search for. Values may use `*` and `?` wildcards.
+    /// Combine terms with AND, OR, NOT (or `-`), and parentheses.
+    #[arg(short, long)]
+    query: Option<String>,
+    /// Show how relevant each search result is, and which fields matched
+    ///
+    /// Shows the 100 most relevant standards, so it can't be sorted, paged, or formatted.
+    #[arg(
+        long,
+        requires = "search",
+        conflicts_with_all = ["sort", "offset", "limit", "format"]
+    )]
+    explain: bool,
+
+    /// Sort by a field, instead of by name or relevance
+    #[arg(long, value_name = "name|unit|ioos_category|long_name")]
+    sort: Option<SortKey>,
+    /// Sort in descending order
+    #[arg(long, requires = "sort")]
+    descending: bool,
+    /// Skip this many standards
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+    /// Show at most this many standards
+    #[arg(long)]
+    limit: Option<usize>,
 
     /// Format to display in
     #[arg(short, long, value_enum, default_value_t = ListFormat::Short)]
@@ -28,10 +97,16 @@ enum ListFormat {
     Short,
     /// Xarray attributes
     Xarray,
+    /// IOOS category, noting if it was inferred
+    Category,
+    /// ERDDAP datasets.xml <dataVariable>
+    Erddap,
+    /// NetCDF CDL skeleton, for ncgen
+    Cdl,
 }
 
 impl ListFormat {
-    fn format_standards(&self, standards: Vec<Standard>) -> String {
+    fn format_standards(&self, standards: Vec<Arc<Standard>>, cf_version: Option<&str>) -> String {
         match self {
             Self::Short => standards
                 .iter()
@@ -39,16 +114,35 @@ impl ListFormat {
                 .collect::<Vec<String>>()
                 .join("\n"),
 
-            Self::Xarray => standards
+            Self::Xarray => serde_json::to_string_pretty(
+                &standards
+                    .iter()
+                    .map(|standard| standard.xarray_attrs())
+                    .collect::<Vec<_>>(),
+            )
+            .expect("attributes always serialize"),
+
+            Self::Category => standards
+                .iter()
+                .map(|standard| match &standard.ioos_category {
+                    Some(category) => format!("- {} - {category}", standard.name),
+                    None => format!("- {} - {}", standard.name, standard.infer_ioos_category()),
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+
+            Self::Erddap => standards
                 .iter()
-                .map(|standard| standard.display_xarray_attrs())
+                .map(|standard| standard.erddap_data_variable(None, None))
                 .collect::<Vec<String>>()
-                .join(",\n"),
+                .join("\n"),
+
+            Self::Cdl => StandardsFilter::new(standards).to_cdl("standards", cf_version),
         }
     }
 }
 
-pub fn execute(filter_args: &FilterArgs, library: &StandardsLibrary) {
+pub fn execute(filter_args: &FilterArgs, library: &StandardsLibrary, output: Option<OutputFormat>) {
     let mut standards_filter = library.filter();
 
     if let Some(var) = &filter_args.var {
@@ -57,20 +151,99 @@ pub fn execute(filter_args: &FilterArgs, library: &StandardsLibrary) {
     if let Some(ioos_category) = &filter_args.ioos_category {
         standards_filter = standards_filter.by_ioos_category(ioos_category);
     }
+    if let Some(inferred_category) = &filter_args.inferred_category {
+        standards_filter = standards_filter
+            .by_inferred_ioos_category(inferred_category, filter_args.min_confidence);
+    }
     if let Some(unit) = &filter_args.unit {
         standards_filter = standards_filter.by_unit(unit);
     }
+    if let Some(test_type) = &filter_args.test_type {
+        standards_filter = standards_filter.by_qartod_test_type(test_type);
+    }
+    if let Some(attr) = &filter_args.attr {
+        standards_filter = match attr.split_once('=') {
+            Some((key, value)) => standards_filter.by_extra_attr(key, Some(value)),
+            None => standards_filter.by_extra_attr(attr, None),
+        };
+    }
+    if let Some(sibling) = &filter_args.sibling {
+        standards_filter = standards_filter.by_sibling(sibling);
+    }
+    if let Some(related) = &filter_args.related {
+        standards_filter = standards_filter.by_related(related);
+    }
+    if filter_args.has_knowledge {
+        standards_filter = standards_filter.has_knowledge();
+    }
+    if let Some(alias) = &filter_args.alias {
+        standards_filter = standards_filter.by_alias(alias);
+    }
+    if let Some(prefix) = &filter_args.prefix {
+        standards_filter = standards_filter.by_name_prefix(prefix);
+    }
+    if let Some(suffix) = &filter_args.suffix {
+        standards_filter = standards_filter.by_name_suffix(suffix);
+    }
+    if let Some(regex) = &filter_args.regex {
+        standards_filter = match standards_filter.by_name_regex(regex) {
+            Ok(filtered) => filtered,
+            Err(error) => crate::exit_with_error(&error),
+        };
+    }
+    if let Some(query) = &filter_args.query {
+        standards_filter = match standards_filter.query(query) {
+            Ok(filtered) => filtered,
+            Err(error) => crate::exit_with_error(&error),
+        };
+    }
     if let Some(search_str) = &filter_args.search {
-        standards_filter = standards_filter.search(search_str);
+        if filter_args.explain {
+            let results = standards_filter.search_ranked(search_str);
+            if results.is_empty() {
+                eprintln!("No standards found matching the criteria.");
+                process::exit(2);
+            }
+            if let Some(output) = output {
+                crate::print_serialized(&results, output);
+                return;
+            }
+            for result in results {
+                println!(
+                    "- {} ({:.1}: {})",
+                    result.standard.display_short(),
+                    result.score,
+                    result.explain()
+                );
+            }
+            return;
+        }
+        let paged = filter_args.offset > 0 || filter_args.limit.is_some();
+        standards_filter = if paged {
+            standards_filter.search_all(search_str)
+        } else {
+            standards_filter.search(search_str)
+        };
+    }
+
+    if let Some(sort) = filter_args.sort {
+        standards_filter = standards_filter.sort_by(sort, filter_args.descending);
     }
+    standards_filter =
+        standards_filter.paginate(filter_args.offset, filter_args.limit.unwrap_or(usize::MAX));
 
     let filtered_standards: Vec<_> = standards_filter.standards.into_iter().collect();
 
     if filtered_standards.is_empty() {
         eprintln!("No standards found matching the criteria.");
         process::exit(2);
+    } else if let Some(output) = output {
+        crate::print_serialized(&filtered_standards, output);
     } else {
         let format = filter_args.format;
-        println!("{}", format.format_standards(filtered_standards));
+        println!(
+            "{}",
+            format.format_standards(filtered_standards, library.cf_version.as_deref())
+        );
     }
 }
@@ -0,0 +1,174 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use standard_knowledge::{Edge, Relationship, StandardsLibrary};
+use std::process;
+
+use crate::OutputFormat;
+
+#[derive(Parser)]
+pub struct GraphArgs {
+    #[clap(subcommand)]
+    pub command: GraphCommands,
+}
+
+#[derive(Subcommand)]
+pub enum GraphCommands {
+    /// List standards linked to a standard, within a number of hops
+    Related {
+        /// Standard name or alias
+        standard_name: String,
+        /// How many links to follow
+        #[arg(long, default_value_t = 1)]
+        hops: usize,
+        /// Only follow one kind of link
+        #[arg(long)]
+        relationship: Option<Relationship>,
+    },
+    /// List standards that are usually deployed together
+    Siblings {
+        /// Standard name or alias to list the sibling group of, otherwise all groups are listed
+        standard_name: Option<String>,
+    },
+    /// Check for links that aren't returned, or that are to unknown standards
+    Check,
+    /// Export the graph, or the part around a standard
+    Export {
+        /// Standard name or alias to export the neighborhood of, otherwise the whole graph is exported
+        standard_name: Option<String>,
+        /// How many links to follow from the standard
+        #[arg(long, default_value_t = 1, requires = "standard_name")]
+        hops: usize,
+        /// Only include one kind of link
+        #[arg(long)]
+        relationship: Option<Relationship>,
+        /// Format to export in
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Dot)]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ExportFormat {
+    /// Graphviz DOT
+    Dot,
+    /// JSON nodes and edges
+    Json,
+}
+
+/// Problems found by `graph check`
+#[derive(Serialize)]
+struct GraphCheck<'a> {
+    missing_back_links: &'a [Edge],
+    unknown_links: &'a [&'a Edge],
+}
+
+pub fn execute(graph_args: &GraphArgs, library: &StandardsLibrary, output: Option<OutputFormat>) {
+    let graph = library.graph();
+
+    match &graph_args.command {
+        GraphCommands::Related {
+            standard_name,
+            hops,
+            relationship,
+        } => match graph.within(standard_name, *hops, *relationship) {
+            Ok(reached) => {
+                if reached.is_empty() {
+                    eprintln!("No standards linked to {standard_name}");
+                    process::exit(2);
+                }
+                if let Some(output) = output {
+                    crate::print_serialized(&reached, output);
+                    return;
+                }
+                for reached in reached {
+                    let unit = if reached.hops == 1 { "hop" } else { "hops" };
+                    println!("- {} ({} {unit})", reached.name, reached.hops);
+                }
+            }
+            Err(error) => crate::exit_with_error(&error),
+        },
+        GraphCommands::Siblings { standard_name } => {
+            let groups = match standard_name {
+                Some(standard_name) => match graph.sibling_group(standard_name) {
+                    Ok(group) => vec![group],
+                    Err(error) => crate::exit_with_error(&error),
+                },
+                None => graph.sibling_groups(),
+            };
+            if let Some(output) = output {
+                crate::print_serialized(&groups, output);
+                return;
+            }
+            let groups: Vec<String> = groups
+                .iter()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|name| format!("- {name}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect();
+            println!("{}", groups.join("\n\n"));
+        }
+        GraphCommands::Check => {
+            let missing = graph.missing_back_links();
+            let unknown = graph.unknown_links();
+
+            if let Some(output) = output {
+                crate::print_serialized(
+                    &GraphCheck {
+                        missing_back_links: &missing,
+                        unknown_links: &unknown,
+                    },
+                    output,
+                );
+                if !missing.is_empty() || !unknown.is_empty() {
+                    process::exit(2);
+                }
+                return;
+            }
+
+            if missing.is_empty() && unknown.is_empty() {
+                println!("All links are returned and to known standards");
+                return;
+            }
+            if !missing.is_empty() {
+                println!("Missing back-links ({}):", missing.len());
+                for edge in &missing {
+                    println!("+ {edge}");
+                }
+            }
+            if !unknown.is_empty() {
+                if !missing.is_empty() {
+                    println!();
+                }
+                println!("Links to unknown standards ({}):", unknown.len());
+                for edge in &unknown {
+                    println!("? {edge}");
+                }
+            }
+            process::exit(2);
+        }
+        GraphCommands::Export {
+            standard_name,
+            hops,
+            relationship,
+            format,
+        } => {
+            let graph = match standard_name {
+                Some(standard_name) => {
+                    match graph.neighborhood(standard_name, *hops, *relationship) {
+                        Ok(neighborhood) => neighborhood,
+                        Err(error) => crate::exit_with_error(&error),
+                    }
+                }
+                None => graph,
+            };
+            match format {
+                ExportFormat::Dot => println!("{}", graph.to_dot()),
+                ExportFormat::Json => println!("{}", graph.to_json()),
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use standard_knowledge::cf::convert_cf_xml_to_yaml;
+
+#[derive(Parser)]
+pub struct ImportCfArgs {
+    /// Path to a CF standard name table XML file
+    xml: PathBuf,
+
+    /// Where to write the converted YAML. Prints to stdout when not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+pub fn execute(import_args: &ImportCfArgs) {
+    let xml = match fs::read_to_string(&import_args.xml) {
+        Ok(xml) => xml,
+        Err(e) => {
+            eprintln!(
+                "Unable to read CF standard name table {}: {e}",
+                import_args.xml.display()
+            );
+            process::exit(1);
+        }
+    };
+
+    let yaml = match convert_cf_xml_to_yaml(&xml) {
+        Ok(yaml) => yaml,
+        Err(e) => {
+            eprintln!("Error converting CF standard name table: {e}");
+            process::exit(1);
+        }
+    };
+
+    if let Some(output) = &import_args.output {
+        if let Err(e) = fs::write(output, yaml) {
+            eprintln!("Unable to write {}: {e}", output.display());
+            process::exit(1);
+        }
+    } else {
+        print!("{yaml}");
+    }
+}
@@ -1,7 +1,10 @@
-use standard_knowledge::{Knowledge, StandardsLibrary, YamlKnowledge};
+use standard_knowledge::{Knowledge, KnowledgeRule, StandardsLibrary};
 use std::fs;
 use std::path::Path;
 
+/// File name (without extension) of knowledge rules for families of standards
+const RULES_FILENAME: &str = "_rules";
+
 /// Load knowledge from a file path (single file or directory)
 pub fn load_knowledge_from_path(
     library: &mut StandardsLibrary,
@@ -9,14 +12,19 @@ pub fn load_knowledge_from_path(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let path = path.as_ref();
 
-    if path.is_file() {
+    if path.is_file() && path.file_stem().is_some_and(|stem| stem == RULES_FILENAME) {
+        // Load rules file
+        let rules = load_rules_file(path)?;
+        library.apply_rules(rules);
+    } else if path.is_file() {
         // Load single file
         let knowledge = load_single_knowledge_file(path)?;
         library.apply_knowledge(knowledge);
     } else if path.is_dir() {
         // Load all YAML files from directory
-        let knowledge = load_knowledge_from_directory(path)?;
+        let (knowledge, rules) = load_knowledge_from_directory(path)?;
         library.apply_knowledge(knowledge);
+        library.apply_rules(rules);
     } else {
         return Err(format!("Path does not exist: {}", path.display()).into());
     }
@@ -48,7 +56,15 @@ pub fn load_knowledge_from_url(
         .and_then(|f| f.strip_suffix(".yaml").or_else(|| f.strip_suffix(".yml")))
         .unwrap_or("unknown_standard");
 
-    let knowledge = parse_knowledge(filename, contents);
+    if filename == RULES_FILENAME {
+        match KnowledgeRule::from_yaml(url, &contents) {
+            Err(e) => return Err(format!("Failed to parse rules from {url}: {e}").into()),
+            Ok(rules) => library.apply_rules(rules),
+        }
+        return Ok(());
+    }
+
+    let knowledge = Knowledge::from_yaml(filename, &contents);
 
     match knowledge {
         Err(e) => return Err(format!("Failed to parse knowledge from {url}: {e}").into()),
@@ -68,68 +84,23 @@ fn load_single_knowledge_file(path: &Path) -> Result<Vec<Knowledge>, String> {
     let contents = fs::read_to_string(path)
         .map_err(|e| format!("Unable to read knowledge file {}: {}", path.display(), e))?;
 
-    parse_knowledge(filename, contents)
+    Knowledge::from_yaml(filename, &contents).map_err(|e| e.to_string())
 }
 
-/// Parse knowledge from YAML contents
-fn parse_knowledge(filename: &str, contents: String) -> Result<Vec<Knowledge>, String> {
-    let partial_knowledge: Result<YamlKnowledge, serde_yaml_ng::Error> =
-        serde_yaml_ng::from_str(&contents);
-
-    if let Ok(partial_knowledge) = partial_knowledge {
-        let knowledge = Knowledge {
-            name: partial_knowledge
-                .name
-                .unwrap_or_else(|| filename.to_string()),
-            long_name: partial_knowledge.long_name,
-            ioos_category: partial_knowledge.ioos_category,
-            common_variable_names: partial_knowledge.common_variable_names.unwrap_or_default(),
-            related_standards: partial_knowledge.related_standards.unwrap_or_default(),
-            sibling_standards: partial_knowledge.sibling_standards.unwrap_or_default(),
-            extra_attrs: partial_knowledge.extra_attrs.unwrap_or_default(),
-            other_units: partial_knowledge.other_units.unwrap_or_default(),
-            comments: partial_knowledge.comments,
-            qc: partial_knowledge.qc,
-        };
-        return Ok(vec![knowledge]);
-    }
+/// Load a file of knowledge rules
+fn load_rules_file(path: &Path) -> Result<Vec<KnowledgeRule>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read rules file {}: {}", path.display(), e))?;
 
-    let knowledge: Result<Vec<YamlKnowledge>, serde_yaml_ng::Error> =
-        serde_yaml_ng::from_str(&contents);
-    match knowledge {
-        Ok(knowledge) => Ok(knowledge
-            .iter()
-            .map(|partial_knowledge| Knowledge {
-                name: partial_knowledge.name.clone().unwrap_or_default(),
-                long_name: partial_knowledge.long_name.clone(),
-                ioos_category: partial_knowledge.ioos_category.clone(),
-                common_variable_names: partial_knowledge
-                    .common_variable_names
-                    .clone()
-                    .unwrap_or_default(),
-                related_standards: partial_knowledge
-                    .related_standards
-                    .clone()
-                    .unwrap_or_default(),
-                sibling_standards: partial_knowledge
-                    .sibling_standards
-                    .clone()
-                    .unwrap_or_default(),
-                extra_attrs: partial_knowledge.extra_attrs.clone().unwrap_or_default(),
-                other_units: partial_knowledge.other_units.clone().unwrap_or_default(),
-                comments: partial_knowledge.comments.clone(),
-                qc: partial_knowledge.qc.clone(),
-            })
-            .collect()),
-        Err(e) => Err(format!("Failed to deserialize YAML from {e}")),
-    }
+    KnowledgeRule::from_yaml(&path.display().to_string(), &contents).map_err(|e| e.to_string())
 }
 
-/// Load all knowledge files from a directory
+/// Load all knowledge files, and any rules file, from a directory
 fn load_knowledge_from_directory(
     path: &Path,
-) -> Result<Vec<Knowledge>, Box<dyn std::error::Error>> {
+) -> Result<(Vec<Knowledge>, Vec<KnowledgeRule>), Box<dyn std::error::Error>> {
     let mut knowledge_list: Vec<Knowledge> = Vec::new();
+    let mut rules: Vec<KnowledgeRule> = Vec::new();
 
     for entry in fs::read_dir(path)? {
         let entry = entry?;
@@ -142,6 +113,15 @@ fn load_knowledge_from_directory(
                     if stem == "_cf_standards" {
                         continue;
                     }
+                    if stem == RULES_FILENAME {
+                        match load_rules_file(&file_path) {
+                            Ok(mut file_rules) => rules.append(&mut file_rules),
+                            Err(e) => {
+                                eprintln!("Warning: Failed to load {}: {}", file_path.display(), e)
+                            }
+                        }
+                        continue;
+                    }
                 }
 
                 match load_single_knowledge_file(&file_path) {
@@ -152,5 +132,5 @@ fn load_knowledge_from_directory(
         }
     }
 
-    Ok(knowledge_list)
+    Ok((knowledge_list, rules))
 }
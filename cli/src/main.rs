@@ -1,11 +1,21 @@
 use std::process;
 
 use clap::{Parser, Subcommand, ValueEnum};
-use standard_knowledge::StandardsLibrary;
+use serde::Serialize;
+use standard_knowledge::{Error, StandardsLibrary};
 
+pub mod check;
+pub mod diff;
+pub mod erddap;
 pub mod filter;
+pub mod graph;
+pub mod import_cf;
 pub mod knowledge_loader;
+pub mod name;
+pub mod profile;
 pub mod qc;
+pub mod rules;
+pub mod upgrade;
 
 #[derive(Parser)]
 struct Cli {
@@ -14,6 +24,15 @@ struct Cli {
     #[arg(short = 'k', long = "knowledge", value_name = "SOURCE")]
     knowledge_sources: Vec<String>,
 
+    /// CF standard name table to load instead of the built-in one. Accepts the official XML or the YAML format.
+    #[arg(long = "cf-table", value_name = "PATH")]
+    cf_table: Option<String>,
+
+    /// Print results as JSON or YAML instead of text, for other tools to read.
+    /// Commands that write a document, like erddap, upgrade, or import-cf, aren't affected.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    output: Option<OutputFormat>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -28,13 +47,48 @@ enum Commands {
         /// Format to display in
         #[arg(short, long, value_enum, default_value_t = GetFormat::Full)]
         format: GetFormat,
+
+        /// Variable name in the source data, for ERDDAP. Defaults to the standard name
+        #[arg(long, value_name = "NAME")]
+        source_name: Option<String>,
+
+        /// Variable name ERDDAP serves, defaults to the source name
+        #[arg(long, value_name = "NAME")]
+        destination_name: Option<String>,
     },
 
     /// Filter standards
-    Filter(filter::FilterArgs),
+    Filter(Box<filter::FilterArgs>),
 
     /// QARTOD test suites
     Qc(qc::QcArgs),
+
+    /// Generate an ERDDAP datasets.xml <dataset> for a set of source columns
+    Erddap(Box<erddap::ErddapArgs>),
+
+    /// Explore related and sibling links between standards
+    Graph(graph::GraphArgs),
+
+    /// Parse and compose standard names from CF grammar
+    Name(name::NameArgs),
+
+    /// List rules that give families of standards default knowledge
+    Rules(rules::RulesArgs),
+
+    /// Rewrite superseded standard name aliases to current canonical names
+    Upgrade(upgrade::UpgradeArgs),
+
+    /// Compare standards and knowledge against another CF table or knowledge set
+    Diff(diff::DiffArgs),
+
+    /// Check a variable's attributes against the IOOS Metadata Profile 1.2
+    Profile(profile::ProfileArgs),
+
+    /// Check the variables in a NetCDF-3 file or CDL header against the standards
+    Check(check::CheckArgs),
+
+    /// Convert a CF standard name table XML into the YAML format built into the library
+    ImportCf(import_cf::ImportCfArgs),
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -45,26 +99,81 @@ enum GetFormat {
     Full,
     /// Xarray attributes
     Xarray,
-    // /// ERDDAP datasets.xml <addAttributes>
-    // Erddap,
+    /// ERDDAP datasets.xml <dataVariable>
+    Erddap,
+    /// NetCDF CDL skeleton, for ncgen
+    Cdl,
 }
 
-fn main() {
-    let cli = Cli::parse();
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+}
+
+/// Print a result as JSON or YAML
+fn print_serialized<T: Serialize + ?Sized>(value: &T, output: OutputFormat) {
+    let serialized = match output {
+        OutputFormat::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        OutputFormat::Yaml => serde_yaml_ng::to_string(value).map_err(|e| e.to_string()),
+    };
+    match serialized {
+        Ok(serialized) => println!("{}", serialized.trim_end()),
+        Err(e) => {
+            eprintln!("Unable to serialize output: {e}");
+            process::exit(1);
+        }
+    }
+}
 
+/// Report an error, such as a standard that couldn't be found along with any suggestions, and exit
+fn exit_with_error(error: &Error) -> ! {
+    match error {
+        Error::UnknownStandard(unknown) => {
+            eprintln!("Didn't find a standard matching: {}", unknown.name);
+            if !unknown.suggestions.is_empty() {
+                eprintln!("\nDid you mean:");
+                for suggestion in &unknown.suggestions {
+                    eprintln!("  - {suggestion}");
+                }
+            }
+        }
+        error => eprintln!("{error}"),
+    }
+    process::exit(2)
+}
+
+fn load_builtin_knowledge(library: &mut StandardsLibrary) {
+    if let Err(e) = library.load_knowledge() {
+        eprintln!("Error loading built-in knowledge: {e}");
+        process::exit(1);
+    }
+}
+
+/// Load CF standards, knowledge, and test suites as requested by the command line
+fn load_library(cf_table: Option<&String>, knowledge_sources: &[String]) -> StandardsLibrary {
     let mut library = StandardsLibrary::default();
-    library.load_cf_standards();
+
+    if let Some(cf_table) = cf_table {
+        if let Err(e) = library.load_cf_standards_from_path(cf_table) {
+            eprintln!("Error loading CF standard name table '{cf_table}': {e}");
+            process::exit(1);
+        }
+    } else if let Err(e) = library.load_cf_standards() {
+        eprintln!("Error loading built-in CF standards: {e}");
+        process::exit(1);
+    }
 
     // Handle knowledge loading based on CLI arguments
-    if cli.knowledge_sources.is_empty() {
+    if knowledge_sources.is_empty() {
         // Default behavior: load built-in knowledge
-        library.load_knowledge();
+        load_builtin_knowledge(&mut library);
     } else {
         // Load knowledge from specified sources
-        for source in &cli.knowledge_sources {
+        for source in knowledge_sources {
             if source == "lib" {
                 // Load built-in knowledge
-                library.load_knowledge();
+                load_builtin_knowledge(&mut library);
             } else if source.starts_with("http://")
                 || source.starts_with("https://")
                 || source.starts_with("file://")
@@ -86,9 +195,44 @@ fn main() {
 
     library.load_test_suites();
 
+    library
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    // Converting a table doesn't need any standards or knowledge loaded
+    if let Commands::ImportCf(import_args) = &cli.command {
+        import_cf::execute(import_args);
+        return;
+    }
+
+    let library = load_library(cli.cf_table.as_ref(), &cli.knowledge_sources);
+
     match &cli.command {
-        Commands::Get { name, format } => {
-            if let Ok(standard) = library.get(name) {
+        Commands::Get {
+            name,
+            format,
+            source_name,
+            destination_name,
+        } => match library.resolve(name) {
+            Ok(resolved) => {
+                if let Some(alias) = &resolved.alias {
+                    let version = resolved
+                        .table_version
+                        .as_ref()
+                        .map(|version| format!(" as of CF table version {version}"))
+                        .unwrap_or_default();
+                    eprintln!(
+                        "Note: {alias} is a superseded alias{version}, use {} instead",
+                        resolved.standard.name
+                    );
+                }
+                let standard = resolved.standard;
+                if let Some(output) = cli.output {
+                    print_serialized(&standard, output);
+                    return;
+                }
                 match format {
                     GetFormat::Short => {
                         println!("{}", standard.display_short())
@@ -99,18 +243,66 @@ fn main() {
                     GetFormat::Xarray => {
                         println!("{}", standard.display_xarray_attrs());
                     }
+                    GetFormat::Erddap => {
+                        println!(
+                            "{}",
+                            standard.erddap_data_variable(
+                                source_name.as_deref(),
+                                destination_name.as_deref()
+                            )
+                        );
+                    }
+                    GetFormat::Cdl => {
+                        println!(
+                            "{}",
+                            standard_knowledge::cdl::dataset(
+                                &standard.name,
+                                [standard.as_ref()],
+                                library.cf_version.as_deref()
+                            )
+                        );
+                    }
                 }
-            } else {
-                eprintln!("Didn't find a standard matching: {name}");
-                process::exit(2)
             }
-        }
+            Err(error) => exit_with_error(&error),
+        },
         Commands::Filter(filter_args) => {
-            filter::execute(filter_args, &library);
+            filter::execute(filter_args, &library, cli.output);
         }
         Commands::Qc(qc_args) => {
-            qc::execute(qc_args, &library);
+            qc::execute(qc_args, &library, cli.output);
+        }
+        Commands::Erddap(erddap_args) => {
+            erddap::execute(erddap_args, &library);
+        }
+        Commands::Graph(graph_args) => {
+            graph::execute(graph_args, &library, cli.output);
+        }
+        Commands::Name(name_args) => {
+            name::execute(name_args, &library, cli.output);
+        }
+        Commands::Rules(rules_args) => {
+            rules::execute(rules_args, &library, cli.output);
+        }
+        Commands::Upgrade(upgrade_args) => {
+            upgrade::execute(upgrade_args, &library);
+        }
+        Commands::Diff(diff_args) => {
+            diff::execute(
+                diff_args,
+                &library,
+                cli.cf_table.as_ref(),
+                &cli.knowledge_sources,
+                cli.output,
+            );
+        }
+        Commands::Profile(profile_args) => {
+            profile::execute(profile_args, &library, cli.output);
+        }
+        Commands::Check(check_args) => {
+            check::execute(check_args, &library, cli.output);
         }
+        Commands::ImportCf(_) => unreachable!("Handled before loading the library"),
     }
 }
 
@@ -0,0 +1,104 @@
+use clap::{Parser, Subcommand};
+use standard_knowledge::{NameParts, StandardsLibrary, Transformation};
+use std::process;
+
+use crate::OutputFormat;
+
+#[derive(Parser)]
+pub struct NameArgs {
+    #[clap(subcommand)]
+    pub command: NameCommands,
+}
+
+#[derive(Subcommand)]
+pub enum NameCommands {
+    /// Break a standard name into its transformations, qualifiers, and base quantity
+    Parse {
+        /// Standard name
+        standard_name: String,
+    },
+    /// Build a standard name from its parts, and check if it is in the CF standard name table
+    Compose {
+        /// Base quantity, like sea_water_temperature
+        #[arg(long)]
+        base: String,
+        /// Transformation, outermost first, like tendency_of. Derivatives take what they are with respect to, like derivative_of:depth
+        #[arg(long = "transform", value_name = "TRANSFORMATION")]
+        transformations: Vec<Transformation>,
+        /// Surface written before the quantity, like surface or toa
+        #[arg(long)]
+        leading_surface: Option<String>,
+        /// Direction of a vector component, like northward or upward
+        #[arg(long)]
+        component: Option<String>,
+        /// Surface the quantity is at, like sea_floor
+        #[arg(long = "at", value_name = "SURFACE")]
+        surface: Option<String>,
+        /// Medium the quantity is in, like sea_water
+        #[arg(long = "in", value_name = "MEDIUM")]
+        medium: Option<String>,
+        /// Process causing the quantity, like advection
+        #[arg(long = "due-to", value_name = "PROCESS")]
+        process: Option<String>,
+        /// Condition assumed, like clear_sky
+        #[arg(long = "assuming", value_name = "CONDITION")]
+        condition: Option<String>,
+    },
+}
+
+pub fn execute(name_args: &NameArgs, library: &StandardsLibrary, output: Option<OutputFormat>) {
+    match &name_args.command {
+        NameCommands::Parse { standard_name } => match NameParts::parse(standard_name) {
+            Ok(parts) => match output {
+                Some(output) => crate::print_serialized(&parts, output),
+                None => println!("{parts}"),
+            },
+            Err(error) => crate::exit_with_error(&error),
+        },
+        NameCommands::Compose {
+            base,
+            transformations,
+            leading_surface,
+            component,
+            surface,
+            medium,
+            process,
+            condition,
+        } => {
+            let parts = NameParts {
+                transformations: transformations.clone(),
+                leading_surface: leading_surface.clone(),
+                component: component.clone(),
+                surface: surface.clone(),
+                medium: medium.clone(),
+                process: process.clone(),
+                condition: condition.clone(),
+                ..NameParts::new(base)
+            };
+
+            match library.check_name(&parts) {
+                Ok(composed) => {
+                    if let Some(output) = output {
+                        crate::print_serialized(&composed, output);
+                        if !composed.is_known() {
+                            process::exit(2);
+                        }
+                        return;
+                    }
+                    println!("{}", composed.name);
+                    if !composed.is_known() {
+                        eprintln!("Not in the CF standard name table");
+                        if !composed.alternatives.is_empty() {
+                            eprintln!("\nClosest known names:");
+                            for alternative in &composed.alternatives {
+                                eprintln!("  - {alternative}");
+                            }
+                        }
+                        process::exit(2);
+                    }
+                }
+                Err(error) => crate::exit_with_error(&error),
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use clap::Parser;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use standard_knowledge::{AttrValue, ProfileIssue, Requirement, StandardsLibrary};
+
+use crate::OutputFormat;
+
+#[derive(Parser)]
+pub struct ProfileArgs {
+    /// Standard name or alias for the variable's standard_name attribute
+    standard_name: Option<String>,
+
+    /// Variable attribute as name=value. Values of attributes like _FillValue or valid_min are read as numbers or [lists]
+    #[arg(short = 'a', long = "attr", value_name = "NAME=VALUE")]
+    attrs: Vec<String>,
+
+    /// JSON or YAML mapping of the variable's attributes
+    #[arg(long, value_name = "PATH")]
+    attrs_file: Option<PathBuf>,
+
+    /// Fill in missing attributes from knowledge for the standard before checking
+    #[arg(long)]
+    fill: bool,
+}
+
+#[derive(Serialize)]
+struct ProfileCheck<'a> {
+    attrs: &'a BTreeMap<String, AttrValue>,
+    filled: &'a [String],
+    issues: &'a [ProfileIssue],
+}
+
+fn load_attrs(profile_args: &ProfileArgs) -> BTreeMap<String, AttrValue> {
+    let mut attrs = BTreeMap::new();
+
+    if let Some(path) = &profile_args.attrs_file {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("Unable to read {}: {e}", path.display());
+                process::exit(1);
+            }
+        };
+        attrs = match serde_yaml_ng::from_str(&contents) {
+            Ok(attrs) => attrs,
+            Err(e) => {
+                eprintln!("Unable to parse attributes from {}: {e}", path.display());
+                process::exit(2);
+            }
+        };
+    }
+
+    if let Some(standard_name) = &profile_args.standard_name {
+        attrs.insert("standard_name".to_string(), standard_name.as_str().into());
+    }
+
+    for attr in &profile_args.attrs {
+        match attr.split_once('=') {
+            Some((name, value)) => {
+                attrs.insert(name.to_string(), AttrValue::parse_attr(name, value));
+            }
+            None => {
+                eprintln!("Invalid attribute format: {attr}");
+                process::exit(2);
+            }
+        }
+    }
+
+    attrs
+}
+
+pub fn execute(
+    profile_args: &ProfileArgs,
+    library: &StandardsLibrary,
+    output: Option<OutputFormat>,
+) {
+    let mut attrs = load_attrs(profile_args);
+
+    let filled = if profile_args.fill {
+        library.fill_ioos_profile_defaults(&mut attrs)
+    } else {
+        Vec::new()
+    };
+    let issues = library.check_ioos_profile(&attrs);
+
+    match output {
+        Some(output) => crate::print_serialized(
+            &ProfileCheck {
+                attrs: &attrs,
+                filled: &filled,
+                issues: &issues,
+            },
+            output,
+        ),
+        None => {
+            if !filled.is_empty() {
+                println!("Filled from knowledge: {}", filled.join(", "));
+            }
+            if issues.is_empty() {
+                println!("Attributes meet the IOOS Metadata Profile 1.2");
+            }
+            for issue in &issues {
+                println!("- {issue}");
+            }
+        }
+    }
+
+    if issues
+        .iter()
+        .any(|issue| issue.requirement == Requirement::Required)
+    {
+        process::exit(2);
+    }
+}
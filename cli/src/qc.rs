@@ -5,6 +5,8 @@ use std::process;
 
 use standard_knowledge::qartod::types::{ArgumentType, ArgumentValue};
 
+use crate::OutputFormat;
+
 #[derive(Parser)]
 pub struct QcArgs {
     #[clap(subcommand)]
@@ -34,15 +36,26 @@ pub enum QcCommands {
         /// Test configuration arguments
         args: Vec<String>,
     },
+    /// Generate CF ancillary flag variables for a standard's QARTOD tests
+    Flags {
+        /// Name of the standard
+        standard_name: String,
+        /// Name of the data variable, defaults to the standard name
+        #[arg(long)]
+        variable: Option<String>,
+    },
 }
 
-pub fn execute(qc_args: &QcArgs, library: &StandardsLibrary) {
+pub fn execute(qc_args: &QcArgs, library: &StandardsLibrary, output: Option<OutputFormat>) {
     match &qc_args.command {
-        QcCommands::List { standard_name } => {
-            if let Ok(standard) = library.get(standard_name.as_str()) {
+        QcCommands::List { standard_name } => match library.get(standard_name.as_str()) {
+            Ok(standard) => {
                 if standard.qartod.is_empty() {
                     eprintln!("No QARTOD test suites for standard: {standard_name}");
                     process::exit(2);
+                } else if let Some(output) = output {
+                    let infos: Vec<_> = standard.qartod.iter().map(|suite| suite.info()).collect();
+                    crate::print_serialized(&infos, output);
                 } else {
                     println!(
                         "QARTOD Test Suites for {}:\n{}",
@@ -55,39 +68,38 @@ pub fn execute(qc_args: &QcArgs, library: &StandardsLibrary) {
                             .join("\n")
                     );
                 }
-            } else {
-                eprintln!("Didn't find a standard matching: {standard_name}");
-                process::exit(2)
             }
-        }
+            Err(error) => crate::exit_with_error(&error),
+        },
         QcCommands::Get {
             standard_name,
             test_suite,
-        } => {
-            if let Ok(standard) = library.get(standard_name.as_str()) {
+        } => match library.get(standard_name.as_str()) {
+            Ok(standard) => {
                 if let Some(suite) = standard
                     .qartod
                     .iter()
                     .find(|s| s.info().slug == *test_suite)
                 {
-                    println!("{}", suite.info().details());
+                    match output {
+                        Some(output) => crate::print_serialized(&suite.info(), output),
+                        None => println!("{}", suite.info().details()),
+                    }
                 } else {
                     eprintln!(
                         "No QARTOD test suite named: {test_suite} for standard: {standard_name}"
                     );
                     process::exit(2);
                 }
-            } else {
-                eprintln!("Didn't find a standard matching: {standard_name}");
-                process::exit(2)
             }
-        }
+            Err(error) => crate::exit_with_error(&error),
+        },
         QcCommands::Config {
             standard_name,
             test_suite,
             args,
-        } => {
-            if let Ok(standard) = library.get(standard_name.as_str()) {
+        } => match library.get(standard_name.as_str()) {
+            Ok(standard) => {
                 if let Some(suite) = standard
                     .qartod
                     .iter()
@@ -108,7 +120,10 @@ pub fn execute(qc_args: &QcArgs, library: &StandardsLibrary) {
                                 .get(key)
                                 .map(|arg| arg.argument_type.clone())
                                 .unwrap_or(ArgumentType::String);
-                            let arg_value = arg_type.value_type(value);
+                            let arg_value = match arg_type.value_type(key, value) {
+                                Ok(arg_value) => arg_value,
+                                Err(error) => crate::exit_with_error(&error),
+                            };
 
                             arguments.insert(key.to_string(), arg_value);
                         } else {
@@ -122,6 +137,8 @@ pub fn execute(qc_args: &QcArgs, library: &StandardsLibrary) {
                     if let Err(error) = config {
                         eprintln!("Error generating configuration: {error}");
                         process::exit(2);
+                    } else if let Some(output) = output {
+                        crate::print_serialized(&config.unwrap(), output);
                     } else {
                         let yaml = serde_yaml_ng::to_string(&config.unwrap())
                             .expect("Failed to serialize configuration to YAML");
@@ -133,10 +150,35 @@ pub fn execute(qc_args: &QcArgs, library: &StandardsLibrary) {
                     );
                     process::exit(2);
                 }
-            } else {
-                eprintln!("Didn't find a standard matching: {standard_name}");
-                process::exit(2)
             }
-        }
+            Err(error) => crate::exit_with_error(&error),
+        },
+        QcCommands::Flags {
+            standard_name,
+            variable,
+        } => match library.get(standard_name.as_str()) {
+            Ok(standard) => match standard.qc_flags(variable.as_deref()) {
+                None => {
+                    eprintln!("No QARTOD test suites for standard: {standard_name}");
+                    process::exit(2);
+                }
+                Some(flags) => match output {
+                    Some(output) => crate::print_serialized(&flags, output),
+                    None => {
+                        println!(
+                            "{}:ancillary_variables: {}",
+                            flags.variable_name, flags.ancillary_variables
+                        );
+                        for flag in &flags.variables {
+                            println!("\n{}", flag.name);
+                            for (name, value) in &flag.attrs {
+                                println!("  {name}: {value}");
+                            }
+                        }
+                    }
+                },
+            },
+            Err(error) => crate::exit_with_error(&error),
+        },
     }
 }
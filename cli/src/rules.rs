@@ -0,0 +1,37 @@
+use clap::Parser;
+use standard_knowledge::StandardsLibrary;
+use std::process;
+
+use crate::OutputFormat;
+
+#[derive(Parser)]
+pub struct RulesArgs {
+    /// Standard name or alias to list the rules for, otherwise all rules are listed
+    pub standard_name: Option<String>,
+}
+
+pub fn execute(rules_args: &RulesArgs, library: &StandardsLibrary, output: Option<OutputFormat>) {
+    let rules = match &rules_args.standard_name {
+        Some(standard_name) => match library.rules_for(standard_name) {
+            Ok(rules) => rules,
+            Err(error) => crate::exit_with_error(&error),
+        },
+        None => library.rules().iter().collect(),
+    };
+
+    if rules.is_empty() {
+        match &rules_args.standard_name {
+            Some(standard_name) => eprintln!("No rules apply to {standard_name}"),
+            None => eprintln!("No rules loaded"),
+        }
+        process::exit(2);
+    }
+
+    if let Some(output) = output {
+        crate::print_serialized(&rules, output);
+        return;
+    }
+
+    let rules: Vec<String> = rules.iter().map(|rule| format!("- {rule}")).collect();
+    println!("{}", rules.join("\n"));
+}
@@ -0,0 +1,164 @@
+use clap::{Parser, ValueEnum};
+use serde_yaml_ng::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use standard_knowledge::upgrade::StandardNameUpgrade;
+use standard_knowledge::StandardsLibrary;
+
+#[derive(Parser)]
+pub struct UpgradeArgs {
+    /// CDL header, or a YAML/JSON file of variable attributes
+    path: PathBuf,
+
+    /// Type of file to upgrade. Guessed from the extension when not given.
+    #[arg(short, long, value_enum)]
+    format: Option<UpgradeFormat>,
+
+    /// Where to write the upgraded file. Prints to stdout when not given.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum UpgradeFormat {
+    /// CDL header, as from `ncdump -h`
+    Cdl,
+    /// YAML mapping of variable names to attributes, or a list of attributes
+    Yaml,
+    /// JSON object of variable names to attributes, or a list of attributes
+    Json,
+}
+
+impl UpgradeFormat {
+    fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("cdl") | Some("txt") => Self::Cdl,
+            Some("json") => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// Upgrade the `standard_name` in a set of attributes, if it is an alias
+fn upgrade_attrs(
+    library: &StandardsLibrary,
+    variable: Option<&str>,
+    attrs: &mut Value,
+    upgrades: &mut Vec<StandardNameUpgrade>,
+) {
+    let Some(standard_name) = attrs.get_mut("standard_name") else {
+        return;
+    };
+    let Some(name) = standard_name.as_str() else {
+        return;
+    };
+
+    if let Some(upgrade) = library.upgrade_standard_name(name) {
+        upgrades.push(StandardNameUpgrade {
+            variable: variable.map(str::to_string),
+            from: name.to_string(),
+            to: upgrade.standard.name.clone(),
+            table_version: upgrade.table_version,
+        });
+        *standard_name = Value::String(upgrade.standard.name.clone());
+    }
+}
+
+/// Upgrade standard names for a mapping of variables, or a list of attribute sets
+fn upgrade_variables(
+    library: &StandardsLibrary,
+    variables: &mut Value,
+    upgrades: &mut Vec<StandardNameUpgrade>,
+) -> Result<(), String> {
+    match variables {
+        Value::Mapping(mapping) => {
+            for (variable, attrs) in mapping.iter_mut() {
+                upgrade_attrs(library, variable.as_str(), attrs, upgrades);
+            }
+            Ok(())
+        }
+        Value::Sequence(sequence) => {
+            for attrs in sequence.iter_mut() {
+                upgrade_attrs(library, None, attrs, upgrades);
+            }
+            Ok(())
+        }
+        _ => Err(
+            "Expected a mapping of variable names to attributes, or a list of attributes"
+                .to_string(),
+        ),
+    }
+}
+
+fn upgrade(
+    library: &StandardsLibrary,
+    format: UpgradeFormat,
+    contents: &str,
+) -> Result<(String, Vec<StandardNameUpgrade>), String> {
+    if format == UpgradeFormat::Cdl {
+        return Ok(library.upgrade_cdl(contents));
+    }
+
+    let mut variables: Value = serde_yaml_ng::from_str(contents)
+        .map_err(|e| format!("Unable to parse variable attributes: {e}"))?;
+    let mut upgrades = Vec::new();
+    upgrade_variables(library, &mut variables, &mut upgrades)?;
+
+    let output = if format == UpgradeFormat::Json {
+        serde_json::to_string_pretty(&variables)
+            .map(|json| json + "\n")
+            .map_err(|e| format!("Unable to serialize variable attributes: {e}"))?
+    } else {
+        serde_yaml_ng::to_string(&variables)
+            .map_err(|e| format!("Unable to serialize variable attributes: {e}"))?
+    };
+
+    Ok((output, upgrades))
+}
+
+pub fn execute(upgrade_args: &UpgradeArgs, library: &StandardsLibrary) {
+    let contents = match fs::read_to_string(&upgrade_args.path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Unable to read {}: {e}", upgrade_args.path.display());
+            process::exit(1);
+        }
+    };
+
+    let format = upgrade_args
+        .format
+        .unwrap_or_else(|| UpgradeFormat::from_path(&upgrade_args.path));
+
+    let (output, upgrades) = match upgrade(library, format, &contents) {
+        Ok(upgraded) => upgraded,
+        Err(e) => {
+            eprintln!("Error upgrading {}: {e}", upgrade_args.path.display());
+            process::exit(1);
+        }
+    };
+
+    for upgrade in &upgrades {
+        let variable = upgrade
+            .variable
+            .as_ref()
+            .map(|variable| format!("{variable}: "))
+            .unwrap_or_default();
+        let version = upgrade
+            .table_version
+            .as_ref()
+            .map(|version| format!(" (alias as of CF table version {version})"))
+            .unwrap_or_default();
+        eprintln!("{variable}{} -> {}{version}", upgrade.from, upgrade.to);
+    }
+
+    if let Some(output_path) = &upgrade_args.output {
+        if let Err(e) = fs::write(output_path, output) {
+            eprintln!("Unable to write {}: {e}", output_path.display());
+            process::exit(1);
+        }
+    } else {
+        print!("{output}");
+    }
+}
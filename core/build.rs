@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::io::Write;
@@ -9,18 +9,27 @@ use flate2::{write::GzEncoder, Compression};
 
 include!("./src/qartod/config.rs");
 include!("./src/qartod/static_qc_include.rs");
+include!("./src/attr_value_include.rs");
 include!("./src/knowledge_include.rs");
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct CfYaml {
-    aliases: HashMap<String, String>,
-    standard_names: HashMap<String, CfStandard>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    aliases: BTreeMap<String, String>,
+    standard_names: BTreeMap<String, CfStandard>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct CfStandard {
     description: String,
     unit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grib: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    amip: Option<String>,
 }
 
 pub fn write_cf_standards_from_yaml() {
@@ -63,7 +72,14 @@ fn find_knowledge() -> Vec<PathBuf> {
         let file_path = entry.path();
 
         if let Some(ext) = file_path.extension() {
-            if ext == "yaml" && file_path.file_stem().unwrap() != "_cf_standards" {
+            // `_cf_standards` and `_rules` aren't knowledge for a single standard
+            if ext == "yaml"
+                && !file_path
+                    .file_stem()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with('_')
+            {
                 knowledge_files.push(file_path);
             }
         }
@@ -77,7 +93,7 @@ fn load_knowledge(path: &PathBuf) -> Knowledge {
     let contents = fs::read_to_string(path).expect("Unable to read knowledge");
 
     let partial_knowledge: YamlKnowledge = serde_yaml_ng::from_str(&contents)
-        .expect(format!("Failed to parse knowledge from {}", path.display()).as_str());
+        .unwrap_or_else(|e| panic!("Failed to parse knowledge from {}: {e}", path.display()));
     Knowledge {
         name: name.to_str().unwrap().to_string(),
         long_name: partial_knowledge.long_name,
@@ -0,0 +1,210 @@
+//! Typed attribute values for standards and knowledge
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+include!("./attr_value_include.rs");
+
+/// Attributes that have the same type as their variable,
+/// so are written as doubles for the double variables that CDL and ERDDAP output declare
+pub(crate) const VARIABLE_TYPED_ATTRS: &[&str] = &[
+    "_FillValue",
+    "actual_max",
+    "actual_min",
+    "actual_range",
+    "add_offset",
+    "flag_masks",
+    "flag_values",
+    "missing_value",
+    "scale_factor",
+    "valid_max",
+    "valid_min",
+    "valid_range",
+];
+
+impl AttrValue {
+    /// Value of an attribute given as text, like on a command line
+    ///
+    /// Attributes that have the variable's type, like `valid_min` or `flag_values`,
+    /// are read as a number or `[list]` of numbers if they look like one.
+    /// Other attributes, like `units=1`, stay text.
+    pub fn parse_attr(name: &str, value: &str) -> Self {
+        if VARIABLE_TYPED_ATTRS.contains(&name) {
+            if let Ok(parsed) = serde_yaml_ng::from_str::<AttrValue>(value) {
+                if parsed.is_numeric() {
+                    return parsed;
+                }
+            }
+        }
+        value.into()
+    }
+
+    /// The value if it is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Is the value a number or list of numbers
+    pub fn is_numeric(&self) -> bool {
+        !matches!(self, AttrValue::Text(_))
+    }
+
+    /// Numbers in the value, with integers widened to floats
+    ///
+    /// Strings don't have any numbers, even if they look like one.
+    pub fn numbers(&self) -> Vec<f64> {
+        match self {
+            AttrValue::Int(value) => vec![*value as f64],
+            AttrValue::Float(value) => vec![*value],
+            AttrValue::Ints(values) => values.iter().map(|value| *value as f64).collect(),
+            AttrValue::Floats(values) => values.clone(),
+            AttrValue::Text(_) => Vec::new(),
+        }
+    }
+}
+
+/// Strings as is, and lists of numbers separated by commas
+impl fmt::Display for AttrValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        match self {
+            AttrValue::Int(value) => write!(f, "{value}"),
+            AttrValue::Float(value) => write!(f, "{value}"),
+            AttrValue::Text(value) => write!(f, "{value}"),
+            AttrValue::Ints(values) => write!(f, "{}", join(values)),
+            AttrValue::Floats(values) => write!(f, "{}", join(values)),
+        }
+    }
+}
+
+impl From<&str> for AttrValue {
+    fn from(value: &str) -> Self {
+        AttrValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(value: String) -> Self {
+        AttrValue::Text(value)
+    }
+}
+
+impl From<i64> for AttrValue {
+    fn from(value: i64) -> Self {
+        AttrValue::Int(value)
+    }
+}
+
+impl From<f64> for AttrValue {
+    fn from(value: f64) -> Self {
+        AttrValue::Float(value)
+    }
+}
+
+impl From<Vec<i64>> for AttrValue {
+    fn from(values: Vec<i64>) -> Self {
+        AttrValue::Ints(values)
+    }
+}
+
+impl From<Vec<f64>> for AttrValue {
+    fn from(values: Vec<f64>) -> Self {
+        AttrValue::Floats(values)
+    }
+}
+
+impl PartialEq<str> for AttrValue {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for AttrValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn parses_numbers_for_variable_typed_attrs() {
+        assert_eq!(AttrValue::parse_attr("units", "1"), AttrValue::from("1"));
+        assert_eq!(
+            AttrValue::parse_attr("long_name", "2024"),
+            AttrValue::from("2024")
+        );
+        assert_eq!(
+            AttrValue::parse_attr("valid_min", "-2.5"),
+            AttrValue::Float(-2.5)
+        );
+        assert_eq!(
+            AttrValue::parse_attr("flag_values", "[1, 2, 4]"),
+            AttrValue::Ints(vec![1, 2, 4])
+        );
+        assert_eq!(
+            AttrValue::parse_attr("_FillValue", "missing"),
+            AttrValue::from("missing")
+        );
+    }
+
+    #[test]
+    fn keeps_yaml_types() {
+        let attrs: BTreeMap<String, AttrValue> = serde_yaml_ng::from_str(
+            "axis: Y\nvalid_min: -90\nscale_factor: 0.01\nflag_values: [1, 2, 3]\nvalid_range: [0, 42.5]\nquoted: \"5\"",
+        )
+        .unwrap();
+
+        assert_eq!(attrs["axis"], AttrValue::Text("Y".to_string()));
+        assert_eq!(attrs["valid_min"], AttrValue::Int(-90));
+        assert_eq!(attrs["scale_factor"], AttrValue::Float(0.01));
+        assert_eq!(attrs["flag_values"], AttrValue::Ints(vec![1, 2, 3]));
+        assert_eq!(attrs["valid_range"], AttrValue::Floats(vec![0.0, 42.5]));
+        assert_eq!(attrs["quoted"], "5");
+        assert_eq!(attrs["valid_range"].to_string(), "0, 42.5");
+        assert_eq!(
+            serde_json::to_string(&attrs["flag_values"]).unwrap(),
+            "[1,2,3]"
+        );
+    }
+
+    #[test]
+    fn names_non_finite_floats() {
+        let values = vec![
+            AttrValue::Float(f64::INFINITY),
+            AttrValue::Floats(vec![1.5, f64::NEG_INFINITY]),
+            AttrValue::Text("NaN".to_string()),
+            AttrValue::Text("Infinity".to_string()),
+        ];
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"float":"Infinity"},[1.5,"-Infinity"],"NaN","Infinity"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<AttrValue>>(&json).unwrap(),
+            values
+        );
+
+        let nan: AttrValue = serde_json::from_str(r#"{"float": "NaN"}"#).unwrap();
+        assert!(matches!(nan, AttrValue::Float(value) if value.is_nan()));
+        assert!(serde_json::from_str::<AttrValue>(r#"{"float": "NaN", "x": 1}"#).is_err());
+        let yaml: AttrValue = serde_yaml_ng::from_str(".nan").unwrap();
+        assert!(matches!(yaml, AttrValue::Float(value) if value.is_nan()));
+    }
+}
@@ -0,0 +1,116 @@
+/// Value of a NetCDF or Xarray attribute
+///
+/// Knowledge YAML can give attributes as strings, integers, floats, or lists of numbers,
+/// so that attributes like `valid_min`, `_FillValue`, and `flag_values` keep their type.
+///
+/// JSON has no numbers for floats that aren't finite, so they are serialized by name,
+/// like `{"float": "NaN"}`, or `"Infinity"` and `"-Infinity"` within a list of floats,
+/// so that text like `"NaN"` stays text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EncodedAttrValue", into = "EncodedAttrValue")]
+pub enum AttrValue {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Ints(Vec<i64>),
+    Floats(Vec<f64>),
+}
+
+/// Float that isn't finite, by the name JavaScript and CDL give it
+#[derive(Clone, Copy, Serialize, Deserialize)]
+enum NonFinite {
+    #[serde(rename = "NaN")]
+    NaN,
+    #[serde(rename = "Infinity")]
+    Infinity,
+    #[serde(rename = "-Infinity")]
+    NegInfinity,
+}
+
+/// Float as it is serialized, by name if it isn't finite
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(untagged)]
+enum EncodedFloat {
+    Number(f64),
+    NonFinite(NonFinite),
+}
+
+impl From<f64> for EncodedFloat {
+    fn from(value: f64) -> Self {
+        if value.is_nan() {
+            EncodedFloat::NonFinite(NonFinite::NaN)
+        } else if value == f64::INFINITY {
+            EncodedFloat::NonFinite(NonFinite::Infinity)
+        } else if value == f64::NEG_INFINITY {
+            EncodedFloat::NonFinite(NonFinite::NegInfinity)
+        } else {
+            EncodedFloat::Number(value)
+        }
+    }
+}
+
+impl From<EncodedFloat> for f64 {
+    fn from(value: EncodedFloat) -> Self {
+        match value {
+            EncodedFloat::Number(value) => value,
+            EncodedFloat::NonFinite(NonFinite::NaN) => f64::NAN,
+            EncodedFloat::NonFinite(NonFinite::Infinity) => f64::INFINITY,
+            EncodedFloat::NonFinite(NonFinite::NegInfinity) => f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// Float that isn't finite, tagged so it isn't mistaken for text
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaggedNonFinite {
+    float: NonFinite,
+}
+
+/// Attribute value as it is serialized
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum EncodedAttrValue {
+    Int(i64),
+    Float(f64),
+    NonFinite(TaggedNonFinite),
+    Text(String),
+    Ints(Vec<i64>),
+    Floats(Vec<EncodedFloat>),
+}
+
+impl From<AttrValue> for EncodedAttrValue {
+    fn from(value: AttrValue) -> Self {
+        match value {
+            AttrValue::Int(value) => EncodedAttrValue::Int(value),
+            AttrValue::Float(value) => match EncodedFloat::from(value) {
+                EncodedFloat::Number(value) => EncodedAttrValue::Float(value),
+                EncodedFloat::NonFinite(float) => {
+                    EncodedAttrValue::NonFinite(TaggedNonFinite { float })
+                }
+            },
+            AttrValue::Text(value) => EncodedAttrValue::Text(value),
+            AttrValue::Ints(values) => EncodedAttrValue::Ints(values),
+            AttrValue::Floats(values) => {
+                EncodedAttrValue::Floats(values.into_iter().map(EncodedFloat::from).collect())
+            }
+        }
+    }
+}
+
+impl From<EncodedAttrValue> for AttrValue {
+    fn from(value: EncodedAttrValue) -> Self {
+        match value {
+            EncodedAttrValue::Int(value) => AttrValue::Int(value),
+            EncodedAttrValue::Float(value) => AttrValue::Float(value),
+            EncodedAttrValue::NonFinite(TaggedNonFinite { float }) => {
+                AttrValue::Float(EncodedFloat::NonFinite(float).into())
+            }
+            EncodedAttrValue::Text(value) => AttrValue::Text(value),
+            EncodedAttrValue::Ints(values) => AttrValue::Ints(values),
+            EncodedAttrValue::Floats(values) => {
+                AttrValue::Floats(values.into_iter().map(f64::from).collect())
+            }
+        }
+    }
+}
@@ -0,0 +1,822 @@
+//! NetCDF CDL declarations for standards, as `ncgen` reads and `ncdump` writes,
+//! and parsing of CDL headers to check
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::attr_value::{AttrValue, VARIABLE_TYPED_ATTRS};
+use crate::erddap::EPOCH_TIME_UNITS;
+use crate::error::{Error, Result};
+use crate::header::{Dimension, HeaderVariable, NetcdfHeader};
+use crate::standard::Standard;
+
+/// Quote and escape a CDL string
+fn cdl_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Double constant, which CDL needs a decimal point or exponent for
+fn cdl_double(value: f64) -> String {
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    format!("{value:?}")
+}
+
+/// Attribute value, with numbers kept as ints or doubles,
+/// except attributes that take their variable's type, which are doubles
+fn cdl_value(name: &str, value: &AttrValue) -> String {
+    let join = |values: Vec<String>| values.join(", ");
+    match value {
+        AttrValue::Text(value) => cdl_string(value),
+        value if VARIABLE_TYPED_ATTRS.contains(&name) => {
+            join(value.numbers().into_iter().map(cdl_double).collect())
+        }
+        AttrValue::Int(value) => value.to_string(),
+        AttrValue::Ints(values) => join(values.iter().map(ToString::to_string).collect()),
+        AttrValue::Float(value) => cdl_double(*value),
+        AttrValue::Floats(values) => join(values.iter().copied().map(cdl_double).collect()),
+    }
+}
+
+/// Name that CDL can use without escaping
+fn cdl_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("var_{name}")
+    }
+}
+
+/// Declaration lines for a double variable and its attributes
+fn variable_lines(
+    standard: &Standard,
+    variable_name: &str,
+    dimensions: &[&str],
+    units: Option<&str>,
+) -> Vec<String> {
+    let dimensions = if dimensions.is_empty() {
+        String::new()
+    } else {
+        format!("({})", dimensions.join(", "))
+    };
+    let mut lines = vec![format!("\tdouble {variable_name}{dimensions} ;")];
+
+    let mut attrs = standard.xarray_attrs();
+    if let Some(units) = units {
+        attrs.insert("units", units.into());
+    }
+    for (name, value) in attrs {
+        lines.push(format!(
+            "\t\t{variable_name}:{name} = {} ;",
+            cdl_value(name, &value)
+        ));
+    }
+    lines
+}
+
+/// CDL declaration for a double variable with the standard's attributes
+///
+/// Attributes such as `valid_min` and `_FillValue` that take the variable's type
+/// are written as doubles.
+pub fn variable(standard: &Standard, variable_name: &str, dimensions: &[&str]) -> String {
+    variable_lines(standard, variable_name, dimensions, None).join("\n")
+}
+
+/// `netcdf` skeleton with a variable for each standard, along an unlimited time dimension
+///
+/// A `time` coordinate variable is added if `time` isn't one of the standards.
+pub fn dataset<'a>(
+    dataset_name: &str,
+    standards: impl IntoIterator<Item = &'a Standard>,
+    cf_version: Option<&str>,
+) -> String {
+    let standards: Vec<&Standard> = standards.into_iter().collect();
+    let default_time = Standard {
+        name: "time".to_string(),
+        long_name: Some("Time".to_string()),
+        extra_attrs: [("axis".to_string(), "T".into())].into(),
+        ..Default::default()
+    };
+    let time = standards
+        .iter()
+        .find(|standard| standard.name == "time")
+        .copied()
+        .unwrap_or(&default_time);
+
+    let mut lines = vec![
+        format!("netcdf {} {{", cdl_name(dataset_name)),
+        "dimensions:".to_string(),
+        "\ttime = UNLIMITED ; // (0 currently)".to_string(),
+        "variables:".to_string(),
+    ];
+    lines.extend(variable_lines(
+        time,
+        "time",
+        &["time"],
+        Some(EPOCH_TIME_UNITS),
+    ));
+    for standard in standards.iter().filter(|standard| standard.name != "time") {
+        lines.extend(variable_lines(
+            standard,
+            &cdl_name(&standard.name),
+            &["time"],
+            None,
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("// global attributes:".to_string());
+    lines.push(format!("\t\t:Conventions = {} ;", cdl_string("CF-1.6")));
+    if let Some(version) = cf_version {
+        lines.push(format!(
+            "\t\t:standard_name_vocabulary = {} ;",
+            cdl_string(&format!("CF Standard Name Table v{version}"))
+        ));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// CDL type names, including the `long` and `real` synonyms
+const CDL_TYPES: &[&str] = &[
+    "char", "byte", "ubyte", "short", "ushort", "int", "uint", "int64", "uint64", "float",
+    "double", "string", "long", "real",
+];
+
+/// Token of a CDL header, with the line it is on
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Name, keyword, or number, which are told apart by where they are
+    Word(String),
+    /// Quoted string, unescaped
+    Text(String),
+    /// One of `= ; : , ( ) { } *`
+    Punct(char),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '+' | '@') || !c.is_ascii()
+}
+
+/// Split CDL into tokens, dropping `//` comments
+fn tokenize(cdl: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut chars = cdl.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        None => {
+                            return Err(Error::parse(
+                                "CDL",
+                                format!("line {start}: unterminated string"),
+                            ))
+                        }
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(escaped) => text.push(escaped),
+                            None => {}
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                    }
+                }
+                tokens.push((Token::Text(text), start));
+            }
+            '=' | ';' | ':' | ',' | '(' | ')' | '{' | '}' | '*' => {
+                tokens.push((Token::Punct(c), line))
+            }
+            c if is_word_char(c) || c == '\\' => {
+                let mut word = String::new();
+                let mut next = Some(c);
+                while let Some(c) = next {
+                    if c == '\\' {
+                        chars.next().into_iter().for_each(|c| word.push(c));
+                    } else {
+                        word.push(c);
+                    }
+                    next = chars.next_if(|c| is_word_char(*c) || *c == '\\');
+                }
+                tokens.push((Token::Word(word), line));
+            }
+            c => {
+                return Err(Error::parse(
+                    "CDL",
+                    format!("line {line}: unexpected `{c}`"),
+                ))
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Number in an attribute value, without its CDL type suffix
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+fn parse_number(word: &str) -> Option<Number> {
+    let lower = word.to_lowercase();
+    let unsigned = lower.trim_start_matches(['-', '+']);
+    if let Some(special) = ["nan", "infinity"].into_iter().find(|special| {
+        unsigned
+            .strip_prefix(special)
+            .is_some_and(|rest| matches!(rest, "" | "f" | "d"))
+    }) {
+        let value = match special {
+            "nan" => f64::NAN,
+            _ if lower.starts_with('-') => f64::NEG_INFINITY,
+            _ => f64::INFINITY,
+        };
+        return Some(Number::Float(value));
+    }
+
+    for suffix in ["ull", "ll", "ul", "us", "ub", "u", "l", "s", "b", ""] {
+        let Some(digits) = lower.strip_suffix(suffix) else {
+            continue;
+        };
+        if let Ok(int) = digits.parse() {
+            return Some(Number::Int(int));
+        }
+        // Unsigned 64-bit values past `i64::MAX`, like ncdump's default uint64 fill
+        if let Ok(int) = digits.parse::<u64>() {
+            return Some(Number::Float(int as f64));
+        }
+    }
+
+    let float = lower.strip_suffix(['f', 'd']).unwrap_or(&lower);
+    float.parse().ok().map(Number::Float)
+}
+
+/// Parser over the tokens of a CDL header
+struct HeaderParser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+
+    /// Names of the types declared in a NetCDF-4 `types:` section
+    user_types: Vec<String>,
+}
+
+impl HeaderParser {
+    fn peek(&self, offset: usize) -> Option<&Token> {
+        self.tokens
+            .get(self.position + offset)
+            .map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error(&self, message: impl Display) -> Error {
+        Error::parse("CDL", format!("line {}: {message}", self.line()))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(token, _)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn word(&mut self, expected: &str) -> Result<String> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word),
+            _ => {
+                self.position -= 1;
+                Err(self.error(format!("expected {expected}")))
+            }
+        }
+    }
+
+    fn punct(&mut self, expected: char) -> Result<()> {
+        if self.peek(0) == Some(&Token::Punct(expected)) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{expected}`")))
+        }
+    }
+
+    /// Section keyword like `dimensions:`, which ends its line
+    fn section(&self) -> Option<&str> {
+        let (Token::Word(word), _) = self.tokens.get(self.position)? else {
+            return None;
+        };
+        let (colon, line) = self.tokens.get(self.position + 1)?;
+        let next_line = self.tokens.get(self.position + 2).map(|(_, line)| *line);
+        (*colon == Token::Punct(':') && next_line.is_none_or(|next| next > *line))
+            .then_some(word.as_str())
+    }
+
+    fn parse(mut self) -> Result<NetcdfHeader> {
+        let mut header = NetcdfHeader::default();
+
+        if self.word("`netcdf`")? != "netcdf" {
+            self.position -= 1;
+            return Err(self.error("expected `netcdf`"));
+        }
+        header.name = Some(self.word("dataset name")?);
+        self.punct('{')?;
+
+        let mut section = None;
+        loop {
+            match self.peek(0) {
+                None => return Err(self.error("expected `}` to end the header")),
+                Some(Token::Punct('}')) => break,
+                _ => {}
+            }
+            if let Some(keyword) = self.section() {
+                match keyword {
+                    "dimensions" | "variables" => section = Some(keyword.to_string()),
+                    "types" => {
+                        self.position += 2;
+                        self.skip_types()?;
+                        continue;
+                    }
+                    // Data and groups aren't part of the root group's header
+                    "data" | "group" => break,
+                    other => return Err(self.error(format!("unknown section `{other}`"))),
+                }
+                self.position += 2;
+                continue;
+            }
+
+            match section.as_deref() {
+                Some("dimensions") => self.dimensions(&mut header)?,
+                Some("variables") => self.variable_statement(&mut header)?,
+                _ => return Err(self.error("expected `dimensions:` or `variables:`")),
+            }
+        }
+
+        Ok(header)
+    }
+
+    /// Skip the type definitions of a NetCDF-4 `types:` section, keeping only their names
+    ///
+    /// A type's name is the last word before its `{` or `;`, as in
+    /// `ubyte enum cloud_t {Clear = 0, Cloudy = 1} ;` or `opaque(11) opaque_t ;`.
+    fn skip_types(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        let mut name = None;
+        loop {
+            if depth == 0 && self.section().is_some() {
+                return Ok(());
+            }
+            match self.next() {
+                None => {
+                    self.position -= 1;
+                    return Err(self.error("expected `}` to end the header"));
+                }
+                Some(Token::Punct('}')) if depth == 0 => {
+                    self.position -= 1;
+                    return Ok(());
+                }
+                Some(Token::Punct('{')) => {
+                    if depth == 0 {
+                        self.user_types.extend(name.take());
+                    }
+                    depth += 1;
+                }
+                Some(Token::Punct('}')) => depth -= 1,
+                Some(Token::Punct(';')) if depth == 0 => self.user_types.extend(name.take()),
+                Some(Token::Word(word)) if depth == 0 => name = Some(word),
+                _ => {}
+            }
+        }
+    }
+
+    /// `name = 10, other = UNLIMITED ;`
+    fn dimensions(&mut self, header: &mut NetcdfHeader) -> Result<()> {
+        loop {
+            let name = self.word("dimension name")?;
+            self.punct('=')?;
+            let length = self.word("dimension length")?;
+            let length = if length.eq_ignore_ascii_case("unlimited") {
+                None
+            } else {
+                Some(
+                    length
+                        .parse()
+                        .map_err(|_| self.error(format!("`{length}` is not a dimension length")))?,
+                )
+            };
+            header.dimensions.push(Dimension { name, length });
+
+            match self.next() {
+                Some(Token::Punct(',')) => {}
+                Some(Token::Punct(';')) => return Ok(()),
+                _ => {
+                    self.position -= 1;
+                    return Err(self.error("expected `,` or `;`"));
+                }
+            }
+        }
+    }
+
+    /// Variable declaration, or a variable or global attribute
+    fn variable_statement(&mut self, header: &mut NetcdfHeader) -> Result<()> {
+        let data_type = match self.peek(0) {
+            Some(Token::Word(word))
+                if CDL_TYPES.contains(&word.as_str()) || self.user_types.contains(word) =>
+            {
+                Some(word.clone())
+            }
+            _ => None,
+        };
+        let skip = usize::from(data_type.is_some());
+
+        let is_attribute = self.peek(skip) == Some(&Token::Punct(':'))
+            || self.peek(skip + 1) == Some(&Token::Punct(':'));
+        if is_attribute {
+            self.position += skip;
+            return self.attribute(header, data_type.as_deref());
+        }
+
+        let Some(data_type) = data_type else {
+            return Err(self.error("expected a variable type or an attribute"));
+        };
+        self.position += 1;
+        let data_type = match data_type.as_str() {
+            "long" => "int".to_string(),
+            "real" => "float".to_string(),
+            _ => data_type,
+        };
+
+        loop {
+            let name = self.word("variable name")?;
+            let mut dimensions = Vec::new();
+            if self.peek(0) == Some(&Token::Punct('(')) {
+                self.position += 1;
+                loop {
+                    dimensions.push(self.word("dimension name")?);
+                    match self.next() {
+                        Some(Token::Punct(',')) => {}
+                        Some(Token::Punct(')')) => break,
+                        _ => {
+                            self.position -= 1;
+                            return Err(self.error("expected `,` or `)`"));
+                        }
+                    }
+                }
+            }
+            header.variables.push(HeaderVariable {
+                name,
+                data_type: data_type.clone(),
+                dimensions,
+                attrs: BTreeMap::new(),
+            });
+
+            match self.next() {
+                Some(Token::Punct(',')) => {}
+                Some(Token::Punct(';')) => return Ok(()),
+                _ => {
+                    self.position -= 1;
+                    return Err(self.error("expected `,` or `;`"));
+                }
+            }
+        }
+    }
+
+    /// `variable:name = value, value ;` or `:name = value ;` for a global attribute
+    fn attribute(&mut self, header: &mut NetcdfHeader, data_type: Option<&str>) -> Result<()> {
+        let variable = match self.peek(0) {
+            Some(Token::Punct(':')) => None,
+            _ => {
+                let variable = self.word("variable name")?;
+                match header
+                    .variables
+                    .iter()
+                    .rposition(|declared| declared.name == variable)
+                {
+                    Some(index) => Some(index),
+                    None => {
+                        self.position -= 1;
+                        return Err(
+                            self.error(format!("attribute for undeclared variable `{variable}`"))
+                        );
+                    }
+                }
+            }
+        };
+        self.punct(':')?;
+        let name = self.word("attribute name")?;
+        self.punct('=')?;
+
+        let mut texts = Vec::new();
+        let mut numbers = Vec::new();
+        let mut constants = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token::Text(text)) => texts.push(text),
+                Some(Token::Word(word)) => match parse_number(&word) {
+                    Some(number) => numbers.push(number),
+                    // Enum constants of NetCDF-4 user types are bare names
+                    None => constants.push(word),
+                },
+                _ => {
+                    self.position -= 1;
+                    return Err(self.error("expected an attribute value"));
+                }
+            }
+            match self.next() {
+                Some(Token::Punct(',')) => {}
+                Some(Token::Punct(';')) => break,
+                _ => {
+                    self.position -= 1;
+                    return Err(self.error("expected `,` or `;`"));
+                }
+            }
+        }
+
+        let value = if constants.is_empty() {
+            attr_value(texts, numbers, data_type)
+        } else {
+            (texts.is_empty() && numbers.is_empty()).then(|| AttrValue::Text(constants.join(", ")))
+        }
+        .ok_or_else(|| self.error(format!("`{name}` mixes strings and numbers")))?;
+
+        let attrs = match variable {
+            None => &mut header.global_attrs,
+            Some(index) => &mut header.variables[index].attrs,
+        };
+        attrs.insert(name, value);
+        Ok(())
+    }
+}
+
+/// Attribute value from its strings or numbers, as floats if any are or the type is
+fn attr_value(
+    texts: Vec<String>,
+    numbers: Vec<Number>,
+    data_type: Option<&str>,
+) -> Option<AttrValue> {
+    match (texts.is_empty(), numbers.is_empty()) {
+        (false, true) => return Some(AttrValue::Text(texts.concat())),
+        (true, false) => {}
+        _ => return None,
+    }
+
+    let is_float = matches!(data_type, Some("float" | "double" | "real"))
+        || numbers
+            .iter()
+            .any(|number| matches!(number, Number::Float(_)));
+    if is_float {
+        let floats: Vec<f64> = numbers
+            .into_iter()
+            .map(|number| match number {
+                Number::Int(int) => int as f64,
+                Number::Float(float) => float,
+            })
+            .collect();
+        Some(match floats.as_slice() {
+            [float] => AttrValue::Float(*float),
+            _ => AttrValue::Floats(floats),
+        })
+    } else {
+        let ints: Vec<i64> = numbers
+            .into_iter()
+            .filter_map(|number| match number {
+                Number::Int(int) => Some(int),
+                Number::Float(_) => None,
+            })
+            .collect();
+        Some(match ints.as_slice() {
+            [int] => AttrValue::Int(*int),
+            _ => AttrValue::Ints(ints),
+        })
+    }
+}
+
+/// Parse the dimensions, variables, and attributes of a CDL header
+///
+/// Anything after `data:`, and any groups, are ignored. NetCDF-4 `types:` are skipped,
+/// but variables can still be declared with them.
+pub fn parse_header(cdl: &str) -> Result<NetcdfHeader> {
+    HeaderParser {
+        tokens: tokenize(cdl)?,
+        position: 0,
+        user_types: Vec::new(),
+    }
+    .parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn salinity() -> Standard {
+        Standard {
+            name: "sea_water_practical_salinity".to_string(),
+            unit: "1".to_string(),
+            long_name: Some("Salinity \"PSU\"".to_string()),
+            extra_attrs: [
+                ("valid_min".to_string(), AttrValue::Int(0)),
+                (
+                    "valid_range".to_string(),
+                    AttrValue::Floats(vec![0.0, 42.5]),
+                ),
+                ("valid_max".to_string(), "not a number".into()),
+                ("flag_meanings".to_string(), "1 2".into()),
+                ("_ChunkSizes".to_string(), AttrValue::Ints(vec![1024, 1])),
+            ]
+            .into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn types_numeric_attributes() {
+        assert_eq!(
+            variable(&salinity(), "sal", &["time"]),
+            r#"	double sal(time) ;
+		sal:_ChunkSizes = 1024, 1 ;
+		sal:flag_meanings = "1 2" ;
+		sal:long_name = "Salinity \"PSU\"" ;
+		sal:standard_name = "sea_water_practical_salinity" ;
+		sal:units = "1" ;
+		sal:valid_max = "not a number" ;
+		sal:valid_min = 0.0 ;
+		sal:valid_range = 0.0, 42.5 ;"#
+        );
+        assert_eq!(cdl_value("_FillValue", &AttrValue::Float(-1e30)), "-1e30");
+        assert_eq!(cdl_value("valid_max", &f64::INFINITY.into()), "Infinity");
+        assert_eq!(cdl_value("scale_factor", &"0.01".into()), "\"0.01\"");
+    }
+
+    #[test]
+    fn dataset_adds_time() {
+        let salinity = salinity();
+        let cdl = dataset("2nd buoy", [&salinity], Some("92"));
+
+        assert!(cdl.starts_with("netcdf var_2nd_buoy {\ndimensions:\n\ttime = UNLIMITED ;"));
+        assert!(cdl.contains("\tdouble time(time) ;\n\t\ttime:axis = \"T\" ;"));
+        assert!(cdl.contains("\t\ttime:units = \"seconds since 1970-01-01T00:00:00Z\" ;"));
+        assert!(cdl.contains("\tdouble sea_water_practical_salinity(time) ;"));
+        assert!(cdl.contains(":standard_name_vocabulary = \"CF Standard Name Table v92\" ;"));
+        assert!(cdl.ends_with("\n}"));
+    }
+
+    #[test]
+    fn parses_ncdump_header() {
+        let header = parse_header(
+            r#"netcdf buoy {
+dimensions:
+	time = UNLIMITED ; // (24 currently)
+	station = 1, name_strlen = 8 ;
+variables:
+	double time(time) ;
+		time:units = "seconds since 1970-01-01" ;
+	float temp(time, station), sal(time) ;
+		temp:_FillValue = -9999.f ;
+		temp:flag_values = 1b, 2b, 4b ;
+		temp:long_name = "Sea Water ", "Temperature" ;
+	int crs ;
+		crs:semi_major_axis = 6378137 ;
+	double sal:valid_range = 0, 42 ;
+
+// global attributes:
+		:title = "Buoy: \"A\"" ;
+data:
+ time = 0, 3600 ;
+}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(header.name.as_deref(), Some("buoy"));
+        assert_eq!(
+            header.dimensions,
+            vec![
+                Dimension {
+                    name: "time".to_string(),
+                    length: None
+                },
+                Dimension {
+                    name: "station".to_string(),
+                    length: Some(1)
+                },
+                Dimension {
+                    name: "name_strlen".to_string(),
+                    length: Some(8)
+                },
+            ]
+        );
+
+        let temp = header.variable("temp").unwrap();
+        assert_eq!(temp.data_type, "float");
+        assert_eq!(temp.dimensions, vec!["time", "station"]);
+        assert_eq!(temp.attrs["_FillValue"], AttrValue::Float(-9999.0));
+        assert_eq!(temp.attrs["flag_values"], AttrValue::Ints(vec![1, 2, 4]));
+        assert_eq!(temp.attr_str("long_name"), Some("Sea Water Temperature"));
+
+        let sal = header.variable("sal").unwrap();
+        assert_eq!(sal.attrs["valid_range"], AttrValue::Floats(vec![0.0, 42.0]));
+        assert_eq!(
+            header.variable("crs").unwrap().attrs["semi_major_axis"],
+            AttrValue::Int(6378137)
+        );
+        assert_eq!(header.global_attrs["title"], "Buoy: \"A\"");
+    }
+
+    #[test]
+    fn parses_netcdf4_header() {
+        let header = parse_header(
+            r#"netcdf clouds {
+types:
+  ubyte enum cloud_t {Clear = 0, Cumulonimbus = 1, Missing = 255} ;
+  compound obs_t {
+    int day ;
+    float elev ;
+  }; // obs_t
+  int(*) vlen_t ;
+dimensions:
+	time = 2 ;
+variables:
+	cloud_t primary_cloud(time) ;
+		cloud_t primary_cloud:_FillValue = Missing ;
+	uint64 count(time) ;
+		count:_FillValue = 18446744073709551614ULL ;
+		count:valid_max = 9223372036854775807LL ;
+	obs_t obs ;
+}
+"#,
+        )
+        .unwrap();
+
+        let cloud = header.variable("primary_cloud").unwrap();
+        assert_eq!(cloud.data_type, "cloud_t");
+        assert_eq!(cloud.attr_str("_FillValue"), Some("Missing"));
+
+        let count = header.variable("count").unwrap();
+        assert_eq!(
+            count.attrs["_FillValue"],
+            AttrValue::Float(18446744073709551614.0)
+        );
+        assert_eq!(count.attrs["valid_max"], AttrValue::Int(i64::MAX));
+        assert_eq!(header.variable("obs").unwrap().data_type, "obs_t");
+    }
+
+    #[test]
+    fn header_errors_have_lines() {
+        let error =
+            parse_header("netcdf x {\nvariables:\n\tfloat temp ;\n\tsal:units = \"1\" ;\n}")
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unable to parse CDL: line 4: attribute for undeclared variable `sal`"
+        );
+        assert!(parse_header("netcdf x {\ndimensions:\n\ttime = many ;\n}").is_err());
+        assert_eq!(
+            parse_header("netcdf x {\ndimensions:\n\ttime = 1 ;\n")
+                .unwrap_err()
+                .to_string(),
+            "Unable to parse CDL: line 3: expected `}` to end the header"
+        );
+    }
+}
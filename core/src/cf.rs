@@ -1,33 +1,119 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::io::Read;
+use std::path::Path;
 
 use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
 use crate::standard::Standard;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 struct CfYaml {
-    aliases: HashMap<String, String>,
-    standard_names: HashMap<String, CfStandard>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    aliases: BTreeMap<String, String>,
+    standard_names: BTreeMap<String, CfStandard>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct CfStandard {
     description: String,
     unit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grib: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    amip: Option<String>,
+}
+
+/// A CF standard name table, along with the version it was loaded from
+#[derive(Debug, Default, Clone)]
+pub struct CfTable {
+    /// Version number of the table
+    pub version_number: Option<String>,
+
+    /// When the table was last modified
+    pub last_modified: Option<String>,
+
+    /// Standards by name
+    pub standards: HashMap<String, Standard>,
 }
 
-fn load_cf_yaml() -> CfYaml {
+fn load_cf_yaml() -> Result<CfYaml> {
     let compressed_data = include_bytes!(concat!(env!("OUT_DIR"), "/cf_standards.yaml.gz"));
 
     // Decompress the data
     let mut decoder = GzDecoder::new(&compressed_data[..]);
     let mut yaml_data = String::new();
-    decoder.read_to_string(&mut yaml_data).unwrap();
+    decoder
+        .read_to_string(&mut yaml_data)
+        .map_err(|e| Error::parse("built-in CF standard name table", e))?;
 
     // Deserialize from YAML
-    serde_yaml_ng::from_str(&yaml_data).unwrap()
+    serde_yaml_ng::from_str(&yaml_data)
+        .map_err(|e| Error::parse("built-in CF standard name table", e))
+}
+
+/// Parse the official CF standard name table XML format
+fn parse_cf_xml(xml: &str) -> Result<CfYaml> {
+    let invalid = |message: String| Error::parse("CF standard name table XML", message);
+
+    let document = roxmltree::Document::parse(xml).map_err(|e| invalid(e.to_string()))?;
+
+    let root = document.root_element();
+    if !root.has_tag_name("standard_name_table") {
+        return Err(invalid(format!(
+            "Expected a <standard_name_table> root element, found <{}>",
+            root.tag_name().name()
+        )));
+    }
+
+    let child_text = |node: roxmltree::Node, tag: &str| -> Option<String> {
+        node.children()
+            .find(|child| child.has_tag_name(tag))
+            .map(|child| child.text().unwrap_or_default().trim().to_string())
+    };
+    // GRIB and AMIP codes are frequently present, but left empty
+    let child_code = |node: roxmltree::Node, tag: &str| -> Option<String> {
+        child_text(node, tag).filter(|code| !code.is_empty())
+    };
+
+    let mut cf = CfYaml {
+        version_number: child_text(root, "version_number"),
+        last_modified: child_text(root, "last_modified"),
+        ..CfYaml::default()
+    };
+
+    for node in root.children().filter(|node| node.is_element()) {
+        if node.has_tag_name("entry") {
+            let name = node
+                .attribute("id")
+                .ok_or_else(|| invalid("Found a CF standard name <entry> without an id".into()))?;
+
+            cf.standard_names.insert(
+                name.to_string(),
+                CfStandard {
+                    description: child_text(node, "description").unwrap_or_default(),
+                    unit: child_text(node, "canonical_units").unwrap_or_default(),
+                    grib: child_code(node, "grib"),
+                    amip: child_code(node, "amip"),
+                },
+            );
+        } else if node.has_tag_name("alias") {
+            let alias = node
+                .attribute("id")
+                .ok_or_else(|| invalid("Found a CF standard name <alias> without an id".into()))?;
+            let entry_id = child_text(node, "entry_id")
+                .ok_or_else(|| invalid(format!("CF alias `{alias}` is missing an <entry_id>")))?;
+
+            cf.aliases.insert(alias.to_string(), entry_id);
+        }
+    }
+
+    Ok(cf)
 }
 
 /// Returns a HashMap of standard names: vector of aliases
@@ -46,9 +132,8 @@ fn aliases_by_standard_name(cf_yaml: &CfYaml) -> HashMap<String, Vec<String>> {
     standards
 }
 
-/// Returns a HashMap of standard names to Standard
-pub fn cf_standards() -> HashMap<String, Standard> {
-    let cf_yaml = load_cf_yaml();
+/// Convert the intermediate CF representation into a table of standards
+fn cf_table(cf_yaml: CfYaml) -> CfTable {
     let alias_map = aliases_by_standard_name(&cf_yaml);
 
     let mut standards = HashMap::new();
@@ -64,21 +149,107 @@ pub fn cf_standards() -> HashMap<String, Standard> {
                 unit: cf_standard.unit.clone(),
                 description: cf_standard.description.clone(),
                 aliases: aliases.to_vec(),
+                grib: cf_standard.grib.clone(),
+                amip: cf_standard.amip.clone(),
                 ..Standard::default()
             },
         );
     }
 
-    standards
+    CfTable {
+        version_number: cf_yaml.version_number,
+        last_modified: cf_yaml.last_modified,
+        standards,
+    }
+}
+
+/// Returns the CF standard name table built into the library
+pub fn builtin_cf_table() -> Result<CfTable> {
+    load_cf_yaml().map(cf_table)
+}
+
+/// Returns a HashMap of standard names to Standard
+pub fn cf_standards() -> Result<HashMap<String, Standard>> {
+    builtin_cf_table().map(|table| table.standards)
+}
+
+/// Load a CF standard name table from the official XML format
+pub fn cf_table_from_xml(xml: &str) -> Result<CfTable> {
+    parse_cf_xml(xml).map(cf_table)
+}
+
+/// Load a CF standard name table from the YAML format used by `standards/_cf_standards.yaml`
+pub fn cf_table_from_yaml(yaml: &str) -> Result<CfTable> {
+    serde_yaml_ng::from_str(yaml)
+        .map(cf_table)
+        .map_err(|e| Error::parse("CF standard name table YAML", e))
+}
+
+/// Convert the official CF standard name table XML into the YAML format
+/// used by `standards/_cf_standards.yaml`
+///
+/// Keys are sorted so that regenerating the table produces a minimal diff.
+pub fn convert_cf_xml_to_yaml(xml: &str) -> Result<String> {
+    let cf = parse_cf_xml(xml)?;
+    serde_yaml_ng::to_string(&cf).map_err(|e| Error::parse("CF standard name table as YAML", e))
+}
+
+/// Load a CF standard name table from a local XML or YAML file
+///
+/// The format is picked by the file extension, falling back to
+/// sniffing the contents for an XML declaration or tag.
+pub fn cf_table_from_path(path: impl AsRef<Path>) -> Result<CfTable> {
+    let path = path.as_ref();
+    let contents =
+        fs::read_to_string(path).map_err(|e| Error::io(path.display().to_string(), e))?;
+
+    let is_xml = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("xml") => true,
+        Some("yaml") | Some("yml") => false,
+        _ => contents.trim_start().starts_with('<'),
+    };
+
+    if is_xml {
+        cf_table_from_xml(&contents)
+    } else {
+        cf_table_from_yaml(&contents)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    static CF_XML: &str = r#"<?xml version="1.0"?>
+<standard_name_table xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="cf-standard-name-table-1.1.xsd">
+   <version_number>42</version_number>
+   <last_modified>2017-01-24T11:19:49Z</last_modified>
+   <institution>Centre for Environmental Data Analysis</institution>
+   <contact>support@ceda.ac.uk</contact>
+
+   <entry id="air_pressure_at_mean_sea_level">
+      <canonical_units>Pa</canonical_units>
+      <grib>2</grib>
+      <amip>psl</amip>
+      <description>Air pressure at sea level is the quantity often abbreviated as MSLP or PMSL.</description>
+   </entry>
+
+   <entry id="sea_water_temperature">
+      <canonical_units>K</canonical_units>
+      <grib>80</grib>
+      <amip></amip>
+      <description/>
+   </entry>
+
+   <alias id="air_pressure_at_sea_level">
+      <entry_id>air_pressure_at_mean_sea_level</entry_id>
+   </alias>
+</standard_name_table>
+"#;
+
     #[test]
     fn load_cf_standards() {
-        let standards = cf_standards();
+        let standards = cf_standards().unwrap();
         let pressure = standards["air_pressure_at_mean_sea_level"].clone();
         assert_eq!(pressure.name, "air_pressure_at_mean_sea_level");
 
@@ -92,6 +263,80 @@ mod tests {
         )
     }
 
+    #[test]
+    fn load_cf_table_from_xml() {
+        let table = cf_table_from_xml(CF_XML).unwrap();
+
+        assert_eq!(table.version_number.as_deref(), Some("42"));
+        assert_eq!(table.last_modified.as_deref(), Some("2017-01-24T11:19:49Z"));
+        assert_eq!(table.standards.len(), 2);
+
+        let pressure = &table.standards["air_pressure_at_mean_sea_level"];
+        assert_eq!(pressure.unit, "Pa");
+        assert_eq!(pressure.aliases, vec!["air_pressure_at_sea_level"]);
+        assert!(pressure
+            .description
+            .starts_with("Air pressure at sea level"));
+
+        assert_eq!(pressure.grib.as_deref(), Some("2"));
+        assert_eq!(pressure.amip.as_deref(), Some("psl"));
+
+        let temperature = &table.standards["sea_water_temperature"];
+        assert_eq!(temperature.description, "");
+        assert_eq!(temperature.grib.as_deref(), Some("80"));
+        assert_eq!(temperature.amip, None);
+    }
+
+    #[test]
+    fn convert_cf_xml_round_trips_through_yaml() {
+        let yaml = convert_cf_xml_to_yaml(CF_XML).unwrap();
+
+        assert!(yaml.starts_with("version_number: '42'\nlast_modified: 2017-01-24T11:19:49Z\n"));
+        assert!(yaml.contains("grib: '80'"));
+        assert!(!yaml.contains("amip: ''"));
+
+        let table = cf_table_from_yaml(&yaml).unwrap();
+        let xml_table = cf_table_from_xml(CF_XML).unwrap();
+
+        assert_eq!(table.version_number, xml_table.version_number);
+        assert_eq!(table.last_modified, xml_table.last_modified);
+        assert_eq!(table.standards, xml_table.standards);
+    }
+
+    #[test]
+    fn load_cf_table_from_yaml() {
+        let yaml = r#"
+version_number: "42"
+aliases:
+  air_pressure_at_sea_level: air_pressure_at_mean_sea_level
+standard_names:
+  air_pressure_at_mean_sea_level:
+    description: Air pressure at sea level
+    unit: Pa
+"#;
+        let table = cf_table_from_yaml(yaml).unwrap();
+
+        assert_eq!(table.version_number.as_deref(), Some("42"));
+        assert_eq!(table.last_modified, None);
+        assert_eq!(
+            table.standards["air_pressure_at_mean_sea_level"].aliases,
+            vec!["air_pressure_at_sea_level"]
+        );
+    }
+
+    #[test]
+    fn reject_non_cf_xml() {
+        let error = cf_table_from_xml("<knowledge></knowledge>").unwrap_err();
+        assert_eq!(
+            error,
+            Error::Parse {
+                input: "CF standard name table XML".to_string(),
+                message: "Expected a <standard_name_table> root element, found <knowledge>"
+                    .to_string()
+            }
+        );
+    }
+
     #[test]
     fn test_compressed_loading() {
         // Load the compressed data directly to ensure compression is working
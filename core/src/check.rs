@@ -0,0 +1,331 @@
+//! Checks of the variables in a NetCDF header against the standards and their knowledge
+//!
+//! Each variable's `standard_name` is looked up, with suggestions for typos and
+//! the current name for superseded aliases, and its `units`, `long_name`, and
+//! `ioos_category` are compared to what is known about the standard.
+//! Variables without a `standard_name` get candidate standards from their variable name.
+
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::header::{HeaderVariable, NetcdfHeader};
+use crate::standard::Standard;
+use crate::standards_library::StandardsLibrary;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// Something about a variable that doesn't match the standards
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    /// The `standard_name` isn't a standard name or alias
+    UnknownStandardName {
+        standard_name: String,
+        suggestions: Vec<String>,
+    },
+    /// The `standard_name` is a superseded alias
+    AliasStandardName {
+        standard_name: String,
+        canonical: String,
+    },
+    /// There is no `standard_name`, with standards that commonly use the variable name
+    MissingStandardName { candidates: Vec<String> },
+    /// There are no `units`, but the standard has them
+    MissingUnits { expected: String },
+    /// The `units` are neither the canonical nor other known units for the standard
+    UnexpectedUnits {
+        units: String,
+        expected: Vec<String>,
+    },
+    /// The `long_name` differs from the knowledge for the standard
+    DifferentLongName { long_name: String, expected: String },
+    /// The `ioos_category` differs from the knowledge for the standard
+    DifferentIoosCategory {
+        ioos_category: String,
+        expected: String,
+    },
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Finding::UnknownStandardName { .. } => Severity::Error,
+            Finding::MissingStandardName { candidates } if candidates.is_empty() => Severity::Info,
+            Finding::AliasStandardName { .. }
+            | Finding::MissingStandardName { .. }
+            | Finding::MissingUnits { .. }
+            | Finding::UnexpectedUnits { .. }
+            | Finding::DifferentIoosCategory { .. } => Severity::Warning,
+            Finding::DifferentLongName { .. } => Severity::Info,
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::UnknownStandardName {
+                standard_name,
+                suggestions,
+            } => {
+                write!(f, "Unknown standard_name {standard_name}")?;
+                if !suggestions.is_empty() {
+                    write!(f, ". Did you mean: {}?", suggestions.join(", "))?;
+                }
+                Ok(())
+            }
+            Finding::AliasStandardName {
+                standard_name,
+                canonical,
+            } => write!(
+                f,
+                "standard_name {standard_name} is an alias, use {canonical}"
+            ),
+            Finding::MissingStandardName { candidates } if candidates.is_empty() => {
+                write!(f, "No standard_name")
+            }
+            Finding::MissingStandardName { candidates } => {
+                write!(f, "No standard_name, candidates: {}", candidates.join(", "))
+            }
+            Finding::MissingUnits { expected } => write!(f, "No units, expected {expected}"),
+            Finding::UnexpectedUnits { units, expected } => write!(
+                f,
+                "Units {units} are not known for the standard, expected {}",
+                expected.join(" or ")
+            ),
+            Finding::DifferentLongName {
+                long_name,
+                expected,
+            } => write!(f, "long_name {long_name:?} differs from {expected:?}"),
+            Finding::DifferentIoosCategory {
+                ioos_category,
+                expected,
+            } => write!(f, "ioos_category {ioos_category} differs from {expected}"),
+        }
+    }
+}
+
+/// Findings for a variable in a header
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariableCheck {
+    pub variable: String,
+
+    /// Current standard name of the variable, if its `standard_name` is known
+    pub standard_name: Option<String>,
+
+    /// Most serious first
+    pub findings: Vec<Finding>,
+}
+
+impl VariableCheck {
+    /// Whether any findings are errors
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity() == Severity::Error)
+    }
+}
+
+/// Whether units are the standard's canonical or other units
+///
+/// Time references such as `seconds since 1970-01-01` are accepted for standards in seconds.
+fn units_match(standard: &Standard, units: &str) -> bool {
+    let units = units.trim();
+    units == standard.unit
+        || standard.other_units.iter().any(|other| other == units)
+        || (standard.unit == "s" && units.contains(" since "))
+}
+
+fn check_knowledge(standard: &Standard, variable: &HeaderVariable, findings: &mut Vec<Finding>) {
+    match variable.attr_str("units") {
+        None if !standard.unit.is_empty() && !variable.attrs.contains_key("units") => {
+            findings.push(Finding::MissingUnits {
+                expected: standard.unit.clone(),
+            });
+        }
+        Some(units) if !standard.unit.is_empty() && !units_match(standard, units) => {
+            findings.push(Finding::UnexpectedUnits {
+                units: units.to_string(),
+                expected: std::iter::once(&standard.unit)
+                    .chain(&standard.other_units)
+                    .cloned()
+                    .collect(),
+            });
+        }
+        _ => {}
+    }
+
+    if let (Some(long_name), Some(expected)) = (variable.attr_str("long_name"), &standard.long_name)
+    {
+        if !long_name.trim().eq_ignore_ascii_case(expected) {
+            findings.push(Finding::DifferentLongName {
+                long_name: long_name.to_string(),
+                expected: expected.clone(),
+            });
+        }
+    }
+
+    // A category from a rule is only a guess for a family of standards
+    let curated_category = standard
+        .ioos_category
+        .as_ref()
+        .filter(|_| !standard.rule_defaults.ioos_category);
+    if let (Some(ioos_category), Some(expected)) =
+        (variable.attr_str("ioos_category"), curated_category)
+    {
+        if !ioos_category.trim().eq_ignore_ascii_case(expected) {
+            findings.push(Finding::DifferentIoosCategory {
+                ioos_category: ioos_category.to_string(),
+                expected: expected.clone(),
+            });
+        }
+    }
+}
+
+/// Standards that a variable without a `standard_name` may be, from its name
+fn candidates(library: &StandardsLibrary, variable_name: &str) -> Vec<String> {
+    let mut candidates: Vec<String> = library
+        .lookup(variable_name)
+        .map(|standard| standard.name.clone())
+        .into_iter()
+        .collect();
+    for standard in library.filter().by_variable_name(variable_name).standards {
+        if !candidates.contains(&standard.name) {
+            candidates.push(standard.name.clone());
+        }
+    }
+    candidates
+}
+
+/// Check a variable against the standards
+pub fn check_variable(library: &StandardsLibrary, variable: &HeaderVariable) -> VariableCheck {
+    let mut findings = Vec::new();
+    let mut standard_name = None;
+
+    match variable.attr_str("standard_name").map(str::trim) {
+        None => findings.push(Finding::MissingStandardName {
+            candidates: candidates(library, &variable.name),
+        }),
+        Some(name) => match library.resolve(name) {
+            Ok(resolved) => {
+                if resolved.is_alias() {
+                    findings.push(Finding::AliasStandardName {
+                        standard_name: name.to_string(),
+                        canonical: resolved.standard.name.clone(),
+                    });
+                }
+                check_knowledge(&resolved.standard, variable, &mut findings);
+                standard_name = Some(resolved.standard.name.clone());
+            }
+            Err(Error::UnknownStandard(unknown)) => {
+                findings.push(Finding::UnknownStandardName {
+                    standard_name: unknown.name,
+                    suggestions: unknown.suggestions,
+                });
+            }
+            Err(_) => findings.push(Finding::UnknownStandardName {
+                standard_name: name.to_string(),
+                suggestions: Vec::new(),
+            }),
+        },
+    }
+
+    findings.sort_by_key(Finding::severity);
+    VariableCheck {
+        variable: variable.name.clone(),
+        standard_name,
+        findings,
+    }
+}
+
+/// Check every variable in a header, in the order they are declared
+pub fn check_header(library: &StandardsLibrary, header: &NetcdfHeader) -> Vec<VariableCheck> {
+    header
+        .variables
+        .iter()
+        .map(|variable| check_variable(library, variable))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library
+    }
+
+    #[test]
+    fn checks_cdl_variables() {
+        let library = library();
+        let header = NetcdfHeader::from_cdl(
+            r#"netcdf buoy {
+dimensions:
+    time = UNLIMITED ;
+variables:
+    double time(time) ;
+        time:standard_name = "time" ;
+        time:units = "seconds since 1970-01-01T00:00:00Z" ;
+    float temp(time) ;
+        temp:standard_name = "sea_water_temprature" ;
+    float pres(time) ;
+        pres:standard_name = "air_pressure_at_sea_level" ;
+        pres:units = "furlongs" ;
+    float wind_speed(time) ;
+}
+"#,
+        )
+        .unwrap();
+
+        let checks = check_header(&library, &header);
+        assert_eq!(checks.len(), 4);
+
+        assert_eq!(checks[0].standard_name.as_deref(), Some("time"));
+        assert!(checks[0].findings.is_empty());
+
+        assert!(checks[1].has_errors());
+        let Finding::UnknownStandardName { suggestions, .. } = &checks[1].findings[0] else {
+            panic!("expected an unknown standard name");
+        };
+        assert_eq!(suggestions[0], "sea_water_temperature");
+
+        assert_eq!(
+            checks[2].findings[0],
+            Finding::AliasStandardName {
+                standard_name: "air_pressure_at_sea_level".to_string(),
+                canonical: "air_pressure_at_mean_sea_level".to_string(),
+            }
+        );
+        assert!(matches!(
+            &checks[2].findings[1],
+            Finding::UnexpectedUnits { units, .. } if units == "furlongs"
+        ));
+
+        let Finding::MissingStandardName { candidates } = &checks[3].findings[0] else {
+            panic!("expected a missing standard name");
+        };
+        assert!(candidates.contains(&"wind_speed".to_string()));
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::standard::Standard;
+use crate::standards_library::StandardsLibrary;
+
+/// A single difference in a field between two versions of a standard
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldChange {
+    /// A single valued field changed
+    Value {
+        field: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// Items were added to or removed from a list field
+    List {
+        field: String,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldChange::Value { field, old, new } => write!(
+                f,
+                "{field}: {} -> {}",
+                old.as_deref().filter(|v| !v.is_empty()).unwrap_or("(none)"),
+                new.as_deref().filter(|v| !v.is_empty()).unwrap_or("(none)")
+            ),
+            FieldChange::List {
+                field,
+                added,
+                removed,
+            } => {
+                let changes = added
+                    .iter()
+                    .map(|item| format!("+{item}"))
+                    .chain(removed.iter().map(|item| format!("-{item}")))
+                    .collect::<Vec<_>>();
+                write!(f, "{field}: {}", changes.join(", "))
+            }
+        }
+    }
+}
+
+/// Changes to a standard that exists in both libraries
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StandardDiff {
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two standards libraries
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LibraryDiff {
+    /// CF table version of the original library
+    pub old_cf_version: Option<String>,
+
+    /// CF table version of the library being compared against
+    pub new_cf_version: Option<String>,
+
+    /// Standards that only exist in the new library
+    pub added: Vec<String>,
+
+    /// Standards that only exist in the original library
+    pub removed: Vec<String>,
+
+    /// Standards that exist in both, but differ
+    pub changed: Vec<StandardDiff>,
+}
+
+impl LibraryDiff {
+    /// Are the libraries the same
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for LibraryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.old_cf_version != self.new_cf_version {
+            writeln!(
+                f,
+                "CF table version: {} -> {}",
+                self.old_cf_version.as_deref().unwrap_or("(unknown)"),
+                self.new_cf_version.as_deref().unwrap_or("(unknown)")
+            )?;
+        }
+
+        if self.is_empty() {
+            return write!(f, "No differences");
+        }
+
+        let mut sections = Vec::new();
+        if !self.added.is_empty() {
+            sections.push(format!(
+                "Added standards ({}):\n{}",
+                self.added.len(),
+                self.added
+                    .iter()
+                    .map(|name| format!("+ {name}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        if !self.removed.is_empty() {
+            sections.push(format!(
+                "Removed standards ({}):\n{}",
+                self.removed.len(),
+                self.removed
+                    .iter()
+                    .map(|name| format!("- {name}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        if !self.changed.is_empty() {
+            sections.push(format!(
+                "Changed standards ({}):\n{}",
+                self.changed.len(),
+                self.changed
+                    .iter()
+                    .map(|diff| {
+                        let changes = diff
+                            .changes
+                            .iter()
+                            .map(|change| format!("    {change}"))
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        format!("~ {}\n{changes}", diff.name)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+
+        write!(f, "{}", sections.join("\n\n"))
+    }
+}
+
+fn value_change<T: PartialEq + ToString + ?Sized>(
+    changes: &mut Vec<FieldChange>,
+    field: &str,
+    old: Option<&T>,
+    new: Option<&T>,
+) {
+    if old != new {
+        changes.push(FieldChange::Value {
+            field: field.to_string(),
+            old: old.map(ToString::to_string),
+            new: new.map(ToString::to_string),
+        });
+    }
+}
+
+fn list_change(changes: &mut Vec<FieldChange>, field: &str, old: &[String], new: &[String]) {
+    let old_items: BTreeSet<&String> = old.iter().collect();
+    let new_items: BTreeSet<&String> = new.iter().collect();
+
+    let added: Vec<String> = new_items
+        .difference(&old_items)
+        .map(|item| item.to_string())
+        .collect();
+    let removed: Vec<String> = old_items
+        .difference(&new_items)
+        .map(|item| item.to_string())
+        .collect();
+
+    if !added.is_empty() || !removed.is_empty() {
+        changes.push(FieldChange::List {
+            field: field.to_string(),
+            added,
+            removed,
+        });
+    }
+}
+
+/// Compare the fields of two versions of a standard
+pub fn diff_standards(old: &Standard, new: &Standard) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+
+    value_change(&mut changes, "unit", Some(&old.unit), Some(&new.unit));
+    value_change(
+        &mut changes,
+        "description",
+        Some(&old.description),
+        Some(&new.description),
+    );
+    list_change(&mut changes, "aliases", &old.aliases, &new.aliases);
+    value_change(&mut changes, "grib", old.grib.as_ref(), new.grib.as_ref());
+    value_change(&mut changes, "amip", old.amip.as_ref(), new.amip.as_ref());
+    value_change(
+        &mut changes,
+        "long_name",
+        old.long_name.as_ref(),
+        new.long_name.as_ref(),
+    );
+    value_change(
+        &mut changes,
+        "ioos_category",
+        old.ioos_category.as_ref(),
+        new.ioos_category.as_ref(),
+    );
+    list_change(
+        &mut changes,
+        "common_variable_names",
+        &old.common_variable_names,
+        &new.common_variable_names,
+    );
+    list_change(
+        &mut changes,
+        "related_standards",
+        &old.related_standards,
+        &new.related_standards,
+    );
+    list_change(
+        &mut changes,
+        "sibling_standards",
+        &old.sibling_standards,
+        &new.sibling_standards,
+    );
+
+    let attr_keys: BTreeSet<&String> = old
+        .extra_attrs
+        .keys()
+        .chain(new.extra_attrs.keys())
+        .collect();
+    for key in attr_keys {
+        value_change(
+            &mut changes,
+            &format!("extra_attrs.{key}"),
+            old.extra_attrs.get(key),
+            new.extra_attrs.get(key),
+        );
+    }
+
+    list_change(
+        &mut changes,
+        "other_units",
+        &old.other_units,
+        &new.other_units,
+    );
+    value_change(
+        &mut changes,
+        "comments",
+        old.comments.as_ref(),
+        new.comments.as_ref(),
+    );
+
+    // Test suites are compared by slug, then by the info they describe themselves with
+    let old_suites: BTreeMap<String, String> = old
+        .qartod
+        .iter()
+        .map(|suite| (suite.info().slug, suite.info().details()))
+        .collect();
+    let new_suites: BTreeMap<String, String> = new
+        .qartod
+        .iter()
+        .map(|suite| (suite.info().slug, suite.info().details()))
+        .collect();
+    list_change(
+        &mut changes,
+        "qartod",
+        &old_suites.keys().cloned().collect::<Vec<_>>(),
+        &new_suites.keys().cloned().collect::<Vec<_>>(),
+    );
+    for (slug, old_details) in &old_suites {
+        if let Some(new_details) = new_suites.get(slug) {
+            value_change(
+                &mut changes,
+                &format!("qartod.{slug}"),
+                Some(old_details),
+                Some(new_details),
+            );
+        }
+    }
+
+    changes
+}
+
+/// Compare all the standards in two libraries
+pub fn diff_libraries(old: &StandardsLibrary, new: &StandardsLibrary) -> LibraryDiff {
+    let mut added: Vec<String> = new
+        .standards()
+        .keys()
+        .filter(|name| !old.standards().contains_key(*name))
+        .cloned()
+        .collect();
+    added.sort();
+
+    let mut removed: Vec<String> = old
+        .standards()
+        .keys()
+        .filter(|name| !new.standards().contains_key(*name))
+        .cloned()
+        .collect();
+    removed.sort();
+
+    let mut changed: Vec<StandardDiff> = old
+        .standards()
+        .iter()
+        .filter_map(|(name, old_standard)| {
+            let new_standard = new.standards().get(name)?;
+            if old_standard == new_standard {
+                return None;
+            }
+            let changes = diff_standards(old_standard, new_standard);
+            if changes.is_empty() {
+                return None;
+            }
+            Some(StandardDiff {
+                name: name.clone(),
+                changes,
+            })
+        })
+        .collect();
+    changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    LibraryDiff {
+        old_cf_version: old.cf_version.clone(),
+        new_cf_version: new.cf_version.clone(),
+        added,
+        removed,
+        changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Knowledge;
+
+    static OLD_XML: &str = r#"<?xml version="1.0"?>
+<standard_name_table>
+   <version_number>1</version_number>
+   <entry id="air_pressure_at_sea_level">
+      <canonical_units>Pa</canonical_units>
+      <description>Air pressure at sea level.</description>
+   </entry>
+   <entry id="sea_water_temperature">
+      <canonical_units>K</canonical_units>
+      <description>Sea water temperature.</description>
+   </entry>
+</standard_name_table>
+"#;
+
+    static NEW_XML: &str = r#"<?xml version="1.0"?>
+<standard_name_table>
+   <version_number>2</version_number>
+   <entry id="air_pressure_at_mean_sea_level">
+      <canonical_units>Pa</canonical_units>
+      <description>Air pressure at sea level.</description>
+   </entry>
+   <entry id="sea_water_temperature">
+      <canonical_units>degree_C</canonical_units>
+      <description>Sea water temperature.</description>
+   </entry>
+   <alias id="air_pressure_at_sea_level">
+      <entry_id>air_pressure_at_mean_sea_level</entry_id>
+   </alias>
+</standard_name_table>
+"#;
+
+    fn library(xml: &str) -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards_from_xml(xml).unwrap();
+        library
+    }
+
+    #[test]
+    fn diff_cf_table_versions() {
+        let diff = diff_libraries(&library(OLD_XML), &library(NEW_XML));
+
+        assert_eq!(diff.old_cf_version.as_deref(), Some("1"));
+        assert_eq!(diff.new_cf_version.as_deref(), Some("2"));
+        assert_eq!(diff.added, vec!["air_pressure_at_mean_sea_level"]);
+        assert_eq!(diff.removed, vec!["air_pressure_at_sea_level"]);
+        assert_eq!(
+            diff.changed,
+            vec![StandardDiff {
+                name: "sea_water_temperature".to_string(),
+                changes: vec![FieldChange::Value {
+                    field: "unit".to_string(),
+                    old: Some("K".to_string()),
+                    new: Some("degree_C".to_string()),
+                }],
+            }]
+        );
+
+        assert_eq!(
+            diff.to_string(),
+            "CF table version: 1 -> 2
+Added standards (1):
++ air_pressure_at_mean_sea_level
+
+Removed standards (1):
+- air_pressure_at_sea_level
+
+Changed standards (1):
+~ sea_water_temperature
+    unit: K -> degree_C"
+        );
+    }
+
+    #[test]
+    fn diff_knowledge() {
+        let old = library(NEW_XML);
+        let mut new = old.clone();
+        new.apply_knowledge(vec![Knowledge {
+            name: "sea_water_temperature".to_string(),
+            long_name: Some("Sea Water Temperature".to_string()),
+            common_variable_names: vec!["temp".to_string(), "sst".to_string()],
+            extra_attrs: BTreeMap::from([(
+                "coverage_content_type".to_string(),
+                "physicalMeasurement".into(),
+            )]),
+            ..Default::default()
+        }]);
+
+        let diff = old.diff(&new);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![
+                FieldChange::Value {
+                    field: "long_name".to_string(),
+                    old: None,
+                    new: Some("Sea Water Temperature".to_string()),
+                },
+                FieldChange::List {
+                    field: "common_variable_names".to_string(),
+                    added: vec!["sst".to_string(), "temp".to_string()],
+                    removed: Vec::new(),
+                },
+                FieldChange::Value {
+                    field: "extra_attrs.coverage_content_type".to_string(),
+                    old: None,
+                    new: Some("physicalMeasurement".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_libraries_have_no_diff() {
+        let diff = library(OLD_XML).diff(&library(OLD_XML));
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No differences");
+    }
+}
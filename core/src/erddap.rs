@@ -0,0 +1,827 @@
+//! ERDDAP `datasets.xml` configuration for standards
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::attr_value::{AttrValue, VARIABLE_TYPED_ATTRS};
+use crate::error::{Error, Result};
+use crate::qartod::flags::QC_AGG_SUFFIX;
+use crate::standard::Standard;
+use crate::standards_library::StandardsLibrary;
+
+/// Indentation ERDDAP uses for each level of `datasets.xml`
+const INDENT: &str = "    ";
+
+/// ERDDAP's placeholder for metadata that needs to be filled in
+const TO_FILL_IN: &str = "???";
+
+/// Units ERDDAP expects for the standards it treats as axes
+const AXIS_UNITS: &[(&str, &str)] = &[
+    ("latitude", "degrees_north"),
+    ("longitude", "degrees_east"),
+    ("depth", "m"),
+    ("altitude", "m"),
+];
+
+/// Time units for ISO 8601 strings in text files
+const ISO_TIME_UNITS: &str = "yyyy-MM-dd'T'HH:mm:ssZ";
+
+/// Time units for numeric times in NetCDF files
+pub(crate) const EPOCH_TIME_UNITS: &str = "seconds since 1970-01-01T00:00:00Z";
+
+/// Escape text for an XML element or attribute value
+pub(crate) fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Attribute in an `<addAttributes>` block
+struct Att {
+    name: String,
+    value: String,
+    /// ERDDAP type for non-string values, like `byteList`
+    att_type: Option<&'static str>,
+    /// Comment written before the attribute
+    comment: Option<String>,
+}
+
+impl Att {
+    fn new(name: &str, value: &str) -> Self {
+        Att {
+            name: name.to_string(),
+            value: value.to_string(),
+            att_type: None,
+            comment: None,
+        }
+    }
+
+    /// Attribute with an ERDDAP type for numbers
+    ///
+    /// Attributes that take their variable's type are doubles,
+    /// and lists are separated by spaces.
+    fn typed(name: &str, value: &AttrValue) -> Self {
+        let join = |values: Vec<String>| values.join(" ");
+        let (value, att_type) = match value {
+            AttrValue::Text(value) => return Att::new(name, value),
+            value if VARIABLE_TYPED_ATTRS.contains(&name) => {
+                let numbers = value.numbers();
+                let att_type = if matches!(value, AttrValue::Ints(_) | AttrValue::Floats(_)) {
+                    "doubleList"
+                } else {
+                    "double"
+                };
+                (
+                    join(numbers.iter().map(ToString::to_string).collect()),
+                    att_type,
+                )
+            }
+            AttrValue::Int(value) => (value.to_string(), "int"),
+            AttrValue::Float(value) => (value.to_string(), "double"),
+            AttrValue::Ints(values) => (
+                join(values.iter().map(ToString::to_string).collect()),
+                "intList",
+            ),
+            AttrValue::Floats(values) => (
+                join(values.iter().map(ToString::to_string).collect()),
+                "doubleList",
+            ),
+        };
+        Att {
+            att_type: Some(att_type),
+            ..Att::new(name, &value)
+        }
+    }
+
+    fn line(&self) -> String {
+        let att_type = self
+            .att_type
+            .map(|att_type| format!(" type=\"{att_type}\""))
+            .unwrap_or_default();
+        format!(
+            "<att name=\"{}\"{att_type}>{}</att>",
+            xml_escape(&self.name),
+            xml_escape(&self.value)
+        )
+    }
+}
+
+/// `<dataVariable>` block being built
+struct DataVariable {
+    source_name: String,
+    destination_name: String,
+    data_type: Option<&'static str>,
+    /// Attributes, sorted by name
+    attrs: Vec<Att>,
+}
+
+impl DataVariable {
+    /// Variable with the standard's Xarray attributes
+    ///
+    /// ERDDAP requires an `ioos_category`, so when knowledge doesn't have one,
+    /// the inferred category is used, with a comment noting that it was inferred.
+    fn for_standard(standard: &Standard, source_name: &str, destination_name: &str) -> Self {
+        let mut variable = DataVariable {
+            source_name: source_name.to_string(),
+            destination_name: destination_name.to_string(),
+            data_type: None,
+            attrs: standard
+                .xarray_attrs()
+                .into_iter()
+                .map(|(name, value)| Att::typed(name, &value))
+                .collect(),
+        };
+
+        if !variable.attrs.iter().any(|att| att.name == "ioos_category") {
+            let inferred = standard.infer_ioos_category();
+            variable.set(Att {
+                comment: Some(format!(
+                    "ioos_category inferred from the standard name and unit, {:.0}% confidence",
+                    inferred.confidence * 100.0
+                )),
+                ..Att::new("ioos_category", &inferred.category)
+            });
+        }
+
+        variable
+    }
+
+    /// Variable for a column that no standard was found for
+    fn unresolved(source_name: &str, destination_name: &str) -> Self {
+        DataVariable {
+            source_name: source_name.to_string(),
+            destination_name: destination_name.to_string(),
+            data_type: Some("String"),
+            attrs: vec![Att {
+                comment: Some(format!("No standard found for `{source_name}`")),
+                ..Att::new("ioos_category", "Unknown")
+            }],
+        }
+    }
+
+    /// Add or replace an attribute
+    fn set(&mut self, att: Att) {
+        match self
+            .attrs
+            .binary_search_by(|existing| existing.name.as_str().cmp(att.name.as_str()))
+        {
+            Ok(index) => self.attrs[index] = att,
+            Err(index) => self.attrs.insert(index, att),
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            "<dataVariable>".to_string(),
+            format!(
+                "{INDENT}<sourceName>{}</sourceName>",
+                xml_escape(&self.source_name)
+            ),
+            format!(
+                "{INDENT}<destinationName>{}</destinationName>",
+                xml_escape(&self.destination_name)
+            ),
+        ];
+        if let Some(data_type) = self.data_type {
+            lines.push(format!("{INDENT}<dataType>{data_type}</dataType>"));
+        }
+
+        lines.push(format!("{INDENT}<addAttributes>"));
+        for att in &self.attrs {
+            if let Some(comment) = &att.comment {
+                lines.push(format!("{INDENT}{INDENT}<!-- {comment} -->"));
+            }
+            lines.push(format!("{INDENT}{INDENT}{}", att.line()));
+        }
+        lines.push(format!("{INDENT}</addAttributes>"));
+        lines.push("</dataVariable>".to_string());
+        lines
+    }
+}
+
+/// `<dataVariable>` block for a variable with a standard
+///
+/// The attributes are the standard's Xarray attributes.
+/// ERDDAP requires an `ioos_category`, so when knowledge doesn't have one,
+/// the inferred category is used, with a comment noting that it was inferred.
+pub fn data_variable(standard: &Standard, source_name: &str, destination_name: &str) -> String {
+    DataVariable::for_standard(standard, source_name, destination_name)
+        .lines()
+        .join("\n")
+}
+
+/// Kind of files an ERDDAP dataset reads
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErddapDatasetType {
+    /// CSV or other delimited text files
+    #[default]
+    AsciiFiles,
+    /// NetCDF files
+    NcFiles,
+}
+
+impl ErddapDatasetType {
+    fn default_file_name_regex(&self) -> &'static str {
+        match self {
+            ErddapDatasetType::AsciiFiles => ".*\\.csv",
+            ErddapDatasetType::NcFiles => ".*\\.nc",
+        }
+    }
+}
+
+impl FromStr for ErddapDatasetType {
+    type Err = Error;
+
+    fn from_str(dataset_type: &str) -> Result<Self> {
+        match dataset_type.to_lowercase().as_str() {
+            "ascii" | "asciifiles" | "eddtablefromasciifiles" => Ok(ErddapDatasetType::AsciiFiles),
+            "nc" | "ncfiles" | "eddtablefromncfiles" => Ok(ErddapDatasetType::NcFiles),
+            _ => Err(Error::InvalidArgument {
+                argument: "dataset_type".to_string(),
+                message: format!("`{dataset_type}` isn't a dataset type, expected ascii or nc"),
+            }),
+        }
+    }
+}
+
+impl Display for ErddapDatasetType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErddapDatasetType::AsciiFiles => write!(f, "EDDTableFromAsciiFiles"),
+            ErddapDatasetType::NcFiles => write!(f, "EDDTableFromNcFiles"),
+        }
+    }
+}
+
+/// Dataset level metadata, used for the `<dataset>` settings and ACDD global attributes
+///
+/// Anything left out that ERDDAP requires is written as `???` to be filled in.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DatasetMetadata {
+    /// ERDDAP `datasetID`
+    pub dataset_id: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub institution: Option<String>,
+    /// ERDDAP `infoUrl`
+    pub info_url: Option<String>,
+    /// Defaults to ERDDAP's `[standard]` license
+    pub license: Option<String>,
+    pub creator_name: Option<String>,
+    pub creator_email: Option<String>,
+    pub creator_url: Option<String>,
+    /// Defaults to `Other`
+    pub cdm_data_type: Option<String>,
+    /// Directory ERDDAP reads files from
+    pub file_dir: Option<String>,
+    /// Defaults to `.*\.csv` or `.*\.nc` depending on the dataset type
+    pub file_name_regex: Option<String>,
+}
+
+impl DatasetMetadata {
+    pub fn new(dataset_id: &str) -> Self {
+        DatasetMetadata {
+            dataset_id: dataset_id.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Source column in a dataset, and the standard it was resolved to
+#[derive(Clone)]
+pub struct DatasetColumn {
+    /// Column or variable name in the source files
+    pub source_name: String,
+
+    /// Standard the column name or alias matches,
+    /// otherwise the first standard that lists it as a common variable name
+    pub standard: Option<Arc<Standard>>,
+
+    /// Other standards that list the column as a common variable name
+    pub alternatives: Vec<String>,
+}
+
+impl DatasetColumn {
+    fn resolve(library: &StandardsLibrary, source_name: &str) -> Self {
+        if let Some(standard) = library.lookup(source_name) {
+            return DatasetColumn {
+                source_name: source_name.to_string(),
+                standard: Some(standard),
+                alternatives: Vec::new(),
+            };
+        }
+
+        let mut matches = library
+            .filter()
+            .by_variable_name(source_name)
+            .standards
+            .into_iter();
+        DatasetColumn {
+            source_name: source_name.to_string(),
+            standard: matches.next(),
+            alternatives: matches.map(|standard| standard.name.clone()).collect(),
+        }
+    }
+
+    /// Name ERDDAP serves the column as
+    ///
+    /// ERDDAP needs time, latitude, longitude, and depth or altitude to use those names,
+    /// otherwise it is the source name with anything ERDDAP doesn't allow replaced.
+    pub fn destination_name(&self) -> String {
+        if let Some(standard) = &self.standard {
+            if standard.name == "time" || AXIS_UNITS.iter().any(|(axis, _)| *axis == standard.name)
+            {
+                return standard.name.clone();
+            }
+        }
+
+        let name: String = self
+            .source_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            name
+        } else {
+            format!("var_{name}")
+        }
+    }
+
+    fn is_time(&self) -> bool {
+        self.standard
+            .as_ref()
+            .is_some_and(|standard| standard.name == "time")
+    }
+}
+
+/// `<dataset>` skeleton for a set of source columns
+#[derive(Clone)]
+pub struct ErddapDataset {
+    pub dataset_type: ErddapDatasetType,
+    pub metadata: DatasetMetadata,
+    pub columns: Vec<DatasetColumn>,
+    /// CF standard name table version, for `standard_name_vocabulary`
+    pub cf_version: Option<String>,
+}
+
+impl ErddapDataset {
+    /// Resolve source column names to standards, by name or alias, then by common variable name
+    pub fn new(
+        library: &StandardsLibrary,
+        columns: &[impl AsRef<str>],
+        dataset_type: ErddapDatasetType,
+        metadata: DatasetMetadata,
+    ) -> Result<Self> {
+        let id = &metadata.dataset_id;
+        if !id.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(Error::InvalidArgument {
+                argument: "dataset_id".to_string(),
+                message: format!(
+                    "`{id}` needs to start with a letter and only have letters, digits, and underscores"
+                ),
+            });
+        }
+        if columns.is_empty() {
+            return Err(Error::MissingArgument {
+                argument: "columns".to_string(),
+                context: "ERDDAP dataset".to_string(),
+            });
+        }
+
+        let dataset = ErddapDataset {
+            dataset_type,
+            metadata,
+            columns: columns
+                .iter()
+                .map(|column| DatasetColumn::resolve(library, column.as_ref()))
+                .collect(),
+            cf_version: library.cf_version.clone(),
+        };
+
+        // ERDDAP rejects datasets that serve two variables with the same name
+        let mut sources_by_name: BTreeMap<String, &str> = BTreeMap::new();
+        for column in &dataset.columns {
+            for variable in dataset.data_variables(column) {
+                if let Some(other) =
+                    sources_by_name.insert(variable.destination_name.clone(), &column.source_name)
+                {
+                    return Err(Error::InvalidArgument {
+                        argument: "columns".to_string(),
+                        message: format!(
+                            "`{other}` and `{}` would both be served as `{}`",
+                            column.source_name, variable.destination_name
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+
+    /// Columns that no standard was found for
+    pub fn unresolved(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .filter(|column| column.standard.is_none())
+            .map(|column| column.source_name.as_str())
+            .collect()
+    }
+
+    fn global_attrs(&self) -> Vec<Att> {
+        let metadata = &self.metadata;
+        let or_fill_in =
+            |value: &Option<String>| value.as_deref().unwrap_or(TO_FILL_IN).to_string();
+
+        let keywords: BTreeSet<&str> = self
+            .columns
+            .iter()
+            .filter_map(|column| column.standard.as_ref())
+            .map(|standard| standard.name.as_str())
+            .collect();
+
+        let mut attrs = vec![
+            Att::new(
+                "cdm_data_type",
+                metadata.cdm_data_type.as_deref().unwrap_or("Other"),
+            ),
+            Att::new("Conventions", "COARDS, CF-1.6, ACDD-1.3"),
+        ];
+        for (name, value) in [
+            ("creator_email", &metadata.creator_email),
+            ("creator_name", &metadata.creator_name),
+            ("creator_url", &metadata.creator_url),
+        ] {
+            if let Some(value) = value {
+                attrs.push(Att::new(name, value));
+            }
+        }
+        attrs.push(Att::new("infoUrl", &or_fill_in(&metadata.info_url)));
+        attrs.push(Att::new("institution", &or_fill_in(&metadata.institution)));
+        if !keywords.is_empty() {
+            attrs.push(Att::new(
+                "keywords",
+                &keywords.into_iter().collect::<Vec<_>>().join(", "),
+            ));
+        }
+        attrs.push(Att::new(
+            "license",
+            metadata.license.as_deref().unwrap_or("[standard]"),
+        ));
+        attrs.push(Att::new("sourceUrl", "(local files)"));
+        if let Some(version) = &self.cf_version {
+            attrs.push(Att::new(
+                "standard_name_vocabulary",
+                &format!("CF Standard Name Table v{version}"),
+            ));
+        }
+        attrs.push(Att::new("summary", &or_fill_in(&metadata.summary)));
+        attrs.push(Att::new("title", &or_fill_in(&metadata.title)));
+        attrs
+    }
+
+    /// Data variables for a column, with its QC flag variable if it has QARTOD test suites
+    fn data_variables(&self, column: &DatasetColumn) -> Vec<DataVariable> {
+        let destination_name = column.destination_name();
+
+        let Some(standard) = &column.standard else {
+            return vec![DataVariable::unresolved(
+                &column.source_name,
+                &destination_name,
+            )];
+        };
+
+        let mut variable =
+            DataVariable::for_standard(standard, &column.source_name, &destination_name);
+        // Standards without units, like `platform_name`, are text
+        variable.data_type = Some(if standard.unit.is_empty() {
+            "String"
+        } else {
+            "double"
+        });
+
+        if column.is_time() {
+            let (data_type, units) = match self.dataset_type {
+                ErddapDatasetType::AsciiFiles => ("String", ISO_TIME_UNITS),
+                ErddapDatasetType::NcFiles => ("double", EPOCH_TIME_UNITS),
+            };
+            variable.data_type = Some(data_type);
+            variable.set(Att::new("units", units));
+        } else if let Some((_, units)) = AXIS_UNITS.iter().find(|(axis, _)| *axis == standard.name)
+        {
+            variable.set(Att::new("units", units));
+        }
+
+        // Source files usually only have the aggregate flag, rather than one for each test
+        let Some(flags) = standard.qc_flags(Some(&destination_name)) else {
+            return vec![variable];
+        };
+        let aggregate = flags.aggregate();
+        variable.set(Att::new("ancillary_variables", &aggregate.name));
+
+        let mut flag = DataVariable {
+            source_name: format!("{}{QC_AGG_SUFFIX}", column.source_name),
+            destination_name: aggregate.name.clone(),
+            data_type: Some("byte"),
+            attrs: aggregate
+                .attrs
+                .iter()
+                .map(|(name, value)| Att::typed(name, value))
+                .collect(),
+        };
+        flag.set(Att {
+            att_type: Some("byteList"),
+            ..Att::typed("flag_values", &aggregate.attrs["flag_values"])
+        });
+        flag.set(Att::new("ioos_category", "Quality"));
+
+        vec![variable, flag]
+    }
+
+    /// Render the `<dataset>` for `datasets.xml`
+    pub fn to_xml(&self) -> String {
+        let metadata = &self.metadata;
+        let time_column = self.columns.iter().find(|column| column.is_time());
+
+        let mut lines = vec![
+            format!(
+                "<dataset type=\"{}\" datasetID=\"{}\" active=\"true\">",
+                self.dataset_type,
+                xml_escape(&metadata.dataset_id)
+            ),
+            "<reloadEveryNMinutes>10080</reloadEveryNMinutes>".to_string(),
+            "<updateEveryNMillis>10000</updateEveryNMillis>".to_string(),
+            format!(
+                "<fileDir>{}</fileDir>",
+                xml_escape(metadata.file_dir.as_deref().unwrap_or(TO_FILL_IN))
+            ),
+            format!(
+                "<fileNameRegex>{}</fileNameRegex>",
+                xml_escape(
+                    metadata
+                        .file_name_regex
+                        .as_deref()
+                        .unwrap_or(self.dataset_type.default_file_name_regex())
+                )
+            ),
+            "<recursive>true</recursive>".to_string(),
+            "<pathRegex>.*</pathRegex>".to_string(),
+            "<metadataFrom>last</metadataFrom>".to_string(),
+            "<standardizeWhat>0</standardizeWhat>".to_string(),
+        ];
+        if self.dataset_type == ErddapDatasetType::AsciiFiles {
+            lines.push("<charset>UTF-8</charset>".to_string());
+            lines.push("<columnNamesRow>1</columnNamesRow>".to_string());
+            lines.push("<firstDataRow>2</firstDataRow>".to_string());
+        }
+        if let Some(time_column) = time_column {
+            let source_name = xml_escape(&time_column.source_name);
+            lines.push(format!(
+                "<sortedColumnSourceName>{source_name}</sortedColumnSourceName>"
+            ));
+            lines.push(format!(
+                "<sortFilesBySourceNames>{source_name}</sortFilesBySourceNames>"
+            ));
+        }
+        lines.push("<fileTableInMemory>false</fileTableInMemory>".to_string());
+
+        lines.push("<addAttributes>".to_string());
+        for att in self.global_attrs() {
+            lines.push(format!("{INDENT}{}", att.line()));
+        }
+        lines.push("</addAttributes>".to_string());
+
+        for column in &self.columns {
+            for variable in self.data_variables(column) {
+                lines.extend(variable.lines());
+            }
+        }
+
+        let mut xml: Vec<String> = vec![lines[0].clone()];
+        xml.extend(lines[1..].iter().map(|line| format!("{INDENT}{line}")));
+        xml.push("</dataset>".to_string());
+        xml.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_data_variable() {
+        let standard = Standard {
+            name: "sea_water_temperature".to_string(),
+            unit: "K".to_string(),
+            long_name: Some("Sea Water Temperature".to_string()),
+            ioos_category: Some("Temperature".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            data_variable(&standard, "temp", "sea_water_temperature"),
+            r#"<dataVariable>
+    <sourceName>temp</sourceName>
+    <destinationName>sea_water_temperature</destinationName>
+    <addAttributes>
+        <att name="ioos_category">Temperature</att>
+        <att name="long_name">Sea Water Temperature</att>
+        <att name="standard_name">sea_water_temperature</att>
+        <att name="units">K</att>
+    </addAttributes>
+</dataVariable>"#
+        );
+    }
+
+    #[test]
+    fn types_numeric_attributes() {
+        let standard = Standard {
+            name: "sea_water_practical_salinity".to_string(),
+            ioos_category: Some("Salinity".to_string()),
+            extra_attrs: [
+                ("valid_min".to_string(), AttrValue::Int(0)),
+                (
+                    "valid_range".to_string(),
+                    AttrValue::Floats(vec![0.0, 42.5]),
+                ),
+                ("_ChunkSizes".to_string(), AttrValue::Ints(vec![1024])),
+                ("scale_factor".to_string(), "0.01".into()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        let xml = data_variable(&standard, "sal", "sal");
+        assert!(xml.contains("<att name=\"_ChunkSizes\" type=\"intList\">1024</att>"));
+        assert!(xml.contains("<att name=\"scale_factor\">0.01</att>"));
+        assert!(xml.contains("<att name=\"valid_min\" type=\"double\">0</att>"));
+        assert!(xml.contains("<att name=\"valid_range\" type=\"doubleList\">0 42.5</att>"));
+    }
+
+    #[test]
+    fn infers_missing_category_and_escapes() {
+        let standard = Standard {
+            name: "sea_ice_thickness".to_string(),
+            unit: "m".to_string(),
+            long_name: Some("Ice <thick> & \"thin\"".to_string()),
+            ..Default::default()
+        };
+
+        let xml = data_variable(&standard, "ice", "ice");
+        assert!(xml.contains("<!-- ioos_category inferred from the standard name and unit, 75% confidence -->\n        <att name=\"ioos_category\">Ice Distribution</att>"));
+        assert!(xml.contains("Ice &lt;thick&gt; &amp; &quot;thin&quot;"));
+        assert!(roxmltree::Document::parse(&xml).is_ok());
+    }
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library.load_test_suites();
+        library
+    }
+
+    #[test]
+    fn renders_dataset() {
+        let library = library();
+        let dataset = ErddapDataset::new(
+            &library,
+            &[
+                "time",
+                "latitude",
+                "navd88_meters",
+                "wind speed",
+                "2nd_thing",
+            ],
+            ErddapDatasetType::NcFiles,
+            DatasetMetadata {
+                title: Some("Buoy & Friends".to_string()),
+                ..DatasetMetadata::new("buoy_a01")
+            },
+        )
+        .unwrap();
+
+        assert_eq!(dataset.unresolved(), vec!["wind speed", "2nd_thing"]);
+        let names: Vec<String> = dataset
+            .columns
+            .iter()
+            .map(DatasetColumn::destination_name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "time",
+                "latitude",
+                "navd88_meters",
+                "wind_speed",
+                "var_2nd_thing"
+            ]
+        );
+
+        let xml = dataset.to_xml();
+        let document = roxmltree::Document::parse(&xml).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.attribute("type"), Some("EDDTableFromNcFiles"));
+        assert_eq!(root.attribute("datasetID"), Some("buoy_a01"));
+
+        let variables: Vec<_> = root
+            .children()
+            .filter(|node| node.has_tag_name("dataVariable"))
+            .collect();
+        // The water level has QARTOD test suites, so it gets a flag variable
+        assert_eq!(variables.len(), 6);
+
+        assert!(xml.contains("<att name=\"title\">Buoy &amp; Friends</att>"));
+        assert!(xml.contains("<att name=\"summary\">???</att>"));
+        assert!(xml.contains("<sortedColumnSourceName>time</sortedColumnSourceName>"));
+        assert!(xml.contains("<att name=\"units\">seconds since 1970-01-01T00:00:00Z</att>"));
+        assert!(xml.contains("<att name=\"units\">degrees_north</att>"));
+        assert!(xml.contains("<att name=\"ancillary_variables\">navd88_meters_qc_agg</att>"));
+        assert!(xml.contains("<att name=\"flag_values\" type=\"byteList\">1 2 3 4 9</att>"));
+        assert!(
+            xml.contains("<att name=\"references\">https://ioos.noaa.gov/project/qartod/</att>")
+        );
+    }
+
+    #[test]
+    fn types_columns_by_standard() {
+        let library = library();
+        let dataset = ErddapDataset::new(
+            &library,
+            &["time", "platform_name", "latitude"],
+            ErddapDatasetType::AsciiFiles,
+            DatasetMetadata::new("buoy"),
+        )
+        .unwrap();
+
+        let data_types: Vec<_> = dataset
+            .columns
+            .iter()
+            .flat_map(|column| dataset.data_variables(column))
+            .map(|variable| variable.data_type)
+            .collect();
+        assert_eq!(
+            data_types,
+            vec![Some("String"), Some("String"), Some("double")]
+        );
+    }
+
+    #[test]
+    fn checks_dataset_arguments() {
+        let library = library();
+        let invalid_id = ErddapDataset::new(
+            &library,
+            &["time"],
+            ErddapDatasetType::AsciiFiles,
+            DatasetMetadata::new("buoy-a01"),
+        );
+        assert!(matches!(
+            invalid_id,
+            Err(Error::InvalidArgument { argument, .. }) if argument == "dataset_id"
+        ));
+
+        let no_columns: [&str; 0] = [];
+        assert!(ErddapDataset::new(
+            &library,
+            &no_columns,
+            ErddapDatasetType::AsciiFiles,
+            DatasetMetadata::new("buoy")
+        )
+        .is_err());
+
+        for columns in [["time", "time"], ["wind-speed", "wind_speed"]] {
+            let duplicate = ErddapDataset::new(
+                &library,
+                &columns,
+                ErddapDatasetType::NcFiles,
+                DatasetMetadata::new("buoy"),
+            );
+            assert!(matches!(
+                duplicate,
+                Err(Error::InvalidArgument { argument, .. }) if argument == "columns"
+            ));
+        }
+
+        assert_eq!(
+            "nc".parse::<ErddapDatasetType>().unwrap(),
+            ErddapDatasetType::NcFiles
+        );
+        assert!("grib".parse::<ErddapDatasetType>().is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::{self, Display};
+
+use crate::suggest::UnknownStandard;
+
+/// Errors that can occur when loading, querying, or using standards
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A standard name or alias could not be found
+    UnknownStandard(UnknownStandard),
+
+    /// An argument was given, but its value can't be used
+    InvalidArgument {
+        /// Name of the argument
+        argument: String,
+        /// Why the value is invalid
+        message: String,
+    },
+
+    /// A required argument was not given
+    MissingArgument {
+        /// Name of the argument
+        argument: String,
+        /// What needed the argument, such as a test suite name
+        context: String,
+    },
+
+    /// Input could not be parsed
+    Parse {
+        /// What was being parsed, such as a file path or format
+        input: String,
+        /// Description from the underlying parser
+        message: String,
+    },
+
+    /// Knowledge could not be loaded or applied
+    InvalidKnowledge {
+        /// Standard name or source the knowledge was for
+        name: String,
+        /// What is wrong with the knowledge
+        message: String,
+    },
+
+    /// A test suite can't do what was asked of it
+    UnsupportedSuite {
+        /// Slug of the test suite
+        suite: String,
+        /// What isn't supported
+        message: String,
+    },
+
+    /// A file could not be read or written
+    Io {
+        /// Path of the file
+        path: String,
+        /// Description from the operating system
+        message: String,
+    },
+}
+
+/// Result type for `standard_knowledge` operations
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Name of the kind of error, used for naming binding exception types
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::UnknownStandard(_) => "UnknownStandard",
+            Error::InvalidArgument { .. } => "InvalidArgument",
+            Error::MissingArgument { .. } => "MissingArgument",
+            Error::Parse { .. } => "Parse",
+            Error::InvalidKnowledge { .. } => "InvalidKnowledge",
+            Error::UnsupportedSuite { .. } => "UnsupportedSuite",
+            Error::Io { .. } => "Io",
+        }
+    }
+
+    pub(crate) fn parse(input: impl Into<String>, message: impl Display) -> Self {
+        Error::Parse {
+            input: input.into(),
+            message: message.to_string(),
+        }
+    }
+
+    pub(crate) fn io(path: impl Into<String>, message: impl Display) -> Self {
+        Error::Io {
+            path: path.into(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownStandard(unknown) => write!(f, "{unknown}"),
+            Error::InvalidArgument { argument, message } => {
+                write!(f, "Invalid argument {argument}: {message}")
+            }
+            Error::MissingArgument { argument, context } => {
+                write!(f, "Missing required argument for {context}: {argument}")
+            }
+            Error::Parse { input, message } => write!(f, "Unable to parse {input}: {message}"),
+            Error::InvalidKnowledge { name, message } => {
+                write!(f, "Invalid knowledge for {name}: {message}")
+            }
+            Error::UnsupportedSuite { suite, message } => {
+                write!(f, "Unsupported test suite {suite}: {message}")
+            }
+            Error::Io { path, message } => write!(f, "Unable to access {path}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<UnknownStandard> for Error {
+    fn from(unknown: UnknownStandard) -> Self {
+        Error::UnknownStandard(unknown)
+    }
+}
@@ -0,0 +1,560 @@
+//! Parse and compose CF standard names from their grammatical parts
+//!
+//! CF standard names are built from a base quantity, optionally with transformations
+//! and qualifiers, in the order
+//! `[transformation_of_] [surface_] [component_] base [_at_surface] [_in_medium] [_due_to_process] [_assuming_condition]`.
+//! For example `tendency_of_sea_water_temperature_due_to_advection`,
+//! `sea_water_temperature_at_sea_floor`, and `mass_concentration_of_oxygen_in_sea_water`.
+//!
+//! Parsing only recognizes the common transformations, components, surfaces, and media,
+//! so anything unrecognized stays in the base. Composing a name from parsed parts
+//! always gives back the name that was parsed.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::standard::Standard;
+use crate::standards_library::StandardsLibrary;
+use crate::suggest::{suggest, DEFAULT_SUGGESTION_LIMIT};
+
+/// Transformations that are written as a prefix, outermost first when nested
+const TRANSFORMATIONS: &[&str] = &[
+    "change_over_time_in",
+    "change_in",
+    "tendency_of",
+    "rate_of_change_of",
+    "magnitude_of",
+    "square_of",
+    "direction_of",
+    "histogram_of",
+    "probability_distribution_of",
+    "integral_wrt_time_of",
+    "integral_wrt_depth_of",
+    "integral_wrt_height_of",
+];
+
+/// Transformation that takes what the derivative is with respect to as a suffix
+const DERIVATIVE: &str = "derivative_of";
+
+/// Surfaces that may be written before the rest of the name, like `surface_upward_heat_flux`
+const LEADING_SURFACES: &[&str] = &["surface", "toa", "tropopause"];
+
+/// Directional components, longest first so `net_downward` is found before `downward`
+const COMPONENTS: &[&str] = &[
+    "net_downward",
+    "net_upward",
+    "downwelling",
+    "upwelling",
+    "downward",
+    "upward",
+    "northward",
+    "southward",
+    "eastward",
+    "westward",
+    "outgoing",
+    "incoming",
+    "x",
+    "y",
+];
+
+/// Media that a quantity can be in
+const MEDIA: &[&str] = &[
+    "sea_water",
+    "air",
+    "sea_ice",
+    "soil",
+    "soil_water",
+    "snow",
+    "land_ice",
+    "atmosphere_boundary_layer",
+    "river",
+    "lake",
+    "sea_floor_sediment_pore_water",
+];
+
+/// Most alternatives to return for a composed name that isn't in the table
+const MAX_ALTERNATIVES: usize = 10;
+
+/// A transformation of a quantity, like `tendency_of`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transformation {
+    /// Transformation as written before the quantity, like `tendency_of` or `derivative_of`
+    pub operator: String,
+
+    /// What a derivative is with respect to, written after the quantity as `_wrt_`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrt: Option<String>,
+}
+
+impl Transformation {
+    /// A transformation that doesn't take an argument
+    pub fn new(operator: &str) -> Self {
+        Transformation {
+            operator: operator.to_string(),
+            wrt: None,
+        }
+    }
+}
+
+impl FromStr for Transformation {
+    type Err = Error;
+
+    /// Parse a transformation like `tendency_of`, or `derivative_of:depth` with what it is with respect to
+    fn from_str(transformation: &str) -> Result<Self> {
+        let (operator, wrt) = match transformation.split_once(':') {
+            Some((operator, wrt)) => (operator, Some(wrt.to_string())),
+            None => (transformation, None),
+        };
+        if operator.is_empty() || wrt.as_ref().is_some_and(String::is_empty) {
+            return Err(Error::InvalidArgument {
+                argument: "transformation".to_string(),
+                message: format!(
+                    "`{transformation}` should be like `tendency_of` or `derivative_of:depth`"
+                ),
+            });
+        }
+        Ok(Transformation {
+            operator: operator.to_string(),
+            wrt,
+        })
+    }
+}
+
+impl Display for Transformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.wrt {
+            Some(wrt) => write!(f, "{}_X_wrt_{wrt}", self.operator),
+            None => write!(f, "{}_X", self.operator),
+        }
+    }
+}
+
+/// The grammatical parts of a CF standard name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameParts {
+    /// Transformations applied to the quantity, outermost first
+    #[serde(default)]
+    pub transformations: Vec<Transformation>,
+
+    /// Surface written before the quantity, like `surface` or `toa`
+    #[serde(default)]
+    pub leading_surface: Option<String>,
+
+    /// Direction of a vector component, like `northward` or `upward`
+    #[serde(default)]
+    pub component: Option<String>,
+
+    /// The quantity itself, like `sea_water_temperature`
+    pub base: String,
+
+    /// Surface the quantity is at, like `sea_floor` in `_at_sea_floor`
+    #[serde(default)]
+    pub surface: Option<String>,
+
+    /// Medium the quantity is in, like `sea_water` in `_in_sea_water`
+    #[serde(default)]
+    pub medium: Option<String>,
+
+    /// Process causing the quantity, like `advection` in `_due_to_advection`
+    #[serde(default)]
+    pub process: Option<String>,
+
+    /// Condition assumed, like `clear_sky` in `_assuming_clear_sky`
+    #[serde(default)]
+    pub condition: Option<String>,
+}
+
+/// Is this a well formed piece of a standard name, words separated by single underscores
+///
+/// Names are lowercase, except for chemical symbols such as `127Te`,
+/// so the first word must start with a lowercase letter or digit.
+fn is_well_formed(part: &str) -> bool {
+    part.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && part
+            .split('_')
+            .all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Split a qualifier off the end of a name, if what is left isn't empty
+fn split_suffix<'a>(name: &'a str, separator: &str, first: bool) -> Option<(&'a str, &'a str)> {
+    let index = if first {
+        name.find(separator)
+    } else {
+        name.rfind(separator)
+    }?;
+    let (rest, qualifier) = (&name[..index], &name[index + separator.len()..]);
+    (!rest.is_empty() && !qualifier.is_empty()).then_some((rest, qualifier))
+}
+
+/// Split a word off the start of a name, if what is left isn't empty
+fn split_prefix<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    name.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_prefix('_'))
+        .filter(|rest| !rest.is_empty())
+}
+
+impl NameParts {
+    /// A name with only a base quantity
+    pub fn new(base: &str) -> Self {
+        NameParts {
+            base: base.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Break a standard name into its parts
+    pub fn parse(name: &str) -> Result<Self> {
+        if !is_well_formed(name) {
+            return Err(Error::parse(
+                format!("standard name `{name}`"),
+                "standard names are letters and digits separated by single underscores",
+            ));
+        }
+
+        let mut parts = NameParts::default();
+        let mut rest = name;
+
+        'transformations: loop {
+            if let Some(inner) = split_prefix(rest, DERIVATIVE) {
+                if let Some((inner, wrt)) = split_suffix(inner, "_wrt_", false) {
+                    parts.transformations.push(Transformation {
+                        operator: DERIVATIVE.to_string(),
+                        wrt: Some(wrt.to_string()),
+                    });
+                    rest = inner;
+                    continue;
+                }
+            }
+            for operator in TRANSFORMATIONS {
+                if let Some(inner) = split_prefix(rest, operator) {
+                    parts.transformations.push(Transformation::new(operator));
+                    rest = inner;
+                    continue 'transformations;
+                }
+            }
+            break;
+        }
+
+        if let Some((inner, condition)) = split_suffix(rest, "_assuming_", true) {
+            parts.condition = Some(condition.to_string());
+            rest = inner;
+        }
+        if let Some((inner, process)) = split_suffix(rest, "_due_to_", true) {
+            parts.process = Some(process.to_string());
+            rest = inner;
+        }
+        if let Some((inner, medium)) = split_suffix(rest, "_in_", false) {
+            if MEDIA.contains(&medium) {
+                parts.medium = Some(medium.to_string());
+                rest = inner;
+            }
+        }
+        if let Some((inner, surface)) = split_suffix(rest, "_at_", true) {
+            parts.surface = Some(surface.to_string());
+            rest = inner;
+        }
+
+        if let Some((surface, inner)) = LEADING_SURFACES
+            .iter()
+            .find_map(|surface| split_prefix(rest, surface).map(|inner| (surface, inner)))
+        {
+            parts.leading_surface = Some(surface.to_string());
+            rest = inner;
+        }
+        if let Some((component, inner)) = COMPONENTS
+            .iter()
+            .find_map(|component| split_prefix(rest, component).map(|inner| (component, inner)))
+        {
+            parts.component = Some(component.to_string());
+            rest = inner;
+        }
+
+        parts.base = rest.to_string();
+        Ok(parts)
+    }
+
+    /// Build a standard name from its parts
+    ///
+    /// Returns an error if any part isn't words separated by underscores.
+    pub fn compose(&self) -> Result<String> {
+        let invalid = |argument: &str, value: &str| Error::InvalidArgument {
+            argument: argument.to_string(),
+            message: format!(
+                "`{value}` should be letters and digits separated by single underscores"
+            ),
+        };
+
+        if !is_well_formed(&self.base) {
+            return Err(invalid("base", &self.base));
+        }
+        for transformation in &self.transformations {
+            if !is_well_formed(&transformation.operator) {
+                return Err(invalid("transformation", &transformation.operator));
+            }
+            if let Some(wrt) = &transformation.wrt {
+                if !is_well_formed(wrt) {
+                    return Err(invalid("wrt", wrt));
+                }
+            }
+        }
+        for (argument, value) in [
+            ("leading_surface", &self.leading_surface),
+            ("component", &self.component),
+            ("surface", &self.surface),
+            ("medium", &self.medium),
+            ("process", &self.process),
+            ("condition", &self.condition),
+        ] {
+            if let Some(value) = value {
+                if !is_well_formed(value) {
+                    return Err(invalid(argument, value));
+                }
+            }
+        }
+
+        let mut words: Vec<&str> = Vec::new();
+        for transformation in &self.transformations {
+            words.push(&transformation.operator);
+        }
+        words.extend(self.leading_surface.as_deref());
+        words.extend(self.component.as_deref());
+        words.push(&self.base);
+        for (separator, value) in [
+            ("at", &self.surface),
+            ("in", &self.medium),
+            ("due_to", &self.process),
+            ("assuming", &self.condition),
+        ] {
+            if let Some(value) = value {
+                words.push(separator);
+                words.push(value);
+            }
+        }
+        for transformation in self.transformations.iter().rev() {
+            if let Some(wrt) = &transformation.wrt {
+                words.push("wrt");
+                words.push(wrt);
+            }
+        }
+
+        Ok(words.join("_"))
+    }
+
+    /// How many parts differ from another name, for ranking alternatives
+    pub(crate) fn distance(&self, other: &NameParts) -> usize {
+        [
+            self.transformations != other.transformations,
+            self.leading_surface != other.leading_surface,
+            self.component != other.component,
+            self.base != other.base,
+            self.surface != other.surface,
+            self.medium != other.medium,
+            self.process != other.process,
+            self.condition != other.condition,
+        ]
+        .into_iter()
+        .filter(|differs| *differs)
+        .count()
+    }
+}
+
+impl Display for NameParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for transformation in &self.transformations {
+            writeln!(f, "Transformation: {transformation}")?;
+        }
+        for (label, value) in [
+            ("Leading surface", &self.leading_surface),
+            ("Component", &self.component),
+        ] {
+            if let Some(value) = value {
+                writeln!(f, "{label}: {value}")?;
+            }
+        }
+        write!(f, "Base: {}", self.base)?;
+        for (label, value) in [
+            ("At surface", &self.surface),
+            ("In medium", &self.medium),
+            ("Due to process", &self.process),
+            ("Assuming condition", &self.condition),
+        ] {
+            if let Some(value) = value {
+                write!(f, "\n{label}: {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A standard name composed from parts, and whether it is in the library
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComposedName {
+    pub name: String,
+
+    /// The standard, if the name (or an alias) is in the library
+    pub standard: Option<Arc<Standard>>,
+
+    /// If the name isn't in the library, known names that are closest,
+    /// preferring ones with the same base quantity and the fewest different parts
+    pub alternatives: Vec<String>,
+}
+
+impl ComposedName {
+    /// Compose a name and look it up in a library
+    pub fn check(parts: &NameParts, library: &StandardsLibrary) -> Result<Self> {
+        let name = parts.compose()?;
+
+        if let Some(standard) = library.lookup(&name) {
+            return Ok(ComposedName {
+                name,
+                standard: Some(standard),
+                alternatives: Vec::new(),
+            });
+        }
+
+        let mut same_base: Vec<(usize, &str)> = library
+            .standards()
+            .keys()
+            .filter_map(|known| {
+                let known_parts = NameParts::parse(known).ok()?;
+                (known_parts.base == parts.base)
+                    .then(|| (known_parts.distance(parts), known.as_str()))
+            })
+            .collect();
+        same_base.sort();
+
+        let alternatives = if same_base.is_empty() {
+            suggest(
+                &name,
+                library.standards().values().map(Arc::as_ref),
+                DEFAULT_SUGGESTION_LIMIT,
+            )
+        } else {
+            same_base
+                .into_iter()
+                .take(MAX_ALTERNATIVES)
+                .map(|(_, known)| known.to_string())
+                .collect()
+        };
+
+        Ok(ComposedName {
+            name,
+            standard: None,
+            alternatives,
+        })
+    }
+
+    /// Is the composed name in the library
+    pub fn is_known(&self) -> bool {
+        self.standard.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cf::builtin_cf_table;
+
+    #[test]
+    fn parses_qualifiers() {
+        let parts = NameParts::parse("sea_water_temperature_at_sea_floor").unwrap();
+        assert_eq!(parts.base, "sea_water_temperature");
+        assert_eq!(parts.surface.as_deref(), Some("sea_floor"));
+
+        let parts = NameParts::parse("mass_concentration_of_oxygen_in_sea_water").unwrap();
+        assert_eq!(parts.base, "mass_concentration_of_oxygen");
+        assert_eq!(parts.medium.as_deref(), Some("sea_water"));
+
+        let parts = NameParts::parse("tendency_of_sea_water_temperature_due_to_advection").unwrap();
+        assert_eq!(
+            parts.transformations,
+            vec![Transformation::new("tendency_of")]
+        );
+        assert_eq!(parts.base, "sea_water_temperature");
+        assert_eq!(parts.process.as_deref(), Some("advection"));
+
+        let parts =
+            NameParts::parse("surface_downwelling_shortwave_flux_in_air_assuming_clear_sky")
+                .unwrap();
+        assert_eq!(parts.leading_surface.as_deref(), Some("surface"));
+        assert_eq!(parts.component.as_deref(), Some("downwelling"));
+        assert_eq!(parts.base, "shortwave_flux");
+        assert_eq!(parts.medium.as_deref(), Some("air"));
+        assert_eq!(parts.condition.as_deref(), Some("clear_sky"));
+
+        let parts = NameParts::parse("derivative_of_sea_water_temperature_wrt_depth").unwrap();
+        assert_eq!(parts.transformations[0].wrt.as_deref(), Some("depth"));
+        assert_eq!(parts.base, "sea_water_temperature");
+
+        assert!(NameParts::parse("Sea_Water_Temperature").is_err());
+        assert!(NameParts::parse("sea water temperature").is_err());
+        assert!(NameParts::parse("sea__water").is_err());
+    }
+
+    #[test]
+    fn composes_parts() {
+        let parts = NameParts {
+            transformations: vec![Transformation::new("change_over_time_in")],
+            component: Some("northward".to_string()),
+            surface: Some("sea_floor".to_string()),
+            process: Some("tides".to_string()),
+            ..NameParts::new("sea_water_velocity")
+        };
+        assert_eq!(
+            parts.compose().unwrap(),
+            "change_over_time_in_northward_sea_water_velocity_at_sea_floor_due_to_tides"
+        );
+
+        let invalid = NameParts {
+            medium: Some("Sea Water".to_string()),
+            ..NameParts::new("temperature")
+        };
+        assert!(matches!(
+            invalid.compose(),
+            Err(Error::InvalidArgument { argument, .. }) if argument == "medium"
+        ));
+    }
+
+    #[test]
+    fn checks_composed_names() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+
+        let parts = NameParts {
+            surface: Some("sea_floor".to_string()),
+            ..NameParts::new("sea_water_temperature")
+        };
+        let composed = library.check_name(&parts).unwrap();
+        assert!(composed.is_known());
+        assert_eq!(composed.name, "sea_water_temperature_at_sea_floor");
+
+        let parts = NameParts {
+            surface: Some("sea_ice_base".to_string()),
+            ..NameParts::new("sea_water_temperature")
+        };
+        let composed = library.check_name(&parts).unwrap();
+        assert!(!composed.is_known());
+        assert_eq!(composed.name, "sea_water_temperature_at_sea_ice_base");
+        // One part differs from the unqualified quantity, and from the sea floor
+        assert!(composed
+            .alternatives
+            .contains(&"sea_water_temperature_at_sea_floor".to_string()));
+        assert!(composed
+            .alternatives
+            .iter()
+            .all(|name| NameParts::parse(name).unwrap().base == "sea_water_temperature"));
+    }
+
+    #[test]
+    fn round_trips_the_cf_table() {
+        let table = builtin_cf_table().unwrap();
+        for name in table.standards.keys() {
+            let parts = NameParts::parse(name).unwrap();
+            assert_eq!(&parts.compose().unwrap(), name, "{parts:?}");
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::standard::Standard;
+use crate::standards_library::StandardsLibrary;
+use crate::suggest::UnknownStandard;
+
+/// How one standard refers to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relationship {
+    /// Listed in `related_standards`, another standard to consider
+    Related,
+    /// Listed in `sibling_standards`, usually used together
+    Sibling,
+}
+
+impl Display for Relationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Relationship::Related => write!(f, "related"),
+            Relationship::Sibling => write!(f, "sibling"),
+        }
+    }
+}
+
+impl FromStr for Relationship {
+    type Err = Error;
+
+    fn from_str(relationship: &str) -> Result<Self> {
+        match relationship.to_lowercase().as_str() {
+            "related" | "related_standards" => Ok(Relationship::Related),
+            "sibling" | "siblings" | "sibling_standards" => Ok(Relationship::Sibling),
+            _ => Err(Error::InvalidArgument {
+                argument: "relationship".to_string(),
+                message: format!(
+                    "`{relationship}` is not a relationship, expected related or sibling"
+                ),
+            }),
+        }
+    }
+}
+
+/// A link from one standard to another
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Edge {
+    pub from: String,
+    /// Canonical name of the standard linked to, or the name as written if it isn't known
+    pub to: String,
+    pub relationship: Relationship,
+}
+
+impl Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -[{}]-> {}", self.from, self.relationship, self.to)
+    }
+}
+
+/// A standard found by traversing the graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reached {
+    pub name: String,
+    /// Fewest links between the starting standard and this one
+    pub hops: usize,
+}
+
+#[derive(Serialize)]
+struct JsonNode<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    long_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ioos_category: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonGraph<'a> {
+    nodes: Vec<JsonNode<'a>>,
+    edges: &'a BTreeSet<Edge>,
+}
+
+/// Graph of the `related_standards` and `sibling_standards` links between standards
+///
+/// Links are followed in both directions when traversing,
+/// so a standard can be reached from one that lists it, or one that it lists.
+/// Links by alias are resolved to the canonical name when the graph is built.
+#[derive(Debug, Clone, Default)]
+pub struct StandardsGraph {
+    standards: BTreeMap<String, Arc<Standard>>,
+    aliases: HashMap<String, String>,
+    edges: BTreeSet<Edge>,
+}
+
+impl StandardsGraph {
+    /// Build a graph of every standard in a library
+    pub fn new(library: &StandardsLibrary) -> Self {
+        let mut graph = StandardsGraph::default();
+
+        for (name, standard) in library.standards() {
+            graph.standards.insert(name.clone(), standard.clone());
+            for alias in &standard.aliases {
+                graph.aliases.insert(alias.clone(), name.clone());
+            }
+
+            let links = standard
+                .related_standards
+                .iter()
+                .map(|to| (to, Relationship::Related))
+                .chain(
+                    standard
+                        .sibling_standards
+                        .iter()
+                        .map(|to| (to, Relationship::Sibling)),
+                );
+            for (to, relationship) in links {
+                graph.edges.insert(Edge {
+                    from: name.clone(),
+                    to: library.canonical_name(to).unwrap_or(to).to_string(),
+                    relationship,
+                });
+            }
+        }
+
+        graph
+    }
+
+    /// Names of all standards in the graph, sorted
+    pub fn nodes(&self) -> Vec<&str> {
+        self.standards.keys().map(String::as_str).collect()
+    }
+
+    /// All links, sorted by the standard they are from
+    pub fn edges(&self) -> Vec<&Edge> {
+        self.edges.iter().collect()
+    }
+
+    fn canonical_name<'a>(&'a self, standard_name_or_alias: &'a str) -> Result<&'a str> {
+        if self.standards.contains_key(standard_name_or_alias) {
+            return Ok(standard_name_or_alias);
+        }
+        self.aliases
+            .get(standard_name_or_alias)
+            .map(String::as_str)
+            .ok_or_else(|| {
+                UnknownStandard::new(
+                    standard_name_or_alias,
+                    self.standards.values().map(Arc::as_ref),
+                )
+                .into()
+            })
+    }
+
+    /// Known standards linked to or from a standard
+    fn neighbors<'a>(
+        &'a self,
+        name: &'a str,
+        relationship: Option<Relationship>,
+    ) -> impl Iterator<Item = &'a str> {
+        self.edges
+            .iter()
+            .filter(move |edge| relationship.is_none_or(|r| r == edge.relationship))
+            .filter_map(move |edge| {
+                if edge.from == name {
+                    Some(edge.to.as_str())
+                } else if edge.to == name {
+                    Some(edge.from.as_str())
+                } else {
+                    None
+                }
+            })
+            .filter(|neighbor| self.standards.contains_key(*neighbor))
+    }
+
+    /// Standards within a number of links of a standard, closest first
+    ///
+    /// The starting standard isn't included. Limit to one kind of link with `relationship`.
+    pub fn within(
+        &self,
+        standard_name_or_alias: &str,
+        hops: usize,
+        relationship: Option<Relationship>,
+    ) -> Result<Vec<Reached>> {
+        let start = self.canonical_name(standard_name_or_alias)?;
+
+        let mut seen: BTreeMap<&str, usize> = BTreeMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(name) = queue.pop_front() {
+            let distance = seen[name];
+            if distance == hops {
+                continue;
+            }
+            for neighbor in self.neighbors(name, relationship) {
+                if !seen.contains_key(neighbor) {
+                    seen.insert(neighbor, distance + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        let mut reached: Vec<Reached> = seen
+            .into_iter()
+            .filter(|(name, _)| *name != start)
+            .map(|(name, hops)| Reached {
+                name: name.to_string(),
+                hops,
+            })
+            .collect();
+        reached.sort_by(|a, b| a.hops.cmp(&b.hops).then_with(|| a.name.cmp(&b.name)));
+        Ok(reached)
+    }
+
+    /// Subgraph of a standard and everything within a number of links of it
+    pub fn neighborhood(
+        &self,
+        standard_name_or_alias: &str,
+        hops: usize,
+        relationship: Option<Relationship>,
+    ) -> Result<StandardsGraph> {
+        let start = self.canonical_name(standard_name_or_alias)?;
+        let mut names: BTreeSet<String> = self
+            .within(start, hops, relationship)?
+            .into_iter()
+            .map(|reached| reached.name)
+            .collect();
+        names.insert(start.to_string());
+
+        Ok(self.subgraph(&names, relationship))
+    }
+
+    fn subgraph(&self, names: &BTreeSet<String>, relationship: Option<Relationship>) -> Self {
+        StandardsGraph {
+            standards: self
+                .standards
+                .iter()
+                .filter(|(name, _)| names.contains(*name))
+                .map(|(name, standard)| (name.clone(), standard.clone()))
+                .collect(),
+            aliases: self
+                .aliases
+                .iter()
+                .filter(|(_, name)| names.contains(*name))
+                .map(|(alias, name)| (alias.clone(), name.clone()))
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|edge| relationship.is_none_or(|r| r == edge.relationship))
+                .filter(|edge| names.contains(&edge.from) && names.contains(&edge.to))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Standards connected to a standard by sibling links, including itself, sorted
+    ///
+    /// These are the standards that are usually deployed together.
+    pub fn sibling_group(&self, standard_name_or_alias: &str) -> Result<Vec<String>> {
+        let start = self.canonical_name(standard_name_or_alias)?;
+        let mut group: Vec<String> = self
+            .within(start, usize::MAX, Some(Relationship::Sibling))?
+            .into_iter()
+            .map(|reached| reached.name)
+            .chain([start.to_string()])
+            .collect();
+        group.sort();
+        Ok(group)
+    }
+
+    /// Every group of two or more standards connected by sibling links
+    pub fn sibling_groups(&self) -> Vec<Vec<String>> {
+        let mut grouped: BTreeSet<String> = BTreeSet::new();
+        let mut groups = Vec::new();
+
+        for name in self.standards.keys() {
+            if grouped.contains(name) {
+                continue;
+            }
+            let group = self
+                .sibling_group(name)
+                .expect("Standards in the graph should be known");
+            if group.len() > 1 {
+                grouped.extend(group.iter().cloned());
+                groups.push(group);
+            }
+        }
+
+        groups
+    }
+
+    /// Links that a standard should have back to the standards that link to it
+    ///
+    /// For each link from A to B where B doesn't link back to A in the same way,
+    /// the missing link from B to A is returned.
+    pub fn missing_back_links(&self) -> Vec<Edge> {
+        self.edges
+            .iter()
+            .filter(|edge| self.standards.contains_key(&edge.to))
+            .map(|edge| Edge {
+                from: edge.to.clone(),
+                to: edge.from.clone(),
+                relationship: edge.relationship,
+            })
+            .filter(|back| !self.edges.contains(back))
+            .collect()
+    }
+
+    /// Links to standards that aren't in the library
+    pub fn unknown_links(&self) -> Vec<&Edge> {
+        self.edges
+            .iter()
+            .filter(|edge| !self.standards.contains_key(&edge.to))
+            .collect()
+    }
+
+    /// Export to Graphviz DOT
+    ///
+    /// Related links are solid and sibling links are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut output = "digraph standards {\n  node [shape=box];\n".to_string();
+
+        for (name, standard) in &self.standards {
+            match &standard.long_name {
+                Some(long_name) => output.push_str(&format!(
+                    "  \"{name}\" [tooltip=\"{}\"];\n",
+                    long_name.replace('"', "\\\"")
+                )),
+                None => output.push_str(&format!("  \"{name}\";\n")),
+            }
+        }
+
+        for edge in &self.edges {
+            let style = match edge.relationship {
+                Relationship::Related => "solid",
+                Relationship::Sibling => "dashed",
+            };
+            output.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\", style={style}];\n",
+                edge.from, edge.to, edge.relationship
+            ));
+        }
+
+        output.push('}');
+        output
+    }
+
+    /// Export to JSON, as `nodes` with their long names and IOOS categories, and `edges`
+    pub fn to_json(&self) -> String {
+        let graph = JsonGraph {
+            nodes: self
+                .standards
+                .iter()
+                .map(|(name, standard)| JsonNode {
+                    name,
+                    long_name: standard.long_name.as_deref(),
+                    ioos_category: standard.ioos_category.as_deref(),
+                })
+                .collect(),
+            edges: &self.edges,
+        };
+        serde_json::to_string_pretty(&graph).expect("Graph should serialize to JSON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library
+    }
+
+    #[test]
+    fn traverses_links_in_both_directions() {
+        let graph = library().graph();
+
+        let reached = graph
+            .within("sea_water_speed", 1, Some(Relationship::Sibling))
+            .unwrap();
+        let names: Vec<&str> = reached.iter().map(|r| r.name.as_str()).collect();
+        assert!(names.contains(&"sea_water_velocity_to_direction"));
+        assert!(reached.iter().all(|r| r.hops == 1));
+
+        let further = graph.within("sea_water_speed", 3, None).unwrap();
+        assert!(further.len() >= reached.len());
+        assert!(further.windows(2).all(|w| w[0].hops <= w[1].hops));
+
+        assert!(matches!(
+            graph.within("sea_water_sped", 1, None),
+            Err(Error::UnknownStandard(_))
+        ));
+    }
+
+    #[test]
+    fn finds_sibling_groups() {
+        let graph = library().graph();
+
+        let group = graph
+            .sibling_group("tidal_sea_surface_height_above_mean_lower_low_water")
+            .unwrap();
+        assert!(group.contains(&"sea_surface_height_above_geopotential_datum".to_string()));
+        assert!(
+            group.contains(&"tidal_sea_surface_height_above_mean_higher_high_water".to_string())
+        );
+
+        let groups = graph.sibling_groups();
+        assert!(groups.contains(&group));
+        assert!(groups.iter().all(|group| group.len() > 1));
+    }
+
+    #[test]
+    fn finds_missing_back_links() {
+        let mut library = library();
+        library.apply_knowledge(vec![crate::Knowledge {
+            name: "air_pressure".to_string(),
+            related_standards: vec!["air_pressure_at_sea_level".to_string()],
+            sibling_standards: vec!["not_a_standard".to_string()],
+            ..Default::default()
+        }]);
+        let graph = library.graph();
+
+        // The alias is resolved, so this matches the existing link back
+        assert!(graph.edges().contains(&&Edge {
+            from: "air_pressure".to_string(),
+            to: "air_pressure_at_mean_sea_level".to_string(),
+            relationship: Relationship::Related,
+        }));
+        assert!(!graph.missing_back_links().contains(&Edge {
+            from: "air_pressure_at_mean_sea_level".to_string(),
+            to: "air_pressure".to_string(),
+            relationship: Relationship::Related,
+        }));
+        assert!(graph
+            .unknown_links()
+            .iter()
+            .any(|edge| edge.to == "not_a_standard"));
+    }
+
+    #[test]
+    fn exports_neighborhood() {
+        let graph = library()
+            .graph()
+            .neighborhood("sea_water_speed", 1, Some(Relationship::Sibling))
+            .unwrap();
+
+        assert!(graph.nodes().contains(&"sea_water_speed"));
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph standards {"));
+        assert!(dot.contains("\"sea_water_speed\" -> \"sea_water_velocity_to_direction\""));
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), graph.nodes().len());
+        assert_eq!(json["edges"][0]["relationship"], "sibling");
+    }
+}
@@ -0,0 +1,116 @@
+//! Dimensions, variables, and attributes from the header of a NetCDF file,
+//! without any of its data
+
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use serde::Serialize;
+
+use crate::attr_value::AttrValue;
+use crate::error::{Error, Result};
+
+/// Magic bytes that start HDF5 files, which NetCDF-4 files are
+const HDF5_MAGIC: &[u8] = b"\x89HDF\r\n\x1a\n";
+
+/// Dimension of a NetCDF file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dimension {
+    pub name: String,
+
+    /// Length, or `None` for the unlimited dimension
+    pub length: Option<usize>,
+}
+
+/// Variable declared in a NetCDF header
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeaderVariable {
+    pub name: String,
+
+    /// CDL type name, like `double` or `char`
+    pub data_type: String,
+
+    /// Names of the variable's dimensions
+    pub dimensions: Vec<String>,
+
+    pub attrs: BTreeMap<String, AttrValue>,
+}
+
+impl HeaderVariable {
+    /// String attribute of the variable
+    pub fn attr_str(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).and_then(AttrValue::as_str)
+    }
+}
+
+/// Header of a NetCDF file, as `ncdump -h` shows it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NetcdfHeader {
+    /// Dataset name from a CDL header
+    pub name: Option<String>,
+
+    pub dimensions: Vec<Dimension>,
+
+    /// Variables, in the order they are declared
+    pub variables: Vec<HeaderVariable>,
+
+    pub global_attrs: BTreeMap<String, AttrValue>,
+}
+
+impl NetcdfHeader {
+    /// Parse the header from CDL, such as the output of `ncdump -h`
+    ///
+    /// Anything after `data:` is ignored.
+    pub fn from_cdl(cdl: &str) -> Result<Self> {
+        crate::cdl::parse_header(cdl)
+    }
+
+    /// Read the header of a NetCDF-3 classic or 64-bit offset file
+    ///
+    /// Only the bytes up to the end of the header are needed.
+    pub fn from_netcdf3(bytes: &[u8]) -> Result<Self> {
+        crate::netcdf3::read_header(bytes)
+    }
+
+    /// Read a header from either a NetCDF-3 file or CDL, telling them apart by the file's magic bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if crate::netcdf3::is_netcdf3(bytes) {
+            return Self::from_netcdf3(bytes);
+        }
+        if bytes.starts_with(HDF5_MAGIC) {
+            return Err(Error::parse(
+                "NetCDF",
+                "NetCDF-4 files are not supported, convert the header to CDL with `ncdump -h`",
+            ));
+        }
+        let cdl = std::str::from_utf8(bytes)
+            .map_err(|_| Error::parse("CDL", "not a NetCDF-3 file or UTF-8 text"))?;
+        Self::from_cdl(cdl)
+    }
+
+    /// Read a header from the start of a NetCDF-3 file, or from CDL, such as an uploaded file
+    ///
+    /// Only as much of a NetCDF-3 file as its header needs is read, not its data.
+    pub fn read_from(mut reader: impl Read) -> Result<Self> {
+        let mut start = Vec::new();
+        reader
+            .by_ref()
+            .take(HDF5_MAGIC.len() as u64)
+            .read_to_end(&mut start)
+            .map_err(|e| Error::io("NetCDF", e))?;
+
+        if crate::netcdf3::is_netcdf3(&start) {
+            return crate::netcdf3::read_header_from(start.as_slice().chain(reader));
+        }
+
+        let mut bytes = start;
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::io("NetCDF", e))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Variable by name
+    pub fn variable(&self, name: &str) -> Option<&HeaderVariable> {
+        self.variables.iter().find(|variable| variable.name == name)
+    }
+}
@@ -1,6 +1,9 @@
 // Categories from https://github.com/ERDDAP/erddap/blob/26c55b4f125ece1e70081a4c46565cf4b8bd6eda/WEB-INF/classes/gov/noaa/pfel/erddap/variable/EDV.java#L119
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display};
+
+use serde::Serialize;
 
 pub fn erddap_categories() -> HashSet<&'static str> {
     HashSet::from([
@@ -51,3 +54,330 @@ pub fn erddap_categories() -> HashSet<&'static str> {
         "Zooplankton Abundance",
     ])
 }
+
+/// IOOS category proposed for a standard from its name and unit,
+/// rather than curated in knowledge
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InferredCategory {
+    /// Category from `erddap_categories()`, `Unknown` if nothing matched
+    pub category: String,
+
+    /// How sure the classifier is, from 0 to 1
+    pub confidence: f64,
+
+    /// Parts of the name and unit that point to the category
+    pub evidence: Vec<String>,
+}
+
+impl Display for InferredCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (inferred, {:.0}% confidence)",
+            self.category,
+            self.confidence * 100.0
+        )
+    }
+}
+
+/// Phrases of whole name words that point to a category
+///
+/// When phrases overlap, only the longest counts,
+/// so `air_temperature` wins over `temperature`.
+/// Phrases without a category only hide shorter ones,
+/// like `wrt_time` in `derivative_of_..._wrt_time`.
+const NAME_PHRASES: &[(&str, Option<&str>)] = &[
+    ("temperature", Some("Temperature")),
+    ("air_temperature", Some("Meteorology")),
+    ("air_potential_temperature", Some("Meteorology")),
+    ("dew_point_temperature", Some("Meteorology")),
+    ("virtual_temperature", Some("Meteorology")),
+    ("soil_temperature", Some("Soils")),
+    ("brightness_temperature", Some("Optical Properties")),
+    ("salinity", Some("Salinity")),
+    ("sea_water_velocity", Some("Currents")),
+    ("sea_water_x_velocity", Some("Currents")),
+    ("sea_water_y_velocity", Some("Currents")),
+    ("sea_water_speed", Some("Currents")),
+    ("wind", Some("Wind")),
+    ("wind_wave", Some("Surface Waves")),
+    ("wave", Some("Surface Waves")),
+    ("sea_surface_wave", Some("Surface Waves")),
+    ("swell", Some("Surface Waves")),
+    ("trough_depth", Some("Surface Waves")),
+    ("sea_surface_height", Some("Sea Level")),
+    ("sea_surface_elevation", Some("Sea Level")),
+    ("water_surface_height", Some("Sea Level")),
+    ("sea_level", Some("Sea Level")),
+    ("tidal", Some("Sea Level")),
+    ("tide", Some("Sea Level")),
+    ("sea_water_density", Some("Physical Oceanography")),
+    ("sea_water_potential_density", Some("Physical Oceanography")),
+    ("sea_water_neutral_density", Some("Physical Oceanography")),
+    ("sea_water_sigma_t", Some("Physical Oceanography")),
+    ("sea_water_sigma_theta", Some("Physical Oceanography")),
+    ("electrical_conductivity", Some("Physical Oceanography")),
+    ("speed_of_sound_in_sea_water", Some("Physical Oceanography")),
+    ("mixed_layer", Some("Physical Oceanography")),
+    ("oxygen", Some("Dissolved O2")),
+    ("molecular_oxygen", Some("Dissolved O2")),
+    ("nitrate", Some("Dissolved Nutrients")),
+    ("nitrite", Some("Dissolved Nutrients")),
+    ("ammonium", Some("Dissolved Nutrients")),
+    ("phosphate", Some("Dissolved Nutrients")),
+    ("silicate", Some("Dissolved Nutrients")),
+    ("nutrient", Some("Dissolved Nutrients")),
+    ("carbon_dioxide", Some("CO2")),
+    ("partial_pressure_of_carbon_dioxide", Some("CO2")),
+    ("ph", Some("CO2")),
+    ("carbonate", Some("CO2")),
+    ("bicarbonate", Some("CO2")),
+    ("alkalinity", Some("CO2")),
+    ("dissolved_inorganic_carbon", Some("CO2")),
+    ("aragonite", Some("CO2")),
+    ("calcite", Some("CO2")),
+    ("chlorophyll", Some("Ocean Color")),
+    ("chlorophyll_a", Some("Ocean Color")),
+    ("expressed_as_chlorophyll", Some("Ocean Color")),
+    ("remote_sensing_reflectance", Some("Ocean Color")),
+    ("water_leaving_radiance", Some("Ocean Color")),
+    (
+        "dissolved_organic_matter",
+        Some("Colored Dissolved Organic Matter"),
+    ),
+    (
+        "colored_dissolved_organic_matter",
+        Some("Colored Dissolved Organic Matter"),
+    ),
+    ("turbidity", Some("Optical Properties")),
+    ("irradiance", Some("Optical Properties")),
+    ("radiance", Some("Optical Properties")),
+    ("reflectance", Some("Optical Properties")),
+    ("attenuation", Some("Optical Properties")),
+    ("optical_thickness", Some("Optical Properties")),
+    ("albedo", Some("Optical Properties")),
+    ("photosynthetic_radiative_flux", Some("Optical Properties")),
+    ("secchi_depth", Some("Optical Properties")),
+    ("latitude", Some("Location")),
+    ("longitude", Some("Location")),
+    ("depth", Some("Location")),
+    ("altitude", Some("Location")),
+    ("projection_x_coordinate", Some("Location")),
+    ("projection_y_coordinate", Some("Location")),
+    ("sea_floor_depth", Some("Bathymetry")),
+    ("bathymetry", Some("Bathymetry")),
+    ("time", Some("Time")),
+    ("heat_flux", Some("Heat Flux")),
+    ("heat_transport", Some("Heat Flux")),
+    ("latent_heat", Some("Heat Flux")),
+    ("sensible_heat", Some("Heat Flux")),
+    ("ice", Some("Ice Distribution")),
+    ("sea_ice", Some("Ice Distribution")),
+    ("river", Some("Hydrology")),
+    ("runoff", Some("Hydrology")),
+    ("groundwater", Some("Hydrology")),
+    ("river_channel", Some("Stream Flow")),
+    ("discharge", Some("Stream Flow")),
+    ("soil", Some("Soils")),
+    (
+        "suspended_particulate_matter",
+        Some("Total Suspended Matter"),
+    ),
+    ("suspended_sediment", Some("Total Suspended Matter")),
+    ("primary_production", Some("Productivity")),
+    ("primary_productivity", Some("Productivity")),
+    ("biomass", Some("Biology")),
+    ("phytoplankton", Some("Biology")),
+    ("zooplankton", Some("Biology")),
+    ("diatoms", Some("Biology")),
+    ("bacteria", Some("Biology")),
+    ("quality_flag", Some("Quality")),
+    ("status_flag", Some("Quality")),
+    ("platform_id", Some("Identifier")),
+    ("platform_name", Some("Identifier")),
+    ("station_id", Some("Identifier")),
+    ("air", Some("Meteorology")),
+    ("air_pressure", Some("Meteorology")),
+    ("atmosphere", Some("Meteorology")),
+    ("humidity", Some("Meteorology")),
+    ("precipitation", Some("Meteorology")),
+    ("rainfall", Some("Meteorology")),
+    ("snowfall", Some("Meteorology")),
+    ("cloud", Some("Meteorology")),
+    ("cloud_ice", Some("Meteorology")),
+    ("visibility", Some("Meteorology")),
+    ("fog", Some("Meteorology")),
+    ("lightning", Some("Meteorology")),
+    ("pressure", Some("Pressure")),
+    ("over_time", None),
+    ("wrt_time", None),
+    ("above_mean_sea_level", None),
+    ("at_mean_sea_level", None),
+];
+
+/// Canonical units that point to a category when nothing in the name does
+const UNIT_HINTS: &[(&str, &str)] = &[
+    ("K", "Temperature"),
+    ("degree_C", "Temperature"),
+    ("Pa", "Pressure"),
+    ("dbar", "Pressure"),
+    ("1e-3", "Salinity"),
+    ("degree_north", "Location"),
+    ("degree_east", "Location"),
+    ("degrees_north", "Location"),
+    ("degrees_east", "Location"),
+    ("W m-2", "Heat Flux"),
+];
+
+/// How much a unit counts compared to a phrase in the name
+const UNIT_WEIGHT: f64 = 0.5;
+
+/// Propose an IOOS category for a standard from the words in its name and its canonical unit
+///
+/// Every standard gets a category, but when nothing points to one,
+/// it is `Unknown` with no confidence.
+pub fn infer_ioos_category(name: &str, unit: &str) -> InferredCategory {
+    let words: Vec<&str> = name.split('_').collect();
+
+    // Every place a phrase is found, as the range of words it covers
+    let mut found = Vec::new();
+    for (phrase, category) in NAME_PHRASES {
+        let phrase_words: Vec<&str> = phrase.split('_').collect();
+        if phrase_words.len() > words.len() {
+            continue;
+        }
+        for start in 0..=words.len() - phrase_words.len() {
+            if words[start..start + phrase_words.len()] == phrase_words[..] {
+                found.push((start, start + phrase_words.len(), *phrase, *category));
+            }
+        }
+    }
+
+    let mut scores: BTreeMap<&str, f64> = BTreeMap::new();
+    let mut evidence = Vec::new();
+
+    for (start, end, phrase, category) in &found {
+        let hidden = found.iter().any(|(other_start, other_end, _, _)| {
+            other_end - other_start > end - start && other_start <= start && end <= other_end
+        });
+        if let (false, Some(category)) = (hidden, category) {
+            *scores.entry(category).or_default() += 1.0;
+            evidence.push(format!("`{phrase}` in name"));
+        }
+    }
+
+    if scores.is_empty() {
+        if let Some((unit, category)) = UNIT_HINTS.iter().find(|(hint, _)| *hint == unit) {
+            scores.insert(category, UNIT_WEIGHT);
+            evidence.push(format!("unit `{unit}`"));
+        }
+    }
+
+    let total: f64 = scores.values().sum();
+    // Highest score, with ties going to the first category alphabetically
+    let best = scores.iter().fold(
+        None,
+        |best: Option<(&str, f64)>, (category, score)| match best {
+            Some((_, best_score)) if best_score >= *score => best,
+            _ => Some((category, *score)),
+        },
+    );
+
+    match best {
+        Some((category, score)) => {
+            // Agreement between the evidence, scaled by how much evidence there is
+            let confidence = (score / total) * (1.0 - 0.25_f64.powf(score));
+            InferredCategory {
+                category: category.to_string(),
+                confidence: (confidence * 100.0).round() / 100.0,
+                evidence,
+            }
+        }
+        None => InferredCategory {
+            category: "Unknown".to_string(),
+            confidence: 0.0,
+            evidence,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StandardsLibrary;
+
+    #[test]
+    fn infers_from_name_phrases() {
+        let salinity = infer_ioos_category("sea_water_practical_salinity", "1");
+        assert_eq!(salinity.category, "Salinity");
+        assert_eq!(salinity.confidence, 0.75);
+        assert_eq!(salinity.evidence, vec!["`salinity` in name"]);
+
+        // The longer phrase wins
+        let air = infer_ioos_category("air_temperature", "K");
+        assert_eq!(air.category, "Meteorology");
+
+        let waves = infer_ioos_category("sea_surface_wind_wave_period", "s");
+        assert_eq!(waves.category, "Surface Waves");
+
+        let tendency = infer_ioos_category("change_over_time_in_sea_water_salinity", "1e-3");
+        assert_eq!(tendency.category, "Salinity");
+    }
+
+    #[test]
+    fn infers_from_units_and_unknown() {
+        let unit_only = infer_ioos_category("something_obscure", "K");
+        assert_eq!(unit_only.category, "Temperature");
+        assert_eq!(unit_only.confidence, 0.5);
+
+        let unknown = infer_ioos_category("something_obscure", "1");
+        assert_eq!(unknown.category, "Unknown");
+        assert_eq!(unknown.confidence, 0.0);
+        assert!(unknown.evidence.is_empty());
+
+        // Disagreeing phrases lower confidence
+        let mixed = infer_ioos_category("sea_water_salinity_at_sea_floor_depth", "1e-3");
+        assert!(mixed.confidence < 0.5);
+    }
+
+    #[test]
+    fn inferred_categories_are_erddap_categories() {
+        let categories = erddap_categories();
+        for (_, category) in NAME_PHRASES {
+            if let Some(category) = category {
+                assert!(categories.contains(category), "{category}");
+            }
+        }
+        for (_, category) in UNIT_HINTS {
+            assert!(categories.contains(category), "{category}");
+        }
+    }
+
+    #[test]
+    fn inferred_categories_mostly_agree_with_knowledge() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+
+        let curated: Vec<_> = library
+            .standards()
+            .values()
+            .filter_map(|standard| {
+                standard
+                    .ioos_category
+                    .as_ref()
+                    .map(|category| (category, standard.infer_ioos_category()))
+            })
+            .collect();
+        let agree = curated
+            .iter()
+            .filter(|(category, inferred)| **category == inferred.category)
+            .count();
+
+        assert!(
+            agree * 10 >= curated.len() * 8,
+            "{agree} of {} agree",
+            curated.len()
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Checks of a variable's attributes against the IOOS Metadata Profile 1.2
+//!
+//! <https://ioos.github.io/ioos-metadata/ioos-metadata-profile-v1-2.html>
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::attr_value::AttrValue;
+use crate::ioos_categories::erddap_categories;
+use crate::standards_library::StandardsLibrary;
+
+/// Controlled values for `coverage_content_type`, from ISO 19115-1 `MD_CoverageContentTypeCode`
+pub const COVERAGE_CONTENT_TYPES: &[&str] = &[
+    "auxiliaryInformation",
+    "coordinate",
+    "image",
+    "modelResult",
+    "physicalMeasurement",
+    "qualityInformation",
+    "referenceInformation",
+    "thematicClassification",
+];
+
+/// How strongly the profile asks for an attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Requirement {
+    Required,
+    Recommended,
+}
+
+impl Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Required => write!(f, "required"),
+            Requirement::Recommended => write!(f, "recommended"),
+        }
+    }
+}
+
+/// What is wrong with an attribute
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileProblem {
+    Missing,
+    /// The value isn't allowed, with why
+    Invalid(String),
+}
+
+/// Attribute that doesn't meet the profile
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileIssue {
+    pub attribute: String,
+    pub requirement: Requirement,
+    pub problem: ProfileProblem,
+}
+
+impl Display for ProfileIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            ProfileProblem::Missing => {
+                write!(
+                    f,
+                    "Missing {} attribute {}",
+                    self.requirement, self.attribute
+                )
+            }
+            ProfileProblem::Invalid(message) => write!(
+                f,
+                "Invalid {} attribute {}: {message}",
+                self.requirement, self.attribute
+            ),
+        }
+    }
+}
+
+/// Check of an attribute's value, returning why it is invalid
+type ValueCheck = fn(&AttrValue, &StandardsLibrary) -> Result<(), String>;
+
+/// Variable attributes in the profile, in the order they are checked
+const PROFILE_ATTRIBUTES: &[(&str, Requirement, ValueCheck)] = &[
+    ("standard_name", Requirement::Required, check_standard_name),
+    ("units", Requirement::Required, check_text),
+    (
+        "coverage_content_type",
+        Requirement::Required,
+        check_coverage_content_type,
+    ),
+    ("long_name", Requirement::Recommended, check_text),
+    ("standard_name_url", Requirement::Recommended, check_url),
+    (
+        "ioos_category",
+        Requirement::Recommended,
+        check_ioos_category,
+    ),
+    ("platform", Requirement::Recommended, check_text),
+    ("instrument", Requirement::Recommended, check_text),
+    ("gts_ingest", Requirement::Recommended, check_gts_ingest),
+    ("_FillValue", Requirement::Recommended, check_number),
+];
+
+fn text(value: &AttrValue) -> Result<&str, String> {
+    match value.as_str() {
+        Some(text) if !text.trim().is_empty() => Ok(text),
+        Some(_) => Err("should not be empty".to_string()),
+        None => Err(format!("`{value}` should be a string")),
+    }
+}
+
+fn check_text(value: &AttrValue, _library: &StandardsLibrary) -> Result<(), String> {
+    text(value).map(|_| ())
+}
+
+fn check_number(value: &AttrValue, _library: &StandardsLibrary) -> Result<(), String> {
+    match value {
+        AttrValue::Int(_) | AttrValue::Float(_) => Ok(()),
+        _ => Err(format!("`{value}` should be a single number")),
+    }
+}
+
+fn check_standard_name(value: &AttrValue, library: &StandardsLibrary) -> Result<(), String> {
+    let name = text(value)?;
+    match library.canonical_name(name) {
+        Some(canonical) if canonical != name => {
+            Err(format!("`{name}` is an alias, use `{canonical}`"))
+        }
+        Some(_) => Ok(()),
+        None => Err(format!("`{name}` is not a known standard name")),
+    }
+}
+
+fn check_coverage_content_type(
+    value: &AttrValue,
+    _library: &StandardsLibrary,
+) -> Result<(), String> {
+    let content_type = text(value)?;
+    if COVERAGE_CONTENT_TYPES.contains(&content_type) {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{content_type}` should be one of {}",
+            COVERAGE_CONTENT_TYPES.join(", ")
+        ))
+    }
+}
+
+fn check_url(value: &AttrValue, _library: &StandardsLibrary) -> Result<(), String> {
+    let url = text(value)?;
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err(format!("`{url}` should be a URL"))
+    }
+}
+
+fn check_ioos_category(value: &AttrValue, _library: &StandardsLibrary) -> Result<(), String> {
+    let category = text(value)?;
+    if erddap_categories().contains(category) {
+        Ok(())
+    } else {
+        Err(format!("`{category}` is not an ERDDAP IOOS category"))
+    }
+}
+
+fn check_gts_ingest(value: &AttrValue, _library: &StandardsLibrary) -> Result<(), String> {
+    match text(value)? {
+        "true" | "false" => Ok(()),
+        other => Err(format!("`{other}` should be `true` or `false`")),
+    }
+}
+
+/// Issues with a variable's attributes, required attributes first
+///
+/// When the standard has QARTOD test suites, `ancillary_variables` is recommended
+/// to link the variable to its flags.
+pub fn check(library: &StandardsLibrary, attrs: &BTreeMap<String, AttrValue>) -> Vec<ProfileIssue> {
+    let mut issues = Vec::new();
+
+    for (attribute, requirement, value_check) in PROFILE_ATTRIBUTES {
+        let problem = match attrs.get(*attribute) {
+            None => ProfileProblem::Missing,
+            Some(value) => match value_check(value, library) {
+                Ok(()) => continue,
+                Err(message) => ProfileProblem::Invalid(message),
+            },
+        };
+        issues.push(ProfileIssue {
+            attribute: attribute.to_string(),
+            requirement: *requirement,
+            problem,
+        });
+    }
+
+    let has_qartod = attrs
+        .get("standard_name")
+        .and_then(AttrValue::as_str)
+        .and_then(|name| library.lookup(name))
+        .is_some_and(|standard| !standard.qartod.is_empty());
+    if has_qartod && !attrs.contains_key("ancillary_variables") {
+        issues.push(ProfileIssue {
+            attribute: "ancillary_variables".to_string(),
+            requirement: Requirement::Recommended,
+            problem: ProfileProblem::Missing,
+        });
+    }
+
+    issues.sort_by_key(|issue| issue.requirement);
+    issues
+}
+
+/// Fill in missing attributes from the knowledge for the variable's `standard_name`
+///
+/// Attributes that are already set are never replaced.
+/// Returns the names of the attributes that were filled in.
+pub fn fill_defaults(
+    library: &StandardsLibrary,
+    attrs: &mut BTreeMap<String, AttrValue>,
+) -> Vec<String> {
+    let Some(standard) = attrs
+        .get("standard_name")
+        .and_then(AttrValue::as_str)
+        .and_then(|name| library.lookup(name))
+    else {
+        return Vec::new();
+    };
+
+    let mut filled = Vec::new();
+    for (name, value) in standard.xarray_attrs() {
+        if !attrs.contains_key(name) {
+            attrs.insert(name.to_string(), value);
+            filled.push(name.to_string());
+        }
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library.load_test_suites();
+        library
+    }
+
+    fn attrs(pairs: &[(&str, AttrValue)]) -> BTreeMap<String, AttrValue> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_missing_and_invalid() {
+        let library = library();
+        let issues = check(
+            &library,
+            &attrs(&[
+                ("standard_name", "sea_water_temprature".into()),
+                ("coverage_content_type", "measurement".into()),
+                ("gts_ingest", "yes".into()),
+                ("_FillValue", AttrValue::Float(-9999.0)),
+            ]),
+        );
+
+        let summary: Vec<String> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "Invalid required attribute standard_name: `sea_water_temprature` is not a known standard name",
+                "Missing required attribute units",
+                "Invalid required attribute coverage_content_type: `measurement` should be one of auxiliaryInformation, coordinate, image, modelResult, physicalMeasurement, qualityInformation, referenceInformation, thematicClassification",
+                "Missing recommended attribute long_name",
+                "Missing recommended attribute standard_name_url",
+                "Missing recommended attribute ioos_category",
+                "Missing recommended attribute platform",
+                "Missing recommended attribute instrument",
+                "Invalid recommended attribute gts_ingest: `yes` should be `true` or `false`",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_alias_standard_names() {
+        let library = library();
+        let issues = check(
+            &library,
+            &attrs(&[("standard_name", "air_pressure_at_sea_level".into())]),
+        );
+
+        assert_eq!(
+            issues[0].to_string(),
+            "Invalid required attribute standard_name: `air_pressure_at_sea_level` is an alias, use `air_pressure_at_mean_sea_level`"
+        );
+    }
+
+    #[test]
+    fn fills_defaults_from_knowledge() {
+        let library = library();
+        let mut variable = attrs(&[
+            (
+                "standard_name",
+                "sea_surface_height_above_geopotential_datum".into(),
+            ),
+            ("units", "ft".into()),
+        ]);
+
+        let filled = fill_defaults(&library, &mut variable);
+        assert!(filled.contains(&"coverage_content_type".to_string()));
+        assert!(!filled.contains(&"units".to_string()));
+        assert_eq!(variable["units"], "ft");
+
+        let issues = check(&library, &variable);
+        assert!(issues
+            .iter()
+            .all(|issue| issue.requirement == Requirement::Recommended));
+        assert!(issues.contains(&ProfileIssue {
+            attribute: "ancillary_variables".to_string(),
+            requirement: Requirement::Recommended,
+            problem: ProfileProblem::Missing,
+        }));
+    }
+}
@@ -2,6 +2,95 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::attr_value::AttrValue;
+use crate::error::{Error, Result};
 use crate::qartod::static_qc::StaticQc;
 
 include!("./knowledge_include.rs");
+
+impl YamlKnowledge {
+    /// Fill in defaults, using `default_name` if the knowledge doesn't name its standard
+    fn into_knowledge(self, default_name: Option<&str>) -> Result<Knowledge> {
+        let name = self
+            .name
+            .or_else(|| default_name.map(str::to_string))
+            .ok_or_else(|| Error::InvalidKnowledge {
+                name: "knowledge list".to_string(),
+                message: "Knowledge in a list needs a `name` of the standard to apply to"
+                    .to_string(),
+            })?;
+
+        Ok(Knowledge {
+            name,
+            long_name: self.long_name,
+            ioos_category: self.ioos_category,
+            common_variable_names: self.common_variable_names.unwrap_or_default(),
+            related_standards: self.related_standards.unwrap_or_default(),
+            sibling_standards: self.sibling_standards.unwrap_or_default(),
+            extra_attrs: self.extra_attrs.unwrap_or_default(),
+            other_units: self.other_units.unwrap_or_default(),
+            comments: self.comments,
+            qc: self.qc,
+        })
+    }
+}
+
+impl Knowledge {
+    /// Parse knowledge from YAML
+    ///
+    /// The YAML can either be a single knowledge mapping, which applies to
+    /// `default_name` (usually the file name) unless it has a `name`,
+    /// or a list of knowledge mappings that each have a `name`.
+    pub fn from_yaml(default_name: &str, contents: &str) -> Result<Vec<Knowledge>> {
+        if let Ok(partial_knowledge) = serde_yaml_ng::from_str::<YamlKnowledge>(contents) {
+            return Ok(vec![partial_knowledge.into_knowledge(Some(default_name))?]);
+        }
+
+        let partial_knowledge: Vec<YamlKnowledge> =
+            serde_yaml_ng::from_str(contents).map_err(|e| Error::InvalidKnowledge {
+                name: default_name.to_string(),
+                message: e.to_string(),
+            })?;
+
+        partial_knowledge
+            .into_iter()
+            .map(|partial_knowledge| partial_knowledge.into_knowledge(None))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knowledge_from_single_yaml() {
+        let knowledge = Knowledge::from_yaml(
+            "air_temperature",
+            "long_name: Air Temperature\nother_units:\n- degC\n",
+        )
+        .unwrap();
+
+        assert_eq!(knowledge.len(), 1);
+        assert_eq!(knowledge[0].name, "air_temperature");
+        assert_eq!(knowledge[0].other_units, vec!["degC"]);
+    }
+
+    #[test]
+    fn knowledge_list_needs_names() {
+        let knowledge =
+            Knowledge::from_yaml("multiple", "- name: air_temperature\n- long_name: Oops\n");
+
+        assert!(matches!(knowledge, Err(Error::InvalidKnowledge { .. })));
+    }
+
+    #[test]
+    fn invalid_knowledge_yaml() {
+        let knowledge = Knowledge::from_yaml("broken", "long_name: [unclosed");
+
+        assert!(matches!(
+            knowledge,
+            Err(Error::InvalidKnowledge { name, .. }) if name == "broken"
+        ));
+    }
+}
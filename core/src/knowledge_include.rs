@@ -20,7 +20,7 @@ pub struct Knowledge {
     pub sibling_standards: Vec<String>,
 
     /// Extra attributes that are usually included in Xarray or NetCDF metadata
-    pub extra_attrs: BTreeMap<String, String>,
+    pub extra_attrs: BTreeMap<String, AttrValue>,
 
     /// Other units that may be seen
     pub other_units: Vec<String>,
@@ -52,7 +52,7 @@ pub struct YamlKnowledge {
     pub sibling_standards: Option<Vec<String>>,
 
     /// Extra attributes that are usually included in Xarray or NetCDF metadata
-    pub extra_attrs: Option<BTreeMap<String, String>>,
+    pub extra_attrs: Option<BTreeMap<String, AttrValue>>,
 
     /// Other units that may be seen
     pub other_units: Option<Vec<String>>,
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use regex::Regex;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::attr_value::AttrValue;
+use crate::error::{Error, Result};
+use crate::query::glob_match;
+use crate::standard::Standard;
+
+/// Which of a standard's knowledge was filled in by rules, rather than curated for it
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleDefaults {
+    /// The IOOS category is from a rule
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub ioos_category: bool,
+
+    /// Extra attributes from rules
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_attrs: Vec<String>,
+
+    /// Other units from rules
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub other_units: Vec<String>,
+}
+
+impl RuleDefaults {
+    /// No knowledge is from rules
+    pub fn is_empty(&self) -> bool {
+        !self.ioos_category && self.extra_attrs.is_empty() && self.other_units.is_empty()
+    }
+}
+
+/// How a knowledge rule picks the standards it applies to
+#[derive(Debug, Clone)]
+pub enum NamePattern {
+    /// `*` matches any run of characters and `?` any one, like `sea_surface_wave_*`
+    Glob(String),
+    /// Regular expression that has to match the whole standard name
+    Regex(Regex),
+}
+
+impl NamePattern {
+    /// Glob pattern, like `*_in_sea_water`
+    pub fn glob(pattern: &str) -> Self {
+        NamePattern::Glob(pattern.to_string())
+    }
+
+    /// Regular expression pattern, like `sea_surface_(swell|wind)_wave_.*`
+    pub fn regex(pattern: &str) -> Result<Self> {
+        Regex::new(&format!("^(?:{pattern})$"))
+            .map(NamePattern::Regex)
+            .map_err(|e| Error::InvalidArgument {
+                argument: "regex".to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    /// Does the pattern match the whole standard name
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NamePattern::Glob(pattern) => {
+                let pattern: Vec<char> = pattern.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                glob_match(&pattern, &name)
+            }
+            NamePattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl Display for NamePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamePattern::Glob(pattern) => write!(f, "{pattern}"),
+            NamePattern::Regex(regex) => {
+                let pattern = regex.as_str();
+                let pattern = &pattern[4..pattern.len() - 2];
+                write!(f, "/{pattern}/")
+            }
+        }
+    }
+}
+
+/// Serialized as written, with regular expressions between slashes
+impl Serialize for NamePattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for NamePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        match pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+        {
+            Some(regex) => NamePattern::regex(regex).map_err(D::Error::custom),
+            None => Ok(NamePattern::glob(&pattern)),
+        }
+    }
+}
+
+/// Default knowledge for every standard with a name matching a pattern
+///
+/// Rules only fill in what a standard is missing,
+/// so knowledge for a specific standard always takes precedence,
+/// as do rules that were applied earlier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeRule {
+    /// Standards the rule applies to
+    pub pattern: NamePattern,
+
+    /// Usual IOOS category for the standards
+    pub ioos_category: Option<String>,
+
+    /// Extra attributes that are usually included in Xarray or NetCDF metadata
+    pub extra_attrs: BTreeMap<String, AttrValue>,
+
+    /// Other units that may be seen
+    pub other_units: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct YamlRule {
+    /// Glob pattern
+    #[serde(rename = "match")]
+    glob: Option<String>,
+    /// Regular expression pattern
+    regex: Option<String>,
+    ioos_category: Option<String>,
+    extra_attrs: Option<BTreeMap<String, AttrValue>>,
+    other_units: Option<Vec<String>>,
+}
+
+impl KnowledgeRule {
+    /// Rule without any defaults yet
+    pub fn new(pattern: NamePattern) -> Self {
+        KnowledgeRule {
+            pattern,
+            ioos_category: None,
+            extra_attrs: BTreeMap::new(),
+            other_units: Vec::new(),
+        }
+    }
+
+    /// Parse a list of rules from YAML
+    ///
+    /// Each rule has either a `match` glob or a `regex`,
+    /// along with the `ioos_category`, `extra_attrs`, and `other_units` to default to.
+    pub fn from_yaml(source: &str, contents: &str) -> Result<Vec<KnowledgeRule>> {
+        let invalid = |message: String| Error::InvalidKnowledge {
+            name: source.to_string(),
+            message,
+        };
+
+        let rules: Vec<YamlRule> =
+            serde_yaml_ng::from_str(contents).map_err(|e| invalid(e.to_string()))?;
+
+        rules
+            .into_iter()
+            .map(|rule| {
+                let pattern = match (rule.glob, rule.regex) {
+                    (Some(glob), None) => NamePattern::glob(&glob),
+                    (None, Some(regex)) => {
+                        NamePattern::regex(&regex).map_err(|e| invalid(e.to_string()))?
+                    }
+                    _ => {
+                        return Err(invalid(
+                            "Each rule needs either a `match` glob or a `regex`".to_string(),
+                        ))
+                    }
+                };
+                Ok(KnowledgeRule {
+                    pattern,
+                    ioos_category: rule.ioos_category,
+                    extra_attrs: rule.extra_attrs.unwrap_or_default(),
+                    other_units: rule.other_units.unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Does the rule apply to a standard name
+    pub fn matches(&self, name: &str) -> bool {
+        self.pattern.matches(name)
+    }
+
+    /// Fill in anything the standard is missing from the rule's defaults
+    pub(crate) fn fill(&self, standard: &mut Standard) {
+        if standard.ioos_category.is_none() && self.ioos_category.is_some() {
+            standard.ioos_category = self.ioos_category.clone();
+            standard.rule_defaults.ioos_category = true;
+        }
+        for (key, value) in &self.extra_attrs {
+            if !standard.extra_attrs.contains_key(key) {
+                standard.extra_attrs.insert(key.clone(), value.clone());
+                standard.rule_defaults.extra_attrs.push(key.clone());
+            }
+        }
+        for unit in &self.other_units {
+            if !standard.other_units.contains(unit) {
+                standard.other_units.push(unit.clone());
+                standard.rule_defaults.other_units.push(unit.clone());
+            }
+        }
+    }
+}
+
+impl Display for KnowledgeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)?;
+        if let Some(category) = &self.ioos_category {
+            write!(f, "\n  IOOS Category: {category}")?;
+        }
+        if !self.other_units.is_empty() {
+            write!(f, "\n  Other units: {}", self.other_units.join(", "))?;
+        }
+        for (key, value) in &self.extra_attrs {
+            write!(f, "\n  {key}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_and_regex_patterns() {
+        let glob = NamePattern::glob("*_in_sea_water");
+        assert!(glob.matches("mole_concentration_of_nitrate_in_sea_water"));
+        assert!(!glob.matches("mole_concentration_of_nitrate_in_sea_water_due_to_x"));
+
+        let regex = NamePattern::regex("sea_surface_(swell|wind)_wave_.*").unwrap();
+        assert!(regex.matches("sea_surface_swell_wave_period"));
+        assert!(!regex.matches("sea_surface_wave_mean_period"));
+        assert!(!regex.matches("not_sea_surface_wind_wave_period"));
+        assert_eq!(regex.to_string(), "/sea_surface_(swell|wind)_wave_.*/");
+
+        assert!(NamePattern::regex("sea_(").is_err());
+
+        let round_trip: NamePattern =
+            serde_json::from_str("\"/sea_surface_(swell|wind)_wave_.*/\"").unwrap();
+        assert!(round_trip.matches("sea_surface_swell_wave_period"));
+        let round_trip: NamePattern = serde_json::from_str("\"*_in_sea_water\"").unwrap();
+        assert_eq!(round_trip.to_string(), "*_in_sea_water");
+    }
+
+    #[test]
+    fn rules_from_yaml() {
+        let rules = KnowledgeRule::from_yaml(
+            "rules",
+            "- match: sea_surface_wave_*\n  ioos_category: Surface Waves\n- regex: .*_in_air\n  other_units: [percent]\n",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].ioos_category.as_deref(), Some("Surface Waves"));
+        assert!(rules[1].matches("visibility_in_air"));
+
+        for invalid in [
+            "- ioos_category: Other\n",
+            "- match: x\n  regex: y\n",
+            "- match: x\n  long_name: Not a default\n",
+        ] {
+            assert!(matches!(
+                KnowledgeRule::from_yaml("rules", invalid),
+                Err(Error::InvalidKnowledge { name, .. }) if name == "rules"
+            ));
+        }
+    }
+
+    #[test]
+    fn rules_only_fill_missing() {
+        let mut rule = KnowledgeRule::new(NamePattern::glob("*"));
+        rule.ioos_category = Some("Other".to_string());
+        rule.extra_attrs
+            .insert("coverage_content_type".into(), "modelResult".into());
+        rule.extra_attrs.insert("ncei_name".into(), "X".into());
+        rule.other_units = vec!["degC".to_string(), "F".to_string()];
+
+        let mut standard = Standard {
+            ioos_category: Some("Temperature".to_string()),
+            extra_attrs: BTreeMap::from([(
+                "coverage_content_type".to_string(),
+                "physicalMeasurement".into(),
+            )]),
+            other_units: vec!["F".to_string()],
+            ..Default::default()
+        };
+        rule.fill(&mut standard);
+
+        assert_eq!(standard.ioos_category.as_deref(), Some("Temperature"));
+        assert_eq!(
+            standard.extra_attrs["coverage_content_type"],
+            "physicalMeasurement"
+        );
+        assert_eq!(standard.extra_attrs["ncei_name"], "X");
+        assert_eq!(standard.other_units, vec!["F", "degC"]);
+        assert_eq!(
+            standard.rule_defaults,
+            RuleDefaults {
+                ioos_category: false,
+                extra_attrs: vec!["ncei_name".to_string()],
+                other_units: vec!["degC".to_string()],
+            }
+        );
+        assert!(standard.has_knowledge());
+
+        let mut family = Standard::default();
+        rule.fill(&mut family);
+        assert_eq!(family.ioos_category.as_deref(), Some("Other"));
+        assert!(!family.has_knowledge());
+        assert_eq!(family.without_rule_defaults(), Standard::default());
+    }
+}
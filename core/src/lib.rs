@@ -1,14 +1,46 @@
+pub mod attr_value;
+pub mod cdl;
 pub mod cf;
+pub mod check;
+pub mod diff;
+pub mod erddap;
+pub mod error;
+pub mod grammar;
+pub mod graph;
+pub mod header;
 pub mod ioos_categories;
+pub mod ioos_profile;
 pub mod knowledge;
+pub mod knowledge_rules;
 pub mod library_knowledge;
+pub mod netcdf3;
 pub mod qartod;
+pub mod query;
+pub mod ranking;
+pub mod search_index;
 pub mod standard;
 pub mod standards_filter;
 pub mod standards_library;
+pub mod suggest;
+pub mod upgrade;
 
+pub use attr_value::AttrValue;
+pub use check::{Finding, Severity, VariableCheck};
+pub use diff::LibraryDiff;
+pub use erddap::{DatasetColumn, DatasetMetadata, ErddapDataset, ErddapDatasetType};
+pub use error::{Error, Result};
+pub use grammar::{ComposedName, NameParts, Transformation};
+pub use graph::{Edge, Reached, Relationship, StandardsGraph};
+pub use header::{Dimension, HeaderVariable, NetcdfHeader};
+pub use ioos_categories::InferredCategory;
+pub use ioos_profile::{ProfileIssue, ProfileProblem, Requirement};
 pub use knowledge::Knowledge;
 pub use knowledge::YamlKnowledge;
+pub use knowledge_rules::{KnowledgeRule, NamePattern, RuleDefaults};
+pub use query::Query;
+pub use ranking::{FieldMatch, SearchField, SearchResult};
 pub use standard::Standard;
-pub use standards_filter::StandardsFilter;
+pub use standards_filter::{SortKey, StandardsFilter};
 pub use standards_library::StandardsLibrary;
+pub use suggest::UnknownStandard;
+pub use upgrade::ResolvedStandard;
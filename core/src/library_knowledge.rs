@@ -1,18 +1,29 @@
 use flate2::read::GzDecoder;
 use std::io::Read;
 
+use crate::error::{Error, Result};
 use crate::knowledge::Knowledge;
+use crate::knowledge_rules::KnowledgeRule;
+
+pub fn load_knowledge() -> Result<Vec<Knowledge>> {
+    let invalid = |e: &dyn std::fmt::Display| Error::InvalidKnowledge {
+        name: "built-in knowledge".to_string(),
+        message: e.to_string(),
+    };
 
-pub fn load_knowledge() -> Vec<Knowledge> {
     let compressed_data = include_bytes!(concat!(env!("OUT_DIR"), "/knowledge.yaml.gz"));
 
     // Decompress the data
     let mut decoder = GzDecoder::new(&compressed_data[..]);
     let mut yaml_data = String::new();
-    decoder.read_to_string(&mut yaml_data).unwrap();
+    decoder
+        .read_to_string(&mut yaml_data)
+        .map_err(|e| invalid(&e))?;
 
     // Deserialize from YAML
-    let knowledge: Vec<Knowledge> = serde_yaml_ng::from_str(&yaml_data).unwrap();
+    serde_yaml_ng::from_str(&yaml_data).map_err(|e| invalid(&e))
+}
 
-    knowledge
+pub fn load_rules() -> Result<Vec<KnowledgeRule>> {
+    KnowledgeRule::from_yaml("built-in rules", include_str!("../standards/_rules.yaml"))
 }
@@ -2,7 +2,9 @@ use standard_knowledge::standards_library::StandardsLibrary;
 
 fn main() {
     let mut library = StandardsLibrary::default();
-    library.load_cf_standards();
+    library
+        .load_cf_standards()
+        .expect("Built-in CF standards should load");
     println!(
         "By name: {:?}",
         &library.get("air_pressure_at_mean_sea_level")
@@ -0,0 +1,504 @@
+//! Reader for the headers of NetCDF-3 classic and 64-bit offset files,
+//! without needing the NetCDF C library
+//!
+//! The header is everything before the data, so only the start of a file is needed.
+//! <https://docs.unidata.ucar.edu/netcdf-c/current/file_format_specifications.html>
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::Read;
+
+use crate::attr_value::AttrValue;
+use crate::error::{Error, Result};
+use crate::header::{Dimension, HeaderVariable, NetcdfHeader};
+
+/// Magic bytes that start a NetCDF-3 file, before the version byte
+pub const MAGIC: &[u8; 3] = b"CDF";
+
+/// How much of a file to read for its header at first, doubled until the header fits
+const INITIAL_PREFIX: usize = 64 * 1024;
+
+/// Largest header that is read, far more than the dimensions, variables,
+/// and attributes of real files take
+const MAX_HEADER: usize = 16 * 1024 * 1024;
+
+/// Version byte of classic files
+const CLASSIC: u8 = 1;
+
+/// Version byte of 64-bit offset files
+const OFFSET_64BIT: u8 = 2;
+
+/// Version byte of 64-bit data (CDF-5) files
+const DATA_64BIT: u8 = 5;
+
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+
+/// Whether bytes start like a NetCDF-3 file of any version
+pub fn is_netcdf3(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Type of an attribute or variable, by its `nc_type` number
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NcType {
+    Byte,
+    Char,
+    Short,
+    Int,
+    Float,
+    Double,
+}
+
+impl NcType {
+    fn size(self) -> usize {
+        match self {
+            NcType::Byte | NcType::Char => 1,
+            NcType::Short => 2,
+            NcType::Int | NcType::Float => 4,
+            NcType::Double => 8,
+        }
+    }
+
+    /// CDL type name, as `ncdump` writes it
+    fn cdl_name(self) -> &'static str {
+        match self {
+            NcType::Byte => "byte",
+            NcType::Char => "char",
+            NcType::Short => "short",
+            NcType::Int => "int",
+            NcType::Float => "float",
+            NcType::Double => "double",
+        }
+    }
+}
+
+/// Big-endian cursor over the header bytes
+struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    version: u8,
+
+    /// Whether the header ran past the end of the bytes, so more of the file is needed
+    truncated: bool,
+}
+
+impl<'a> HeaderReader<'a> {
+    fn error(&self, message: impl Display) -> Error {
+        Error::parse("NetCDF", format!("byte {}: {message}", self.position))
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len());
+        let Some(end) = end else {
+            self.truncated = true;
+            return Err(self.error("header is truncated"));
+        };
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    /// Take a number of bytes, then skip the padding to the next 4 byte boundary
+    fn take_padded(&mut self, length: usize) -> Result<&'a [u8]> {
+        let taken = self.take(length)?;
+        self.take((4 - length % 4) % 4)?;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Count of elements, checked against the bytes left so that a corrupt
+    /// count can't ask for more memory than the file could hold
+    ///
+    /// Counts that no header could hold are corrupt, rather than a sign
+    /// that more of the file is needed.
+    fn count(&mut self, element_size: usize) -> Result<usize> {
+        let count = self.u32()? as usize;
+        let size = count.saturating_mul(element_size);
+        if size > MAX_HEADER {
+            return Err(self.error(format!("count of {count} is too large for a header")));
+        }
+        if size > self.bytes.len() - self.position {
+            self.truncated = true;
+            return Err(self.error(format!("count of {count} is larger than the header")));
+        }
+        Ok(count)
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let length = self.count(1)?;
+        let name = self.take_padded(length)?;
+        String::from_utf8(name.to_vec()).map_err(|_| self.error("name is not UTF-8"))
+    }
+
+    fn nc_type(&mut self) -> Result<NcType> {
+        Ok(match self.u32()? {
+            1 => NcType::Byte,
+            2 => NcType::Char,
+            3 => NcType::Short,
+            4 => NcType::Int,
+            5 => NcType::Float,
+            6 => NcType::Double,
+            other => return Err(self.error(format!("unknown type {other}"))),
+        })
+    }
+
+    /// Number of elements in a list with the given tag, which may be absent
+    fn list(&mut self, tag: u32, element_size: usize) -> Result<usize> {
+        let found = self.u32()?;
+        let count = self.count(element_size)?;
+        match found {
+            0 if count == 0 => Ok(0),
+            found if found == tag => Ok(count),
+            found => Err(self.error(format!("expected list tag {tag:#x}, found {found:#x}"))),
+        }
+    }
+
+    fn dimensions(&mut self) -> Result<Vec<Dimension>> {
+        (0..self.list(NC_DIMENSION, 8)?)
+            .map(|_| {
+                let name = self.name()?;
+                let length = self.u32()? as usize;
+                Ok(Dimension {
+                    name,
+                    length: (length != 0).then_some(length),
+                })
+            })
+            .collect()
+    }
+
+    fn attributes(&mut self) -> Result<BTreeMap<String, AttrValue>> {
+        (0..self.list(NC_ATTRIBUTE, 12)?)
+            .map(|_| {
+                let name = self.name()?;
+                let nc_type = self.nc_type()?;
+                let count = self.count(nc_type.size())?;
+                let values = self.take_padded(count * nc_type.size())?;
+                Ok((name, attr_value(nc_type, values)))
+            })
+            .collect()
+    }
+
+    fn variables(&mut self, dimensions: &[Dimension]) -> Result<Vec<HeaderVariable>> {
+        (0..self.list(NC_VARIABLE, 16)?)
+            .map(|_| {
+                let name = self.name()?;
+                let dimension_ids = (0..self.count(4)?)
+                    .map(|_| self.u32())
+                    .collect::<Result<Vec<_>>>()?;
+                let dimensions = dimension_ids
+                    .into_iter()
+                    .map(|id| {
+                        dimensions
+                            .get(id as usize)
+                            .map(|dimension| dimension.name.clone())
+                            .ok_or_else(|| self.error(format!("no dimension with id {id}")))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let attrs = self.attributes()?;
+                let data_type = self.nc_type()?.cdl_name().to_string();
+
+                // The size and where the data begins aren't needed for metadata
+                let begin_size = if self.version == OFFSET_64BIT { 8 } else { 4 };
+                self.take(4 + begin_size)?;
+
+                Ok(HeaderVariable {
+                    name,
+                    data_type,
+                    dimensions,
+                    attrs,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Attribute value from its big-endian bytes
+///
+/// Text has trailing nulls removed, and a single number is a scalar rather than a list.
+fn attr_value(nc_type: NcType, bytes: &[u8]) -> AttrValue {
+    let chunks = bytes.chunks_exact(nc_type.size());
+    let ints = |ints: Vec<i64>| match ints.as_slice() {
+        [int] => AttrValue::Int(*int),
+        _ => AttrValue::Ints(ints),
+    };
+    let floats = |floats: Vec<f64>| match floats.as_slice() {
+        [float] => AttrValue::Float(*float),
+        _ => AttrValue::Floats(floats),
+    };
+
+    match nc_type {
+        NcType::Char => AttrValue::Text(
+            String::from_utf8_lossy(bytes)
+                .trim_end_matches('\0')
+                .to_string(),
+        ),
+        NcType::Byte => ints(bytes.iter().map(|byte| *byte as i8 as i64).collect()),
+        NcType::Short => ints(
+            chunks
+                .map(|chunk| i16::from_be_bytes([chunk[0], chunk[1]]) as i64)
+                .collect(),
+        ),
+        NcType::Int => ints(
+            chunks
+                .map(|chunk| i32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as i64)
+                .collect(),
+        ),
+        NcType::Float => floats(
+            chunks
+                .map(|chunk| f32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as f64)
+                .collect(),
+        ),
+        NcType::Double => floats(
+            chunks
+                .map(|chunk| {
+                    f64::from_be_bytes([
+                        chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6],
+                        chunk[7],
+                    ])
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Read the dimensions, variables, and attributes from the header of a NetCDF-3 file
+///
+/// Only the bytes up to the end of the header are needed.
+pub fn read_header(bytes: &[u8]) -> Result<NetcdfHeader> {
+    parse_header(bytes).0
+}
+
+/// Read the header of a NetCDF-3 file from the start of a reader, such as an upload
+///
+/// Rather than reading the whole file, 64 KiB is read at first,
+/// then twice as much each time the header doesn't fit,
+/// up to the end of the file or 16 MiB.
+pub fn read_header_from(mut reader: impl Read) -> Result<NetcdfHeader> {
+    let mut bytes = Vec::new();
+    let mut prefix = INITIAL_PREFIX;
+    loop {
+        let wanted = prefix - bytes.len();
+        let read = reader
+            .by_ref()
+            .take(wanted as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::io("NetCDF", e))?;
+
+        match parse_header(&bytes) {
+            (Err(_), true) if read == wanted && prefix < MAX_HEADER => {
+                prefix = (prefix * 2).min(MAX_HEADER);
+            }
+            (Err(_), true) if read == wanted => {
+                return Err(Error::parse(
+                    "NetCDF",
+                    format!("header is larger than {} MiB", MAX_HEADER / 1024 / 1024),
+                ))
+            }
+            (result, _) => return result,
+        }
+    }
+}
+
+/// Header, and whether reading it ran past the end of the bytes
+fn parse_header(bytes: &[u8]) -> (Result<NetcdfHeader>, bool) {
+    let version = match bytes {
+        [b'C', b'D', b'F', version, ..] => *version,
+        _ => return (Err(Error::parse("NetCDF", "not a NetCDF-3 file")), false),
+    };
+    let unsupported = match version {
+        CLASSIC | OFFSET_64BIT => None,
+        DATA_64BIT => Some("64-bit data (CDF-5) files are not supported".to_string()),
+        other => Some(format!("unknown NetCDF-3 version {other}")),
+    };
+    if let Some(message) = unsupported {
+        return (Err(Error::parse("NetCDF", message)), false);
+    }
+
+    let mut reader = HeaderReader {
+        bytes,
+        position: 4,
+        version,
+        truncated: false,
+    };
+    let header = reader.header();
+    (header, reader.truncated)
+}
+
+impl HeaderReader<'_> {
+    fn header(&mut self) -> Result<NetcdfHeader> {
+        // Number of records, which isn't part of the metadata
+        self.u32()?;
+
+        let dimensions = self.dimensions()?;
+        let global_attrs = self.attributes()?;
+        let variables = self.variables(&dimensions)?;
+
+        Ok(NetcdfHeader {
+            name: None,
+            dimensions,
+            variables,
+            global_attrs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a header the way the NetCDF library lays it out
+    #[derive(Default)]
+    struct Writer(Vec<u8>);
+
+    impl Writer {
+        fn u32(&mut self, value: u32) -> &mut Self {
+            self.0.extend(value.to_be_bytes());
+            self
+        }
+
+        fn padded(&mut self, bytes: &[u8]) -> &mut Self {
+            self.0.extend(bytes);
+            self.0.resize(self.0.len().next_multiple_of(4), 0);
+            self
+        }
+
+        fn name(&mut self, name: &str) -> &mut Self {
+            self.u32(name.len() as u32).padded(name.as_bytes())
+        }
+
+        fn text_attr(&mut self, name: &str, text: &str) -> &mut Self {
+            self.name(name)
+                .u32(2)
+                .u32(text.len() as u32)
+                .padded(text.as_bytes())
+        }
+    }
+
+    fn header(version: u8) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.padded(&[b'C', b'D', b'F', version]).u32(24);
+
+        writer.u32(NC_DIMENSION).u32(2);
+        writer.name("time").u32(0);
+        writer.name("name_strlen").u32(8);
+
+        writer.u32(NC_ATTRIBUTE).u32(1);
+        writer.text_attr("Conventions", "CF-1.6");
+
+        writer.u32(NC_VARIABLE).u32(2);
+        writer.name("station_name").u32(1).u32(1);
+        writer.u32(0).u32(0).u32(2).u32(8);
+        writer.0.extend(vec![0; if version == 1 { 4 } else { 8 }]);
+
+        writer.name("temp").u32(1).u32(0);
+        writer.u32(NC_ATTRIBUTE).u32(3);
+        writer.text_attr("standard_name", "sea_water_temperature\0");
+        writer.name("_FillValue").u32(5).u32(1);
+        writer.0.extend((-9999.0f32).to_be_bytes());
+        writer
+            .name("flag_values")
+            .u32(1)
+            .u32(3)
+            .padded(&[1, 2, 0xFF]);
+        writer.u32(5).u32(4);
+        writer.0.extend(vec![0; if version == 1 { 4 } else { 8 }]);
+
+        writer.0
+    }
+
+    #[test]
+    fn reads_classic_and_64bit_offset() {
+        for version in [CLASSIC, OFFSET_64BIT] {
+            let header = read_header(&header(version)).unwrap();
+
+            assert_eq!(header.name, None);
+            assert_eq!(
+                header.dimensions,
+                vec![
+                    Dimension {
+                        name: "time".to_string(),
+                        length: None
+                    },
+                    Dimension {
+                        name: "name_strlen".to_string(),
+                        length: Some(8)
+                    },
+                ]
+            );
+            assert_eq!(header.global_attrs["Conventions"], "CF-1.6");
+
+            let station = header.variable("station_name").unwrap();
+            assert_eq!(station.data_type, "char");
+            assert_eq!(station.dimensions, vec!["name_strlen"]);
+            assert!(station.attrs.is_empty());
+
+            let temp = header.variable("temp").unwrap();
+            assert_eq!(temp.data_type, "float");
+            assert_eq!(temp.dimensions, vec!["time"]);
+            assert_eq!(
+                temp.attr_str("standard_name"),
+                Some("sea_water_temperature")
+            );
+            assert_eq!(temp.attrs["_FillValue"], AttrValue::Float(-9999.0));
+            assert_eq!(temp.attrs["flag_values"], AttrValue::Ints(vec![1, 2, -1]));
+        }
+    }
+
+    #[test]
+    fn reads_headers_larger_than_the_first_prefix() {
+        let mut writer = Writer::default();
+        writer.padded(b"CDF\x01").u32(0);
+        writer.u32(0).u32(0);
+        writer.u32(NC_ATTRIBUTE).u32(1);
+        writer.text_attr("history", &"x".repeat(INITIAL_PREFIX * 3));
+        writer.u32(0).u32(0);
+        // Data after the header that shouldn't be read
+        writer.0.extend(vec![0; INITIAL_PREFIX * 4]);
+
+        let mut upload = writer.0.as_slice();
+        let header = read_header_from(&mut upload).unwrap();
+        assert!(!upload.is_empty());
+        assert_eq!(
+            header.global_attrs["history"].as_str().map(str::len),
+            Some(INITIAL_PREFIX * 3)
+        );
+    }
+
+    #[test]
+    fn corrupt_counts_stop_reading() {
+        let mut writer = Writer::default();
+        writer.padded(b"CDF\x01").u32(0);
+        writer.u32(0).u32(0);
+        writer.u32(NC_ATTRIBUTE).u32(u32::MAX);
+        writer.0.extend(vec![0; INITIAL_PREFIX * 4]);
+
+        let mut upload = writer.0.as_slice();
+        let error = read_header_from(&mut upload).unwrap_err();
+        assert!(error.to_string().ends_with("is too large for a header"));
+        assert_eq!(upload.len(), writer.0.len() - INITIAL_PREFIX);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let truncated = header(CLASSIC);
+        let error = read_header(&truncated[..truncated.len() - 4]).unwrap_err();
+        assert!(error.to_string().ends_with("header is truncated"));
+        assert!(read_header(&truncated[..60]).is_err());
+
+        assert!(read_header(b"\x89HDF\r\n\x1a\n").is_err());
+        assert!(read_header_from(&truncated[..truncated.len() - 4]).is_err());
+        assert_eq!(
+            read_header(b"CDF\x05").unwrap_err().to_string(),
+            "Unable to parse NetCDF: 64-bit data (CDF-5) files are not supported"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! CF ancillary flag variables for QARTOD results, following the IOOS QARTOD flag conventions
+//!
+//! Each variable with QARTOD tests gets an aggregate flag variable named `{variable}_qc_agg`,
+//! and can have a flag variable for each test, named `{variable}_qartod_{test}_test`
+//! as `ioos_qc` writes them.
+//! The parent variable lists them in its `ancillary_variables` attribute.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::types::QartodTestTypes;
+use crate::attr_value::AttrValue;
+
+/// Suffix for the aggregate flag variable that goes with a variable
+pub const QC_AGG_SUFFIX: &str = "_qc_agg";
+
+/// QARTOD flag values, in the same order as [`FLAG_MEANINGS`]
+pub const FLAG_VALUES: [i64; 5] = [1, 2, 3, 4, 9];
+
+/// QARTOD flag meanings, in the same order as [`FLAG_VALUES`]
+pub const FLAG_MEANINGS: &str = "PASS NOT_EVALUATED SUSPECT FAIL MISSING";
+
+/// Where the QARTOD flags and tests are described
+pub const QARTOD_REFERENCES: &str = "https://ioos.noaa.gov/project/qartod/";
+
+/// Flag variable for the QARTOD results of a variable
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct QcFlagVariable {
+    pub name: String,
+
+    /// Test the flag is for, or `None` for the aggregate flag
+    pub test_type: Option<QartodTestTypes>,
+
+    /// `standard_name`, `long_name`, `flag_values`, `flag_meanings`, and `references`
+    pub attrs: BTreeMap<String, AttrValue>,
+}
+
+impl QcFlagVariable {
+    /// Aggregate flag, which combines the results of all the tests
+    pub fn aggregate(variable_name: &str, long_name: &str) -> Self {
+        Self::new(
+            format!("{variable_name}{QC_AGG_SUFFIX}"),
+            None,
+            format!("{long_name} Aggregate Quality Flag"),
+            "aggregate_quality_flag",
+        )
+    }
+
+    /// Flag for the results of a single test
+    pub fn for_test(test_type: &QartodTestTypes, variable_name: &str, long_name: &str) -> Self {
+        Self::new(
+            format!("{variable_name}_qartod_{}_test", test_type.slug()),
+            Some(test_type.clone()),
+            format!("{long_name} {test_type} Test Quality Flag"),
+            test_type.flag_standard_name(),
+        )
+    }
+
+    fn new(
+        name: String,
+        test_type: Option<QartodTestTypes>,
+        long_name: String,
+        standard_name: &str,
+    ) -> Self {
+        let attrs = BTreeMap::from([
+            ("flag_meanings".to_string(), FLAG_MEANINGS.into()),
+            ("flag_values".to_string(), FLAG_VALUES.to_vec().into()),
+            ("long_name".to_string(), long_name.into()),
+            ("references".to_string(), QARTOD_REFERENCES.into()),
+            ("standard_name".to_string(), standard_name.into()),
+        ]);
+        QcFlagVariable {
+            name,
+            test_type,
+            attrs,
+        }
+    }
+}
+
+/// Flag variables for a variable, and the `ancillary_variables` attribute that links them
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct QcFlags {
+    pub variable_name: String,
+
+    /// Names of the flag variables, separated by spaces, for the parent variable
+    pub ancillary_variables: String,
+
+    /// Aggregate flag first, then a flag for each test
+    pub variables: Vec<QcFlagVariable>,
+}
+
+impl QcFlags {
+    /// Aggregate flag and a flag for each distinct test type
+    pub fn new<'a>(
+        variable_name: &str,
+        long_name: &str,
+        test_types: impl IntoIterator<Item = &'a QartodTestTypes>,
+    ) -> Self {
+        let mut variables = vec![QcFlagVariable::aggregate(variable_name, long_name)];
+        for test_type in test_types {
+            if !variables
+                .iter()
+                .any(|variable| variable.test_type.as_ref() == Some(test_type))
+            {
+                variables.push(QcFlagVariable::for_test(
+                    test_type,
+                    variable_name,
+                    long_name,
+                ));
+            }
+        }
+
+        QcFlags {
+            variable_name: variable_name.to_string(),
+            ancillary_variables: variables
+                .iter()
+                .map(|variable| variable.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            variables,
+        }
+    }
+
+    /// Aggregate flag variable
+    pub fn aggregate(&self) -> &QcFlagVariable {
+        &self.variables[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_and_per_test_flags() {
+        let flags = QcFlags::new(
+            "temp",
+            "Sea Water Temperature",
+            &[
+                QartodTestTypes::GrossRange,
+                QartodTestTypes::NearestNeighbor,
+                QartodTestTypes::GrossRange,
+            ],
+        );
+
+        assert_eq!(
+            flags.ancillary_variables,
+            "temp_qc_agg temp_qartod_gross_range_test temp_qartod_nearest_neighbor_test"
+        );
+
+        let aggregate = flags.aggregate();
+        assert_eq!(aggregate.test_type, None);
+        assert_eq!(aggregate.attrs["standard_name"], "aggregate_quality_flag");
+        assert_eq!(
+            aggregate.attrs["long_name"],
+            "Sea Water Temperature Aggregate Quality Flag"
+        );
+        assert_eq!(
+            aggregate.attrs["flag_values"],
+            AttrValue::Ints(vec![1, 2, 3, 4, 9])
+        );
+        assert_eq!(aggregate.attrs["flag_meanings"], FLAG_MEANINGS);
+        assert_eq!(aggregate.attrs["references"], QARTOD_REFERENCES);
+
+        let gross_range = &flags.variables[1];
+        assert_eq!(gross_range.test_type, Some(QartodTestTypes::GrossRange));
+        assert_eq!(
+            gross_range.attrs["standard_name"],
+            "gross_range_test_quality_flag"
+        );
+        assert_eq!(
+            gross_range.attrs["long_name"],
+            "Sea Water Temperature Gross Range Test Quality Flag"
+        );
+        assert_eq!(
+            flags.variables[2].attrs["standard_name"],
+            "neighbor_test_quality_flag"
+        );
+    }
+}
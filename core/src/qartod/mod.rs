@@ -4,10 +4,12 @@ use std::collections::HashMap;
 // pub use config::{ArgumentValue, Call, Config, ConfigBuilder, Context, ContextConfig, TimeWindow};
 
 pub mod config;
+pub mod flags;
 pub mod static_qc;
 pub mod types;
 pub mod water_level;
 
+pub use flags::{QcFlagVariable, QcFlags};
 pub use static_qc::StaticQcTestSuite;
 pub use types::TestSuite;
 
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 
 use super::config::ConfigStream;
 use super::types::{QartodTestTypes, TestSuite, TestSuiteInfo};
+use crate::error::Result;
 
 include!("./static_qc_include.rs");
 
@@ -28,7 +29,11 @@ impl TestSuite for StaticQcTestSuite {
     fn scaffold(
         &self,
         _arguments: std::collections::HashMap<String, super::types::ArgumentValue>,
-    ) -> Result<ConfigStream, String> {
+    ) -> Result<ConfigStream> {
         Ok(self.qc.tests.clone())
     }
+
+    fn static_qc(&self) -> Option<&StaticQc> {
+        Some(&self.qc)
+    }
 }
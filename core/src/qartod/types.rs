@@ -1,16 +1,30 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::str::FromStr;
 
 use dyn_clone::DynClone;
+use serde::{Deserialize, Serialize, Serializer};
 
 use super::config::ConfigStream;
+use super::flags::QcFlags;
+use super::static_qc::StaticQc;
+use crate::error::{Error, Result};
+
+/// Serialize a map sorted by key, so output is stable
+fn sorted_map<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
+}
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TestSuiteInfo {
     pub name: String,
     pub slug: String,
     pub summary: String,
     pub description: String,
+    #[serde(serialize_with = "sorted_map")]
     pub arguments: HashMap<String, TestArgument>,
     pub test_types: Vec<QartodTestTypes>,
 }
@@ -50,14 +64,15 @@ impl TestSuiteInfo {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TestArgument {
     pub argument_type: ArgumentType,
     pub description: String,
     pub required: bool,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ArgumentType {
     String,
     Bool,
@@ -66,13 +81,19 @@ pub enum ArgumentType {
 }
 
 impl ArgumentType {
-    pub fn value_type(&self, value: &str) -> ArgumentValue {
-        match self {
+    /// Parse a string value for the named argument into this type
+    pub fn value_type(&self, argument: &str, value: &str) -> Result<ArgumentValue> {
+        let invalid = |e: &dyn Display| Error::InvalidArgument {
+            argument: argument.to_string(),
+            message: format!("`{value}` is not a valid {self:?}: {e}"),
+        };
+
+        Ok(match self {
             ArgumentType::String => ArgumentValue::String(value.to_string()),
-            ArgumentType::Bool => ArgumentValue::Bool(value.parse().unwrap_or_default()),
-            ArgumentType::Int => ArgumentValue::Int(value.parse().unwrap_or_default()),
-            ArgumentType::Float => ArgumentValue::Float(value.parse().unwrap_or_default()),
-        }
+            ArgumentType::Bool => ArgumentValue::Bool(value.parse().map_err(|e| invalid(&e))?),
+            ArgumentType::Int => ArgumentValue::Int(value.parse().map_err(|e| invalid(&e))?),
+            ArgumentType::Float => ArgumentValue::Float(value.parse().map_err(|e| invalid(&e))?),
+        })
     }
 }
 
@@ -84,7 +105,8 @@ pub enum ArgumentValue {
     Float(f64),
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum QartodTestTypes {
     Location,
     GrossRange,
@@ -98,6 +120,38 @@ pub enum QartodTestTypes {
 }
 
 impl QartodTestTypes {
+    /// Name of the test as `ioos_qc` uses it, without the `_test` suffix
+    pub fn slug(&self) -> &'static str {
+        match self {
+            QartodTestTypes::Location => "location",
+            QartodTestTypes::GrossRange => "gross_range",
+            QartodTestTypes::Climatology => "climatology",
+            QartodTestTypes::Spike => "spike",
+            QartodTestTypes::RateOfChange => "rate_of_change",
+            QartodTestTypes::FlatLine => "flat_line",
+            QartodTestTypes::AttenuatedSignal => "attenuated_signal",
+            QartodTestTypes::DensityInversion => "density_inversion",
+            QartodTestTypes::NearestNeighbor => "nearest_neighbor",
+        }
+    }
+
+    /// CF standard name for a flag variable with the results of the test
+    ///
+    /// The density inversion test doesn't have its own, so it uses the generic `quality_flag`.
+    pub fn flag_standard_name(&self) -> &'static str {
+        match self {
+            QartodTestTypes::Location => "location_test_quality_flag",
+            QartodTestTypes::GrossRange => "gross_range_test_quality_flag",
+            QartodTestTypes::Climatology => "climatology_test_quality_flag",
+            QartodTestTypes::Spike => "spike_test_quality_flag",
+            QartodTestTypes::RateOfChange => "rate_of_change_test_quality_flag",
+            QartodTestTypes::FlatLine => "flat_line_test_quality_flag",
+            QartodTestTypes::AttenuatedSignal => "attenuated_signal_test_quality_flag",
+            QartodTestTypes::DensityInversion => "quality_flag",
+            QartodTestTypes::NearestNeighbor => "neighbor_test_quality_flag",
+        }
+    }
+
     pub fn tests_in_config(config: &ConfigStream) -> Vec<QartodTestTypes> {
         let mut test_types = Vec::new();
         if config.qartod.gross_range_test.is_some() {
@@ -119,6 +173,38 @@ impl QartodTestTypes {
     }
 }
 
+impl FromStr for QartodTestTypes {
+    type Err = Error;
+
+    /// Parse a test type from its name, like `gross_range`, `Gross Range`, or `gross_range_test`
+    fn from_str(test_type: &str) -> Result<Self> {
+        let normalized: String = test_type
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect();
+        let normalized = normalized.strip_suffix("test").unwrap_or(&normalized);
+
+        Ok(match normalized {
+            "location" => QartodTestTypes::Location,
+            "grossrange" => QartodTestTypes::GrossRange,
+            "climatology" => QartodTestTypes::Climatology,
+            "spike" => QartodTestTypes::Spike,
+            "rateofchange" => QartodTestTypes::RateOfChange,
+            "flatline" => QartodTestTypes::FlatLine,
+            "attenuatedsignal" => QartodTestTypes::AttenuatedSignal,
+            "densityinversion" => QartodTestTypes::DensityInversion,
+            "nearestneighbor" => QartodTestTypes::NearestNeighbor,
+            _ => {
+                return Err(Error::InvalidArgument {
+                    argument: "test_type".to_string(),
+                    message: format!("`{test_type}` is not a known QARTOD test type"),
+                })
+            }
+        })
+    }
+}
+
 impl Display for QartodTestTypes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -140,7 +226,19 @@ pub trait TestSuite: std::fmt::Debug + Send + Sync + DynClone {
 
     /// This should return a Config that represents an `ioos_qc.Config`
     /// https://ioos.github.io/ioos_qc/usage.html#config
-    fn scaffold(&self, arguments: HashMap<String, ArgumentValue>) -> Result<ConfigStream, String>;
+    fn scaffold(&self, arguments: HashMap<String, ArgumentValue>) -> Result<ConfigStream>;
+
+    /// Configuration of a suite that doesn't take arguments, so it can be serialized in full.
+    ///
+    /// Suites implemented in the library return `None`, and are found again by slug.
+    fn static_qc(&self) -> Option<&StaticQc> {
+        None
+    }
+
+    /// Ancillary flag variables for the results of the suite's tests on a variable
+    fn qc_flags(&self, variable_name: &str, long_name: &str) -> QcFlags {
+        QcFlags::new(variable_name, long_name, &self.info().test_types)
+    }
 }
 
 dyn_clone::clone_trait_object!(TestSuite);
@@ -6,6 +6,7 @@ use super::config::{
 use super::types::{
     ArgumentType, ArgumentValue, QartodTestTypes, TestArgument, TestSuite, TestSuiteInfo,
 };
+use crate::error::{Error, Result};
 
 static FEET_TO_METERS: f64 = 0.3048;
 
@@ -74,6 +75,25 @@ Let’s start with 0.1 feet over 2 hours for suspect, and 0.1 feet over 3 hours
 Rationale: During neap tides in Portland, you could see as little as +/- 0.25 ft per hour of variation in the 2 hours around slack tide (HW or LW)
 "#;
 
+/// Get a required float argument for a test suite
+fn float_argument(
+    arguments: &HashMap<String, ArgumentValue>,
+    argument: &str,
+    context: &str,
+) -> Result<f64> {
+    match arguments.get(argument) {
+        Some(ArgumentValue::Float(value)) => Ok(*value),
+        Some(value) => Err(Error::InvalidArgument {
+            argument: argument.to_string(),
+            message: format!("Expected a float, got {value:?}"),
+        }),
+        None => Err(Error::MissingArgument {
+            argument: argument.to_string(),
+            context: context.to_string(),
+        }),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct GulfOfMaineWaterLevel {}
 
@@ -114,25 +134,10 @@ impl TestSuite for GulfOfMaineWaterLevel {
         }
     }
 
-    fn scaffold(&self, arguments: HashMap<String, ArgumentValue>) -> Result<ConfigStream, String> {
-        if arguments.is_empty() {
-            return Err("No arguments provided for Gulf of Maine water level tests".to_string());
-        }
-
-        let mllw = arguments
-            .get("mllw")
-            .and_then(|v| match v {
-                ArgumentValue::Float(f) => Some(*f),
-                _ => None,
-            })
-            .ok_or("Missing required argument: mllw")?;
-        let mhhw = arguments
-            .get("mhhw")
-            .and_then(|v| match v {
-                ArgumentValue::Float(f) => Some(*f),
-                _ => None,
-            })
-            .ok_or("Missing required argument: mhhw")?;
+    fn scaffold(&self, arguments: HashMap<String, ArgumentValue>) -> Result<ConfigStream> {
+        let context = "Gulf of Maine water level tests";
+        let mllw = float_argument(&arguments, "mllw", context)?;
+        let mhhw = float_argument(&arguments, "mhhw", context)?;
 
         Ok(ConfigStream {
             qartod: ConfigStreamQartod {
@@ -185,8 +190,13 @@ impl TestSuite for LongIslandSoundWaterLevel {
         }
     }
 
-    fn scaffold(&self, arguments: HashMap<String, ArgumentValue>) -> Result<ConfigStream, String> {
-        panic!("Scaffolding water level QARTOD tests for Long Island Sound with {arguments:?}",);
+    fn scaffold(&self, _arguments: HashMap<String, ArgumentValue>) -> Result<ConfigStream> {
+        Err(Error::UnsupportedSuite {
+            suite: "long_island_sound".to_string(),
+            message:
+                "Scaffolding water level QARTOD tests for Long Island Sound is not yet implemented"
+                    .to_string(),
+        })
     }
 }
 
@@ -224,10 +234,12 @@ mod tests {
         let args = HashMap::new();
         let config = gulf_suite.scaffold(args);
 
-        assert!(config.is_err());
         assert_eq!(
-            config.err().unwrap(),
-            "No arguments provided for Gulf of Maine water level tests"
+            config.unwrap_err(),
+            Error::MissingArgument {
+                argument: "mllw".to_string(),
+                context: "Gulf of Maine water level tests".to_string(),
+            }
         );
     }
 
@@ -240,7 +252,33 @@ mod tests {
         let config = gulf_suite.scaffold(args);
 
         assert!(config.is_err());
-        assert_eq!(config.err().unwrap(), "Missing required argument: mllw");
+        assert_eq!(
+            config.unwrap_err().to_string(),
+            "Missing required argument for Gulf of Maine water level tests: mllw"
+        );
+    }
+
+    #[test]
+    fn test_gulf_of_maine_invalid_arg() {
+        let gulf_suite = GulfOfMaineWaterLevel {};
+        let mut args = HashMap::new();
+        args.insert("mllw".to_string(), ArgumentValue::String("low".to_string()));
+        args.insert("mhhw".to_string(), ArgumentValue::Float(1.0));
+
+        let config = gulf_suite.scaffold(args);
+
+        assert!(matches!(
+            config,
+            Err(Error::InvalidArgument { argument, .. }) if argument == "mllw"
+        ));
+    }
+
+    #[test]
+    fn test_long_island_sound_unsupported() {
+        let long_island = LongIslandSoundWaterLevel {};
+        let config = long_island.scaffold(HashMap::new());
+
+        assert!(matches!(config, Err(Error::UnsupportedSuite { .. })));
     }
 
     #[test]
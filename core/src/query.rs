@@ -0,0 +1,666 @@
+//! Structured queries for filtering standards
+//!
+//! A query combines field qualifiers with boolean operators, like
+//! `category:Temperature AND unit:K AND has:qartod AND NOT name:*_at_sea_floor`.
+//!
+//! - `field:value` matches a field. Values may contain `*` and `?` wildcards,
+//!   and can be quoted to include spaces, like `long_name:"Sea Water*"`.
+//! - Words without a field search across all fields, like `filter --search`.
+//! - `AND`, `OR`, and `NOT` combine terms, and parentheses group them.
+//!   Terms next to each other are joined with `AND`, and `-term` is short for `NOT term`.
+//!
+//! Matching is case-insensitive. Without wildcards, `name`, `alias`, `category`,
+//! `unit`, and `var` must match the whole value, while `long_name`, `description`,
+//! and `comments` match anywhere in the text.
+//!
+//! `inferred_category` matches the IOOS category from knowledge,
+//! or for standards without one, the category inferred from the name and unit.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::standard::Standard;
+use crate::standards_filter::StandardsFilter;
+
+/// Field that a query term can match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    Name,
+    Alias,
+    Category,
+    InferredCategory,
+    Unit,
+    Variable,
+    LongName,
+    Description,
+    Comments,
+}
+
+impl QueryField {
+    const NAMES: &str =
+        "name, alias, category, inferred_category, unit, var, long_name, description, comments, has";
+
+    /// Does the value need to match the whole field, or can it be found anywhere in it
+    fn matches_whole(&self) -> bool {
+        !matches!(
+            self,
+            QueryField::LongName | QueryField::Description | QueryField::Comments
+        )
+    }
+
+    fn matches(&self, standard: &Standard, pattern: &Pattern) -> bool {
+        match self {
+            QueryField::Name => pattern.matches(&standard.name),
+            QueryField::Alias => standard.aliases.iter().any(|alias| pattern.matches(alias)),
+            QueryField::Category => standard
+                .ioos_category
+                .as_ref()
+                .is_some_and(|category| pattern.matches(category)),
+            QueryField::InferredCategory => match &standard.ioos_category {
+                Some(category) => pattern.matches(category),
+                None => {
+                    let inferred = standard.infer_ioos_category();
+                    inferred.confidence > 0.0 && pattern.matches(&inferred.category)
+                }
+            },
+            QueryField::Unit => {
+                pattern.matches(&standard.unit)
+                    || standard
+                        .other_units
+                        .iter()
+                        .any(|unit| pattern.matches(unit))
+            }
+            QueryField::Variable => standard
+                .common_variable_names
+                .iter()
+                .any(|name| pattern.matches_variable_name(name)),
+            QueryField::LongName => standard
+                .long_name
+                .as_ref()
+                .is_some_and(|long_name| pattern.matches(long_name)),
+            QueryField::Description => pattern.matches(&standard.description),
+            QueryField::Comments => standard
+                .comments
+                .as_ref()
+                .is_some_and(|comments| pattern.matches(comments)),
+        }
+    }
+}
+
+impl FromStr for QueryField {
+    type Err = ();
+
+    fn from_str(field: &str) -> Result<Self, ()> {
+        match field.to_lowercase().as_str() {
+            "name" => Ok(QueryField::Name),
+            "alias" => Ok(QueryField::Alias),
+            "category" | "ioos_category" => Ok(QueryField::Category),
+            "inferred_category" => Ok(QueryField::InferredCategory),
+            "unit" | "units" => Ok(QueryField::Unit),
+            "var" | "variable" => Ok(QueryField::Variable),
+            "long_name" => Ok(QueryField::LongName),
+            "description" => Ok(QueryField::Description),
+            "comments" => Ok(QueryField::Comments),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for QueryField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryField::Name => write!(f, "name"),
+            QueryField::Alias => write!(f, "alias"),
+            QueryField::Category => write!(f, "category"),
+            QueryField::InferredCategory => write!(f, "inferred_category"),
+            QueryField::Unit => write!(f, "unit"),
+            QueryField::Variable => write!(f, "var"),
+            QueryField::LongName => write!(f, "long_name"),
+            QueryField::Description => write!(f, "description"),
+            QueryField::Comments => write!(f, "comments"),
+        }
+    }
+}
+
+/// Something a standard can have, for `has:` terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryHas {
+    /// QARTOD test suites
+    Qartod,
+    /// Any IOOS knowledge beyond the CF standard name table
+    Knowledge,
+    Aliases,
+    LongName,
+    Category,
+    Comments,
+}
+
+impl QueryHas {
+    const NAMES: &str = "qartod, knowledge, aliases, long_name, category, comments";
+
+    fn matches(&self, standard: &Standard) -> bool {
+        match self {
+            QueryHas::Qartod => !standard.qartod.is_empty(),
+            QueryHas::Knowledge => standard.has_knowledge(),
+            QueryHas::Aliases => !standard.aliases.is_empty(),
+            QueryHas::LongName => standard.long_name.is_some(),
+            QueryHas::Category => standard.ioos_category.is_some(),
+            QueryHas::Comments => standard.comments.is_some(),
+        }
+    }
+}
+
+impl FromStr for QueryHas {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value.to_lowercase().as_str() {
+            "qartod" => Ok(QueryHas::Qartod),
+            "knowledge" => Ok(QueryHas::Knowledge),
+            "aliases" | "alias" => Ok(QueryHas::Aliases),
+            "long_name" => Ok(QueryHas::LongName),
+            "category" | "ioos_category" => Ok(QueryHas::Category),
+            "comments" => Ok(QueryHas::Comments),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for QueryHas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryHas::Qartod => write!(f, "qartod"),
+            QueryHas::Knowledge => write!(f, "knowledge"),
+            QueryHas::Aliases => write!(f, "aliases"),
+            QueryHas::LongName => write!(f, "long_name"),
+            QueryHas::Category => write!(f, "category"),
+            QueryHas::Comments => write!(f, "comments"),
+        }
+    }
+}
+
+/// Case-insensitive value to match, with optional `*` and `?` wildcards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// Pattern as written in the query
+    pub value: String,
+    lowercase: Vec<char>,
+    whole: bool,
+}
+
+impl Pattern {
+    fn new(value: &str, whole: bool) -> Self {
+        Pattern {
+            value: value.to_string(),
+            lowercase: value.to_lowercase().chars().collect(),
+            whole,
+        }
+    }
+
+    fn has_wildcards(&self) -> bool {
+        self.lowercase.iter().any(|c| *c == '*' || *c == '?')
+    }
+
+    /// Does the pattern match the text
+    fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.to_lowercase().chars().collect();
+        if self.whole || self.has_wildcards() {
+            glob_match(&self.lowercase, &text)
+        } else {
+            text.windows(self.lowercase.len())
+                .any(|window| window == self.lowercase.as_slice())
+        }
+    }
+
+    /// Match a variable name, ignoring case and underscores like `by_variable_name`
+    fn matches_variable_name(&self, name: &str) -> bool {
+        let pattern: Vec<char> = self
+            .lowercase
+            .iter()
+            .filter(|c| **c != '_')
+            .copied()
+            .collect();
+        let name: Vec<char> = name.to_lowercase().chars().filter(|c| *c != '_').collect();
+        glob_match(&pattern, &name)
+    }
+}
+
+/// Match text against a pattern where `*` matches any run of characters and `?` any one
+pub(crate) fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*`, and where in the text it started matching
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A parsed query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// `field:value`
+    Field(QueryField, Pattern),
+    /// `has:value`
+    Has(QueryHas),
+    /// Words searched across all fields
+    Search(String),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+}
+
+impl Query {
+    /// Parse a query string
+    pub fn parse(query: &str) -> Result<Self> {
+        let tokens = tokenize(query)?;
+        let mut parser = Parser {
+            query,
+            tokens,
+            position: 0,
+        };
+
+        if parser.tokens.is_empty() {
+            return Err(parser.error("query is empty"));
+        }
+
+        let parsed = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(parsed),
+            Some(Token::Close) => Err(parser.error("unmatched `)`")),
+            Some(token) => Err(parser.error(format!("unexpected {token}"))),
+        }
+    }
+
+    /// Narrow a filter to the standards that match
+    ///
+    /// `AND` narrows one filter after another, `OR` takes the union of both sides,
+    /// and `NOT` removes the standards that match from the filter.
+    pub fn apply(&self, filter: &StandardsFilter) -> StandardsFilter {
+        match self {
+            Query::Field(field, pattern) => {
+                filter.matching(|standard| field.matches(standard, pattern))
+            }
+            Query::Has(has) => filter.matching(|standard| has.matches(standard)),
+            Query::Search(search_str) => filter.search_unranked(search_str),
+            Query::And(left, right) => right.apply(&left.apply(filter)),
+            Query::Or(left, right) => left.apply(filter).union(&right.apply(filter)),
+            Query::Not(query) => filter.difference(&query.apply(filter)),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(query: &str) -> Result<Self> {
+        Query::parse(query)
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Query::Field(field, pattern) => write!(f, "{field}:{}", quote(&pattern.value)),
+            Query::Has(has) => write!(f, "has:{has}"),
+            Query::Search(search_str) => write!(f, "{}", quote(search_str)),
+            Query::And(left, right) => write!(f, "({left} AND {right})"),
+            Query::Or(left, right) => write!(f, "({left} OR {right})"),
+            Query::Not(query) => write!(f, "NOT {query}"),
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    /// A term, with the field if it was qualified
+    Term(Option<String>, String),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Open => write!(f, "`(`"),
+            Token::Close => write!(f, "`)`"),
+            Token::And => write!(f, "`AND`"),
+            Token::Or => write!(f, "`OR`"),
+            Token::Not => write!(f, "`NOT`"),
+            Token::Term(Some(field), value) => write!(f, "`{field}:{value}`"),
+            Token::Term(None, value) => write!(f, "`{value}`"),
+        }
+    }
+}
+
+fn parse_error(query: &str, message: impl Display) -> Error {
+    Error::parse(format!("query `{query}`"), message)
+}
+
+/// Split a query into tokens
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let mut field = None;
+                let mut word = String::new();
+                let mut quoted = false;
+
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    match c {
+                        ':' if field.is_none() && !word.is_empty() => {
+                            field = Some(std::mem::take(&mut word));
+                        }
+                        '"' => {
+                            quoted = true;
+                            loop {
+                                match chars.next() {
+                                    Some('"') => break,
+                                    Some(c) => word.push(c),
+                                    None => {
+                                        return Err(parse_error(query, "unterminated quote"));
+                                    }
+                                }
+                            }
+                        }
+                        c => word.push(c),
+                    }
+                }
+
+                let token = match (field.is_none() && !quoted, word.as_str()) {
+                    (true, "AND") => Token::And,
+                    (true, "OR") => Token::Or,
+                    (true, "NOT") => Token::Not,
+                    _ => {
+                        if word.is_empty() {
+                            let field = field.unwrap_or_default();
+                            return Err(parse_error(
+                                query,
+                                format!("`{field}:` is missing a value"),
+                            ));
+                        }
+                        Token::Term(field, word)
+                    }
+                };
+                tokens.push(token);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser, with `OR` binding looser than `AND`, and `NOT` tightest
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn error(&self, message: impl Display) -> Error {
+        parse_error(self.query, message)
+    }
+
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            let right = self.parse_and()?;
+            query = Query::Or(Box::new(query), Box::new(right));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut query = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                // Terms next to each other are implicitly joined with AND
+                Some(Token::Open | Token::Not | Token::Term(..)) => {}
+                _ => return Ok(query),
+            }
+            let right = self.parse_not()?;
+            query = Query::And(Box::new(query), Box::new(right));
+        }
+    }
+
+    fn parse_not(&mut self) -> Result<Query> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Query> {
+        match self.next() {
+            Some(Token::Open) => {
+                let query = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err(self.error("missing `)`")),
+                }
+            }
+            Some(Token::Term(None, value)) => Ok(Query::Search(value)),
+            Some(Token::Term(Some(field), value)) => {
+                if field.eq_ignore_ascii_case("has") {
+                    return value.parse().map(Query::Has).map_err(|_| {
+                        self.error(format!(
+                            "unknown `has:{value}`, expected one of {}",
+                            QueryHas::NAMES
+                        ))
+                    });
+                }
+                let field: QueryField = field.parse().map_err(|_| {
+                    self.error(format!(
+                        "unknown field `{field}`, expected one of {}",
+                        QueryField::NAMES
+                    ))
+                })?;
+                Ok(Query::Field(
+                    field,
+                    Pattern::new(&value, field.matches_whole()),
+                ))
+            }
+            Some(token) => Err(self.error(format!("expected a term, found {token}"))),
+            None => Err(self.error("query ended early, expected a term")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StandardsLibrary;
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library.load_test_suites();
+        library
+    }
+
+    #[test]
+    fn parses_precedence() {
+        let query =
+            Query::parse("category:Temperature unit:K OR has:qartod -name:*_at_sea_floor").unwrap();
+        assert_eq!(
+            query.to_string(),
+            "((category:Temperature AND unit:K) OR (has:qartod AND NOT name:*_at_sea_floor))"
+        );
+
+        let query = Query::parse("NOT (alias:foo OR long_name:\"Sea Water\")").unwrap();
+        assert_eq!(
+            query.to_string(),
+            "NOT (alias:foo OR long_name:\"Sea Water\")"
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        for (query, message) in [
+            ("", "query is empty"),
+            ("(unit:K", "missing `)`"),
+            ("unit:K)", "unmatched `)`"),
+            ("unit:K AND", "query ended early, expected a term"),
+            ("colour:red", "unknown field `colour`"),
+            ("has:wings", "unknown `has:wings`"),
+            ("unit:", "`unit:` is missing a value"),
+            ("long_name:\"Sea", "unterminated quote"),
+        ] {
+            match Query::parse(query) {
+                Err(Error::Parse { input, message: m }) => {
+                    assert_eq!(input, format!("query `{query}`"));
+                    assert!(m.starts_with(message), "{query}: {m}");
+                }
+                other => panic!("{query} should not parse: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        let pattern = Pattern::new("*_at_sea_floor", true);
+        assert!(pattern.matches("sea_water_temperature_at_sea_floor"));
+        assert!(!pattern.matches("sea_floor_depth_below_sea_surface"));
+
+        let pattern = Pattern::new("sea_water_?emperature", true);
+        assert!(pattern.matches("Sea_Water_Temperature"));
+        assert!(!pattern.matches("sea_water_temperature_at_sea_floor"));
+
+        let pattern = Pattern::new("water", false);
+        assert!(pattern.matches("Sea Water Temperature"));
+    }
+
+    #[test]
+    fn applies_to_filter() {
+        let library = library();
+        let filter = library
+            .filter()
+            .query("category:Temperature AND unit:K AND has:qartod AND NOT name:*_at_sea_floor")
+            .unwrap();
+
+        assert!(!filter.standards.is_empty());
+        for standard in &filter.standards {
+            assert!(standard
+                .ioos_category
+                .as_ref()
+                .is_some_and(|c| c.eq_ignore_ascii_case("temperature")));
+            assert!(standard.unit == "K" || standard.other_units.contains(&"K".to_string()));
+            assert!(!standard.qartod.is_empty());
+            assert!(!standard.name.ends_with("_at_sea_floor"));
+        }
+
+        let either = library
+            .filter()
+            .query("name:air_temperature OR name:sea_water_temperature")
+            .unwrap();
+        let names: Vec<&str> = either.standards.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["air_temperature", "sea_water_temperature"]);
+
+        let searched = library.filter().query("unit:Pa \"sea level\"").unwrap();
+        assert!(searched
+            .standards
+            .iter()
+            .any(|standard| standard.name == "air_pressure_at_mean_sea_level"));
+
+        let curated = library.filter().query("category:Salinity").unwrap();
+        let inferred = library
+            .filter()
+            .query("inferred_category:Salinity")
+            .unwrap();
+        assert!(inferred.standards.len() > curated.standards.len());
+        assert!(inferred
+            .standards
+            .iter()
+            .any(|standard| standard.name == "sea_water_absolute_salinity"));
+    }
+
+    #[test]
+    fn search_terms_are_not_limited() {
+        let library = library();
+        let matches = library.filter().query("temperature").unwrap();
+        assert!(matches.standards.len() > 100);
+
+        let excluded = library
+            .filter()
+            .query("name:*temperature* AND NOT temperature")
+            .unwrap();
+        for standard in &excluded.standards {
+            assert!(!matches
+                .standards
+                .iter()
+                .any(|matched| matched.name == standard.name));
+        }
+        assert!(!excluded.standards.iter().any(|standard| standard.name
+            == "air_temperature_at_effective_cloud_top_defined_by_infrared_radiation"));
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::standard::Standard;
+
+/// Field of a standard that a search matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Name,
+    Alias,
+    CommonVariableName,
+    LongName,
+    IoosCategory,
+    Description,
+    Comments,
+}
+
+impl SearchField {
+    /// Weight when the whole search matches the field exactly
+    fn exact_weight(&self) -> f64 {
+        match self {
+            SearchField::Name => 100.0,
+            SearchField::Alias => 90.0,
+            SearchField::CommonVariableName => 60.0,
+            SearchField::LongName => 15.0,
+            SearchField::IoosCategory => 5.0,
+            SearchField::Description | SearchField::Comments => 0.0,
+        }
+    }
+
+    /// Weight when all search terms are found in the field
+    fn term_weight(&self) -> f64 {
+        match self {
+            SearchField::Name => 30.0,
+            SearchField::Alias => 25.0,
+            SearchField::CommonVariableName => 20.0,
+            SearchField::LongName => 10.0,
+            SearchField::IoosCategory => 3.0,
+            SearchField::Description => 2.0,
+            SearchField::Comments => 1.0,
+        }
+    }
+}
+
+impl Display for SearchField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchField::Name => write!(f, "name"),
+            SearchField::Alias => write!(f, "alias"),
+            SearchField::CommonVariableName => write!(f, "common_variable_name"),
+            SearchField::LongName => write!(f, "long_name"),
+            SearchField::IoosCategory => write!(f, "ioos_category"),
+            SearchField::Description => write!(f, "description"),
+            SearchField::Comments => write!(f, "comments"),
+        }
+    }
+}
+
+/// How a search matched one field of a standard
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldMatch {
+    pub field: SearchField,
+
+    /// Value of the field that matched, such as which alias
+    pub value: String,
+
+    /// Did the whole search match the value, rather than some of its words
+    pub exact: bool,
+
+    /// How much this match contributed to the score
+    pub score: f64,
+}
+
+/// A standard found by a search, with how relevant it is and why
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub standard: Arc<Standard>,
+
+    /// Relevance, higher is better
+    pub score: f64,
+
+    /// Fields that matched, best first.
+    ///
+    /// Empty if the standard was only found by fuzzy matching,
+    /// or by fields that aren't scored such as units or test suites.
+    pub matches: Vec<FieldMatch>,
+}
+
+impl SearchResult {
+    /// Describe which fields matched, like `name (exact), long_name`
+    pub fn explain(&self) -> String {
+        self.matches
+            .iter()
+            .map(|field_match| {
+                if field_match.exact {
+                    format!("{} (exact)", field_match.field)
+                } else {
+                    field_match.field.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Lower case words of text, split on anything that isn't a letter or number
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// A parsed search
+struct Query {
+    /// All words joined, for comparing to whole values
+    joined: String,
+    terms: BTreeSet<String>,
+}
+
+impl Query {
+    fn new(search_str: &str) -> Self {
+        let words = words(search_str);
+        Query {
+            joined: words.join(""),
+            terms: words.into_iter().collect(),
+        }
+    }
+
+    /// Score how well a single value of a field matches
+    fn score_value(&self, field: SearchField, value: &str) -> Option<FieldMatch> {
+        let value_words = words(value);
+        if self.terms.is_empty() || value_words.is_empty() {
+            return None;
+        }
+
+        if value_words.join("") == self.joined && field.exact_weight() > 0.0 {
+            return Some(FieldMatch {
+                field,
+                value: value.to_string(),
+                exact: true,
+                score: field.exact_weight(),
+            });
+        }
+
+        // Whole words count fully, and the start of a word counts half, for search as you type
+        let mut found = 0.0;
+        let mut matched_words = BTreeSet::new();
+        for term in &self.terms {
+            if value_words.contains(term) {
+                found += 1.0;
+                matched_words.insert(term.as_str());
+            } else if let Some(word) = value_words.iter().find(|word| word.starts_with(term)) {
+                found += 0.5;
+                matched_words.insert(word.as_str());
+            }
+        }
+        if found == 0.0 {
+            return None;
+        }
+
+        let coverage = found / self.terms.len() as f64;
+        // Prefer values that are mostly the search, so `air_temperature`
+        // ranks above `air_temperature_at_cloud_top` for `temperature`.
+        let unique_words = value_words.iter().collect::<BTreeSet<_>>().len();
+        let specificity = matched_words.len() as f64 / unique_words as f64;
+
+        Some(FieldMatch {
+            field,
+            value: value.to_string(),
+            exact: false,
+            score: field.term_weight() * coverage * (0.5 + 0.5 * specificity),
+        })
+    }
+
+    /// Best match among all values of a field
+    fn score_field<'a>(
+        &self,
+        field: SearchField,
+        values: impl IntoIterator<Item = &'a String>,
+    ) -> Option<FieldMatch> {
+        values
+            .into_iter()
+            .filter_map(|value| self.score_value(field, value))
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+
+    fn score(&self, standard: &Arc<Standard>) -> SearchResult {
+        let mut matches: Vec<FieldMatch> = [
+            self.score_field(SearchField::Name, [&standard.name]),
+            self.score_field(SearchField::Alias, &standard.aliases),
+            self.score_field(
+                SearchField::CommonVariableName,
+                &standard.common_variable_names,
+            ),
+            self.score_field(SearchField::LongName, &standard.long_name),
+            self.score_field(SearchField::IoosCategory, &standard.ioos_category),
+            self.score_field(SearchField::Description, [&standard.description]),
+            self.score_field(SearchField::Comments, &standard.comments),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        SearchResult {
+            standard: standard.clone(),
+            score: matches.iter().map(|field_match| field_match.score).sum(),
+            matches,
+        }
+    }
+}
+
+/// Score and sort standards found by a search, most relevant first
+///
+/// Ties are broken by name so that results are stable.
+pub fn rank<'a>(
+    search_str: &str,
+    standards: impl IntoIterator<Item = &'a Arc<Standard>>,
+) -> Vec<SearchResult> {
+    let query = Query::new(search_str);
+
+    let mut results: Vec<SearchResult> = standards
+        .into_iter()
+        .map(|standard| query.score(standard))
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.standard.name.cmp(&b.standard.name))
+    });
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standard(name: &str) -> Standard {
+        Standard {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exact_name_outranks_other_fields() {
+        let exact = Arc::new(standard("sea_water_temperature"));
+        let variable = Arc::new(Standard {
+            common_variable_names: vec!["sea_water_temperature".to_string()],
+            ..standard("sea_water_potential_temperature")
+        });
+        let long_name = Arc::new(Standard {
+            long_name: Some("Sea Water Temperature".to_string()),
+            ..standard("sea_water_conservative_temperature")
+        });
+        let description = Arc::new(Standard {
+            description: "Temperature of the sea water".to_string(),
+            ..standard("sea_water_added_conservative_temperature")
+        });
+
+        let results = rank(
+            "sea_water_temperature",
+            [&description, &long_name, &variable, &exact],
+        );
+        let names: Vec<&str> = results.iter().map(|r| r.standard.name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "sea_water_temperature",
+                "sea_water_potential_temperature",
+                "sea_water_conservative_temperature",
+                "sea_water_added_conservative_temperature",
+            ]
+        );
+        assert_eq!(results[0].matches[0].field, SearchField::Name);
+        assert!(results[0].matches[0].exact);
+        assert_eq!(results[1].matches[0].field, SearchField::CommonVariableName);
+    }
+
+    #[test]
+    fn explains_matches() {
+        let pressure = Arc::new(Standard {
+            long_name: Some("Atmospheric Pressure".to_string()),
+            aliases: vec!["air_pressure_at_sea_level".to_string()],
+            ..standard("air_pressure_at_mean_sea_level")
+        });
+
+        let results = rank("air_pressure_at_sea_level", [&pressure]);
+        assert_eq!(results[0].matches[0].field, SearchField::Alias);
+        assert_eq!(results[0].matches[0].value, "air_pressure_at_sea_level");
+        assert_eq!(results[0].explain(), "alias (exact), name, long_name");
+    }
+
+    #[test]
+    fn shorter_names_rank_higher_for_the_same_words() {
+        let short = Arc::new(standard("air_temperature"));
+        let long = Arc::new(standard("air_temperature_at_cloud_top"));
+
+        let results = rank("temperature", [&long, &short]);
+        assert_eq!(results[0].standard.name, "air_temperature");
+        assert!(results[0].score > results[1].score);
+    }
+}
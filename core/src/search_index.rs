@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use indicium::simple::{SearchIndex, SearchType};
+
+use crate::standard::Standard;
+
+/// Search index over every standard in a library
+///
+/// The index is built the first time a search is run, then shared by the library
+/// and every filter created from it. Standards that change after the index
+/// is built, such as when knowledge is applied, are re-indexed individually.
+#[derive(Debug, Default, Clone)]
+pub struct StandardsSearchIndex {
+    standards: HashMap<String, Arc<Standard>>,
+    index: OnceLock<SearchIndex<String>>,
+}
+
+impl StandardsSearchIndex {
+    /// Add or replace a standard, updating the index if it has already been built
+    pub(crate) fn insert(&mut self, standard: Arc<Standard>) {
+        let name = standard.name.clone();
+        let previous = self.standards.insert(name.clone(), standard.clone());
+
+        if let Some(index) = self.index.get_mut() {
+            match previous {
+                Some(previous) => index.replace(&name, previous.as_ref(), standard.as_ref()),
+                None => index.insert(&name, standard.as_ref()),
+            }
+        }
+    }
+
+    /// Has the index been built yet
+    pub fn is_built(&self) -> bool {
+        self.index.get().is_some()
+    }
+
+    /// Return the index, building it if this is the first search
+    fn index(&self) -> &SearchIndex<String> {
+        self.index.get_or_init(|| {
+            let mut index = SearchIndex::default();
+            for (name, standard) in &self.standards {
+                index.insert(name, standard.as_ref());
+            }
+            index
+        })
+    }
+
+    /// Return the names of all standards that match a search
+    pub fn search<'a>(&'a self, search_str: &'a str) -> Vec<&'a str> {
+        self.index()
+            .search_with(&SearchType::Live, &self.standards.len(), search_str)
+            .into_iter()
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{Knowledge, StandardsLibrary};
+
+    #[test]
+    fn index_is_built_once_and_shared() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+
+        let filter = library.filter();
+        let search_index = filter.search_index.clone().unwrap();
+        assert!(!search_index.is_built());
+
+        let results = filter.by_unit("Pa").search("sea level");
+        assert!(results
+            .standards
+            .iter()
+            .any(|standard| standard.name == "air_pressure_at_mean_sea_level"));
+        assert!(results
+            .standards
+            .iter()
+            .all(|standard| standard.unit == "Pa"));
+
+        // Later filters reuse the index that the first search built
+        let later = library.filter().search_index.unwrap();
+        assert!(Arc::ptr_eq(&search_index, &later));
+        assert!(later.is_built());
+    }
+
+    #[test]
+    fn knowledge_is_indexed_incrementally() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        assert!(library.filter().search("qwxzyv").standards.is_empty());
+
+        library.apply_knowledge(vec![Knowledge {
+            name: "air_pressure_at_mean_sea_level".to_string(),
+            common_variable_names: vec!["qwxzyv".to_string()],
+            ..Default::default()
+        }]);
+
+        let search_index = library.filter().search_index.unwrap();
+        assert!(search_index.is_built());
+
+        let results = library.filter().search("qwxzyv");
+        assert_eq!(results.standards.len(), 1);
+        assert_eq!(results.standards[0].name, "air_pressure_at_mean_sea_level");
+    }
+}
@@ -2,8 +2,18 @@ use core::fmt;
 use std::collections::BTreeMap;
 
 use indicium::simple::Indexable;
-
-use crate::qartod::TestSuite;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::attr_value::AttrValue;
+use crate::diff::{diff_standards, FieldChange};
+use crate::ioos_categories::{infer_ioos_category, InferredCategory};
+use crate::knowledge_rules::RuleDefaults;
+use crate::qartod::config::ConfigStream;
+use crate::qartod::flags::QcFlags;
+use crate::qartod::static_qc::StaticQc;
+use crate::qartod::types::TestSuiteInfo;
+use crate::qartod::{test_suites, StaticQcTestSuite, TestSuite};
 
 #[derive(Default, Clone)]
 pub struct Standard {
@@ -15,6 +25,12 @@ pub struct Standard {
     pub description: String,
     pub aliases: Vec<String>,
 
+    /// GRIB parameter code from the CF standard name table
+    pub grib: Option<String>,
+
+    /// AMIP variable name from the CF standard name table
+    pub amip: Option<String>,
+
     /// Usual IOOS category for the standard
     pub ioos_category: Option<String>,
 
@@ -28,7 +44,7 @@ pub struct Standard {
     pub sibling_standards: Vec<String>,
 
     /// Extra attributes that are usually included in Xarray or NetCDF metadata
-    pub extra_attrs: BTreeMap<String, String>,
+    pub extra_attrs: BTreeMap<String, AttrValue>,
 
     /// Other units that may be seen
     pub other_units: Vec<String>,
@@ -38,9 +54,56 @@ pub struct Standard {
 
     /// QARTOD test suites
     pub qartod: Vec<Box<dyn TestSuite>>,
+
+    /// Which knowledge was filled in by rules for families of standards
+    pub rule_defaults: RuleDefaults,
 }
 
 impl Standard {
+    /// Does the standard have any IOOS knowledge beyond the CF standard name table
+    ///
+    /// Knowledge that was only filled in by rules for families of standards doesn't count.
+    pub fn has_knowledge(&self) -> bool {
+        let rules = &self.rule_defaults;
+        self.long_name.is_some()
+            || (self.ioos_category.is_some() && !rules.ioos_category)
+            || !self.common_variable_names.is_empty()
+            || !self.related_standards.is_empty()
+            || !self.sibling_standards.is_empty()
+            || self
+                .extra_attrs
+                .keys()
+                .any(|key| !rules.extra_attrs.contains(key))
+            || self
+                .other_units
+                .iter()
+                .any(|unit| !rules.other_units.contains(unit))
+            || self.comments.is_some()
+            || !self.qartod.is_empty()
+    }
+
+    /// Standard without the knowledge that was filled in by rules
+    pub(crate) fn without_rule_defaults(&self) -> Standard {
+        let rules = &self.rule_defaults;
+        let mut standard = self.clone();
+        if rules.ioos_category {
+            standard.ioos_category = None;
+        }
+        standard
+            .extra_attrs
+            .retain(|key, _| !rules.extra_attrs.contains(key));
+        standard
+            .other_units
+            .retain(|unit| !rules.other_units.contains(unit));
+        standard.rule_defaults = RuleDefaults::default();
+        standard
+    }
+
+    /// Propose an IOOS category from the name and unit, even if one is curated in knowledge
+    pub fn infer_ioos_category(&self) -> InferredCategory {
+        infer_ioos_category(&self.name, &self.unit)
+    }
+
     /// Do any of the fields in the standard match a search pattern
     pub fn matches_pattern(&self, search_str: &str) -> bool {
         let search_str = search_str.to_lowercase();
@@ -78,6 +141,11 @@ impl Standard {
                 .is_some_and(|comment| comment.to_lowercase().contains(search_str))
     }
 
+    /// Fields that differ between this and another version of the standard
+    pub fn diff(&self, other: &Standard) -> Vec<FieldChange> {
+        diff_standards(self, other)
+    }
+
     /// Display all the fields for a standard
     pub fn display_all(&self) -> String {
         let mut output = self.display_short();
@@ -85,8 +153,19 @@ impl Standard {
         if !self.aliases.is_empty() {
             output = format!("{output}\n  Aliases: {}", self.aliases.join(", "))
         }
+        if let Some(grib) = &self.grib {
+            output = format!("{output}\n  GRIB: {grib}")
+        }
+        if let Some(amip) = &self.amip {
+            output = format!("{output}\n  AMIP: {amip}")
+        }
         if let Some(ioos_category) = &self.ioos_category {
             output = format!("{output}\n  IOOS Category: {ioos_category}")
+        } else {
+            let inferred = self.infer_ioos_category();
+            if inferred.confidence > 0.0 {
+                output = format!("{output}\n  IOOS Category: {inferred}")
+            }
         }
         if !self.common_variable_names.is_empty() {
             output = format!(
@@ -137,40 +216,78 @@ impl Standard {
     }
 
     /// Attributes displayed with Xarray
-    pub fn xarray_attrs(&self) -> BTreeMap<&str, &str> {
-        let mut map = BTreeMap::from([("standard_name", self.name.as_str())]);
+    ///
+    /// Extra attributes keep their types from knowledge, so numeric attributes like
+    /// `valid_min` or `flag_values` aren't strings.
+    pub fn xarray_attrs(&self) -> BTreeMap<&str, AttrValue> {
+        let mut map = BTreeMap::from([("standard_name", AttrValue::from(self.name.as_str()))]);
 
         if !self.unit.is_empty() {
-            map.insert("units", self.unit.as_str());
+            map.insert("units", self.unit.as_str().into());
         }
 
         if let Some(long_name) = &self.long_name {
             if !long_name.is_empty() {
-                map.insert("long_name", long_name.as_str());
+                map.insert("long_name", long_name.as_str().into());
             }
         }
 
         if let Some(ioos_category) = &self.ioos_category {
             if !ioos_category.is_empty() {
-                map.insert("ioos_category", ioos_category.as_str());
+                map.insert("ioos_category", ioos_category.as_str().into());
             }
         }
 
         for (key, value) in &self.extra_attrs {
-            map.insert(key, value);
+            map.insert(key, value.clone());
         }
 
         map
     }
 
-    /// Formatted Xarray attributes
-    pub fn display_xarray_attrs(&self) -> String {
-        let mut output = "{".to_string();
-        for (key, value) in self.xarray_attrs() {
-            output = format!("{output}\n  \"{key}\": \"{value}\",");
+    /// ERDDAP `datasets.xml` `<dataVariable>` block for a variable with this standard
+    ///
+    /// The source name defaults to the standard name, and the destination name to the source name.
+    pub fn erddap_data_variable(
+        &self,
+        source_name: Option<&str>,
+        destination_name: Option<&str>,
+    ) -> String {
+        let source_name = source_name.unwrap_or(&self.name);
+        crate::erddap::data_variable(self, source_name, destination_name.unwrap_or(source_name))
+    }
+
+    /// NetCDF CDL declaration for a double variable with this standard's attributes
+    ///
+    /// The variable name defaults to the standard name.
+    pub fn cdl_variable(&self, variable_name: Option<&str>, dimensions: &[&str]) -> String {
+        crate::cdl::variable(self, variable_name.unwrap_or(&self.name), dimensions)
+    }
+
+    /// Ancillary QARTOD flag variables for a variable with this standard,
+    /// if it has QARTOD test suites
+    ///
+    /// There is an aggregate flag, and a flag for each type of test in the suites.
+    /// The variable name defaults to the standard name.
+    pub fn qc_flags(&self, variable_name: Option<&str>) -> Option<QcFlags> {
+        if self.qartod.is_empty() {
+            return None;
         }
-        output = format!("{output}\n}}");
-        output
+        let test_types: Vec<_> = self
+            .qartod
+            .iter()
+            .flat_map(|suite| suite.info().test_types)
+            .collect();
+        Some(QcFlags::new(
+            variable_name.unwrap_or(&self.name),
+            self.long_name.as_deref().unwrap_or(&self.name),
+            &test_types,
+        ))
+    }
+
+    /// Xarray attributes as a JSON object
+    pub fn display_xarray_attrs(&self) -> String {
+        serde_json::to_string_pretty(&self.xarray_attrs()).expect("attributes always serialize")
     }
 
     /// Short format
@@ -190,6 +307,8 @@ impl fmt::Debug for Standard {
             .field("unit", &self.unit)
             .field("description", &self.description)
             .field("aliases", &self.aliases)
+            .field("grib", &self.grib)
+            .field("amip", &self.amip)
             .field("ioos_category", &self.ioos_category)
             .field("common_variable_names", &self.common_variable_names)
             .field("related_standards", &self.related_standards)
@@ -198,10 +317,163 @@ impl fmt::Debug for Standard {
             .field("other_units", &self.other_units)
             .field("comments", &self.comments)
             .field("qartod", &format!("[{} test suites]", self.qartod.len()))
+            .field("rule_defaults", &self.rule_defaults)
             .finish()
     }
 }
 
+/// Test suite as it is serialized, described by its info
+///
+/// Static suites carry their configuration, so they can be rebuilt.
+/// Suites that are implemented in the library are found again by slug.
+#[derive(Serialize)]
+struct SerializedTestSuite<'a> {
+    #[serde(flatten)]
+    info: TestSuiteInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config: Option<&'a ConfigStream>,
+}
+
+/// Test suite as it is deserialized
+#[derive(Deserialize)]
+struct DeserializedTestSuite {
+    #[serde(flatten)]
+    info: TestSuiteInfo,
+    #[serde(default)]
+    config: Option<ConfigStream>,
+}
+
+/// Fields of a standard as they are serialized, borrowed from the standard
+#[derive(Serialize)]
+struct SerializedStandard<'a> {
+    name: &'a str,
+    long_name: &'a Option<String>,
+    unit: &'a str,
+    description: &'a str,
+    aliases: &'a [String],
+    grib: &'a Option<String>,
+    amip: &'a Option<String>,
+    ioos_category: &'a Option<String>,
+    common_variable_names: &'a [String],
+    related_standards: &'a [String],
+    sibling_standards: &'a [String],
+    extra_attrs: &'a BTreeMap<String, AttrValue>,
+    other_units: &'a [String],
+    comments: &'a Option<String>,
+    qartod: Vec<SerializedTestSuite<'a>>,
+    #[serde(skip_serializing_if = "no_rule_defaults")]
+    rule_defaults: &'a RuleDefaults,
+}
+
+fn no_rule_defaults(rule_defaults: &&RuleDefaults) -> bool {
+    rule_defaults.is_empty()
+}
+
+/// Fields of a standard as they are deserialized
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct DeserializedStandard {
+    name: String,
+    long_name: Option<String>,
+    unit: String,
+    description: String,
+    aliases: Vec<String>,
+    grib: Option<String>,
+    amip: Option<String>,
+    ioos_category: Option<String>,
+    common_variable_names: Vec<String>,
+    related_standards: Vec<String>,
+    sibling_standards: Vec<String>,
+    extra_attrs: BTreeMap<String, AttrValue>,
+    other_units: Vec<String>,
+    comments: Option<String>,
+    qartod: Vec<DeserializedTestSuite>,
+    rule_defaults: RuleDefaults,
+}
+
+impl Serialize for Standard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedStandard {
+            name: &self.name,
+            long_name: &self.long_name,
+            unit: &self.unit,
+            description: &self.description,
+            aliases: &self.aliases,
+            grib: &self.grib,
+            amip: &self.amip,
+            ioos_category: &self.ioos_category,
+            common_variable_names: &self.common_variable_names,
+            related_standards: &self.related_standards,
+            sibling_standards: &self.sibling_standards,
+            extra_attrs: &self.extra_attrs,
+            other_units: &self.other_units,
+            comments: &self.comments,
+            qartod: self
+                .qartod
+                .iter()
+                .map(|suite| SerializedTestSuite {
+                    info: suite.info(),
+                    config: suite.static_qc().map(|qc| &qc.tests),
+                })
+                .collect(),
+            rule_defaults: &self.rule_defaults,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Standard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let serialized = DeserializedStandard::deserialize(deserializer)?;
+
+        let mut library_suites = test_suites().remove(&serialized.name).unwrap_or_default();
+        let qartod = serialized
+            .qartod
+            .into_iter()
+            .map(|suite| match suite.config {
+                Some(tests) => Ok(Box::new(StaticQcTestSuite {
+                    slug: suite.info.slug,
+                    qc: StaticQc {
+                        name: suite.info.name,
+                        summary: suite.info.summary,
+                        description: suite.info.description,
+                        tests,
+                    },
+                }) as Box<dyn TestSuite>),
+                None => library_suites
+                    .iter()
+                    .position(|library_suite| library_suite.info().slug == suite.info.slug)
+                    .map(|index| library_suites.remove(index))
+                    .ok_or_else(|| {
+                        D::Error::custom(format!(
+                            "{} doesn't have a test suite `{}` in the library, and no config was given for it",
+                            serialized.name, suite.info.slug
+                        ))
+                    }),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Standard {
+            name: serialized.name,
+            long_name: serialized.long_name,
+            unit: serialized.unit,
+            description: serialized.description,
+            aliases: serialized.aliases,
+            grib: serialized.grib,
+            amip: serialized.amip,
+            ioos_category: serialized.ioos_category,
+            common_variable_names: serialized.common_variable_names,
+            related_standards: serialized.related_standards,
+            sibling_standards: serialized.sibling_standards,
+            extra_attrs: serialized.extra_attrs,
+            other_units: serialized.other_units,
+            comments: serialized.comments,
+            qartod,
+            rule_defaults: serialized.rule_defaults,
+        })
+    }
+}
+
 impl PartialEq for Standard {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
@@ -209,6 +481,8 @@ impl PartialEq for Standard {
             && self.unit == other.unit
             && self.description == other.description
             && self.aliases == other.aliases
+            && self.grib == other.grib
+            && self.amip == other.amip
             && self.ioos_category == other.ioos_category
             && self.common_variable_names == other.common_variable_names
             && self.related_standards == other.related_standards
@@ -216,6 +490,7 @@ impl PartialEq for Standard {
             && self.extra_attrs == other.extra_attrs
             && self.other_units == other.other_units
             && self.comments == other.comments
+            && self.rule_defaults == other.rule_defaults
             // Note: We compare only the length of qartod test suites since trait objects cannot be compared
             && self.qartod.len() == other.qartod.len()
     }
@@ -256,6 +531,8 @@ mod tests {
             unit: "Pa".to_string(),
             description: "A quick note".to_string(),
             aliases: Vec::new(),
+            grib: None,
+            amip: None,
             ioos_category: Some("Meteorology".to_string()),
             common_variable_names: Vec::new(),
             related_standards: Vec::new(),
@@ -264,6 +541,7 @@ mod tests {
             other_units: Vec::new(),
             comments: None,
             qartod: Vec::new(),
+            rule_defaults: RuleDefaults::default(),
         };
 
         assert!(
@@ -276,4 +554,30 @@ mod tests {
             "Shouldn't match something random"
         );
     }
+
+    #[test]
+    fn qc_flags_for_suites() {
+        let name = "sea_surface_height_above_geopotential_datum";
+        let mut standard = Standard {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        assert!(standard.qc_flags(None).is_none());
+
+        standard.qartod = test_suites().remove(name).unwrap();
+        let flags = standard.qc_flags(Some("navd88_meters")).unwrap();
+        // Both water level suites run the same four tests
+        assert_eq!(flags.variables.len(), 5);
+        assert_eq!(
+            flags.ancillary_variables,
+            "navd88_meters_qc_agg navd88_meters_qartod_gross_range_test \
+             navd88_meters_qartod_spike_test navd88_meters_qartod_rate_of_change_test \
+             navd88_meters_qartod_flat_line_test"
+        );
+        assert_eq!(
+            flags.variables[4].attrs["long_name"],
+            format!("{name} Flat Line Test Quality Flag").as_str()
+        );
+        assert_eq!(standard.qartod[0].qc_flags("navd88_meters", name), flags);
+    }
 }
@@ -1,6 +1,18 @@
-use indicium::simple::SearchIndex;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
 
+use indicium::simple::{SearchIndex, SearchType};
+use regex::Regex;
+
+use crate::error::{Error, Result};
+use crate::qartod::types::QartodTestTypes;
+use crate::query::Query;
+use crate::ranking::{rank, SearchResult};
+use crate::search_index::StandardsSearchIndex;
 use crate::standard::Standard;
+use crate::suggest::UnknownStandard;
+use crate::upgrade::ResolvedStandard;
 
 /// Normalize a variable name by converting to lowercase and removing underscores
 /// This allows matching between snake_case, camelCase, and other variations
@@ -8,15 +20,80 @@ fn normalize_variable_name(name: &str) -> String {
     name.to_lowercase().replace('_', "")
 }
 
+/// Most results that a search will return
+const MAX_SEARCH_RESULTS: usize = 100;
+
+/// Field to sort standards by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Unit,
+    IoosCategory,
+    LongName,
+}
+
+impl SortKey {
+    fn value<'a>(&self, standard: &'a Standard) -> &'a str {
+        match self {
+            SortKey::Name => &standard.name,
+            SortKey::Unit => &standard.unit,
+            SortKey::IoosCategory => standard.ioos_category.as_deref().unwrap_or_default(),
+            SortKey::LongName => standard.long_name.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        match key.to_lowercase().as_str() {
+            "name" => Ok(SortKey::Name),
+            "unit" | "units" => Ok(SortKey::Unit),
+            "category" | "ioos_category" => Ok(SortKey::IoosCategory),
+            "long_name" => Ok(SortKey::LongName),
+            _ => Err(Error::InvalidArgument {
+                argument: "sort".to_string(),
+                message: format!(
+                    "`{key}` is not a sort key, expected one of name, unit, ioos_category, long_name"
+                ),
+            }),
+        }
+    }
+}
+
 /// Chainable filter for standards
-#[derive(Clone)]
+///
+/// Standards are shared with the library they came from,
+/// so filtering only copies pointers rather than whole standards.
+#[derive(Clone, Default)]
 pub struct StandardsFilter {
-    pub standards: Vec<Standard>,
+    pub standards: Vec<Arc<Standard>>,
+
+    /// Search index of the library the filter came from, if any
+    pub search_index: Option<Arc<StandardsSearchIndex>>,
 }
 
 impl StandardsFilter {
+    /// Create a filter over standards that aren't from a library
+    pub fn new(standards: Vec<Arc<Standard>>) -> Self {
+        StandardsFilter {
+            standards,
+            search_index: None,
+        }
+    }
+
+    /// Narrow to a subset of standards, keeping the library's search index
+    fn with_standards(&self, mut standards: Vec<Arc<Standard>>) -> Self {
+        standards.sort_by(|a, b| a.name.cmp(&b.name));
+        StandardsFilter {
+            standards,
+            search_index: self.search_index.clone(),
+        }
+    }
+
     /// Return a standard by name or alias
-    pub fn get(&self, standard_name_or_alias: &str) -> Result<&Standard, &'static str> {
+    pub fn get(&self, standard_name_or_alias: &str) -> Result<&Arc<Standard>> {
         for standard in &self.standards {
             if standard.name == standard_name_or_alias
                 || standard
@@ -26,7 +103,24 @@ impl StandardsFilter {
                 return Ok(standard);
             }
         }
-        Err("Unknown Standard")
+        Err(UnknownStandard::new(
+            standard_name_or_alias,
+            self.standards.iter().map(Arc::as_ref),
+        )
+        .into())
+    }
+
+    /// Return a standard by name or alias, along with the alias that matched
+    pub fn resolve(&self, standard_name_or_alias: &str) -> Result<ResolvedStandard> {
+        let standard = self.get(standard_name_or_alias)?;
+        let alias =
+            (standard.name != standard_name_or_alias).then(|| standard_name_or_alias.to_string());
+
+        Ok(ResolvedStandard {
+            standard: standard.clone(),
+            alias,
+            table_version: None,
+        })
     }
 
     /// Returns standards by common variable name
@@ -34,7 +128,7 @@ impl StandardsFilter {
     /// "mean_period", and "MEAN_PERIOD" will all match
     pub fn by_variable_name(&self, variable_name: &str) -> Self {
         let normalized_search = normalize_variable_name(variable_name);
-        let mut standards: Vec<Standard> = self
+        let standards: Vec<Arc<Standard>> = self
             .standards
             .iter()
             .filter(|standard| {
@@ -45,13 +139,12 @@ impl StandardsFilter {
             })
             .cloned()
             .collect();
-        standards.sort_by_key(|s| s.name.clone());
-        StandardsFilter { standards }
+        self.with_standards(standards)
     }
 
     /// Returns standards by IOOS category
     pub fn by_ioos_category(&self, category: &str) -> Self {
-        let mut standards: Vec<Standard> = self
+        let standards: Vec<Arc<Standard>> = self
             .standards
             .iter()
             .filter(|standard| {
@@ -62,13 +155,26 @@ impl StandardsFilter {
             })
             .cloned()
             .collect();
-        standards.sort_by_key(|s| s.name.clone());
-        StandardsFilter { standards }
+        self.with_standards(standards)
+    }
+
+    /// Filter by IOOS category, inferring it for standards without one in knowledge
+    ///
+    /// Inferred categories only count when they are at least `min_confidence` (from 0 to 1).
+    pub fn by_inferred_ioos_category(&self, category: &str, min_confidence: f64) -> Self {
+        self.matching(|standard| match &standard.ioos_category {
+            Some(curated) => curated.eq_ignore_ascii_case(category),
+            None => {
+                let inferred = standard.infer_ioos_category();
+                inferred.confidence >= min_confidence
+                    && inferred.category.eq_ignore_ascii_case(category)
+            }
+        })
     }
 
     /// Returns standards for a given unit
     pub fn by_unit(&self, unit: &str) -> Self {
-        let mut standards: Vec<Standard> = self
+        let standards: Vec<Arc<Standard>> = self
             .standards
             .iter()
             .filter(|standard| {
@@ -76,39 +182,432 @@ impl StandardsFilter {
             })
             .cloned()
             .collect();
-        standards.sort_by_key(|s| s.name.clone());
-        StandardsFilter { standards }
+        self.with_standards(standards)
     }
 
     /// Returns standards that have QARTOD tests
     pub fn has_qartod_tests(&self) -> Self {
-        let mut standards: Vec<Standard> = self
+        let standards: Vec<Arc<Standard>> = self
             .standards
             .iter()
             .filter(|standard| !standard.qartod.is_empty())
             .cloned()
             .collect();
-        standards.sort_by_key(|s| s.name.clone());
-        StandardsFilter { standards }
+        self.with_standards(standards)
     }
 
-    /// Returns standards that match a search pattern
-    pub fn search(&self, search_str: &str) -> Self {
-        let mut search_index: SearchIndex<usize> = SearchIndex::default();
+    /// Returns standards with a QARTOD test suite that includes a type of test
+    pub fn by_qartod_test_type(&self, test_type: &QartodTestTypes) -> Self {
+        self.matching(|standard| {
+            standard
+                .qartod
+                .iter()
+                .any(|suite| suite.info().test_types.contains(test_type))
+        })
+    }
+
+    /// Returns standards with an extra attribute,
+    /// and if a value is given, where the attribute displays as that value
+    pub fn by_extra_attr(&self, key: &str, value: Option<&str>) -> Self {
+        self.matching(|standard| {
+            standard
+                .extra_attrs
+                .get(key)
+                .map(ToString::to_string)
+                .is_some_and(|attr| value.is_none_or(|value| attr == value))
+        })
+    }
 
-        self.standards
+    /// Returns standards that list another standard as a sibling
+    pub fn by_sibling(&self, standard_name: &str) -> Self {
+        self.matching(|standard| {
+            standard
+                .sibling_standards
+                .iter()
+                .any(|sibling| sibling == standard_name)
+        })
+    }
+
+    /// Returns standards that list another standard as related
+    pub fn by_related(&self, standard_name: &str) -> Self {
+        self.matching(|standard| {
+            standard
+                .related_standards
+                .iter()
+                .any(|related| related == standard_name)
+        })
+    }
+
+    /// Returns standards that have IOOS knowledge beyond the CF standard name table
+    pub fn has_knowledge(&self) -> Self {
+        self.matching(Standard::has_knowledge)
+    }
+
+    /// Returns standards with an alias, without matching standard names
+    pub fn by_alias(&self, alias: &str) -> Self {
+        self.matching(|standard| standard.aliases.iter().any(|a| a == alias))
+    }
+
+    /// Returns standards whose name starts with a prefix
+    pub fn by_name_prefix(&self, prefix: &str) -> Self {
+        self.matching(|standard| standard.name.starts_with(prefix))
+    }
+
+    /// Returns standards whose name ends with a suffix
+    pub fn by_name_suffix(&self, suffix: &str) -> Self {
+        self.matching(|standard| standard.name.ends_with(suffix))
+    }
+
+    /// Returns standards whose name matches a regular expression
+    ///
+    /// The expression isn't anchored, so use `^` and `$` to match the whole name.
+    pub fn by_name_regex(&self, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).map_err(|e| Error::InvalidArgument {
+            argument: "regex".to_string(),
+            message: e.to_string(),
+        })?;
+        Ok(self.matching(|standard| regex.is_match(&standard.name)))
+    }
+
+    /// Returns standards that match a structured query,
+    /// like `category:Temperature AND unit:K AND NOT name:*_at_sea_floor`
+    ///
+    /// See [`Query`] for the syntax.
+    pub fn query(&self, query: &str) -> Result<Self> {
+        Ok(Query::parse(query)?.apply(self))
+    }
+
+    /// Returns standards that a predicate is true for
+    pub(crate) fn matching(&self, predicate: impl Fn(&Standard) -> bool) -> Self {
+        let standards: Vec<Arc<Standard>> = self
+            .standards
+            .iter()
+            .filter(|standard| predicate(standard))
+            .cloned()
+            .collect();
+        self.with_standards(standards)
+    }
+
+    /// Returns standards that are in either filter
+    pub fn union(&self, other: &StandardsFilter) -> Self {
+        let names: HashSet<&str> = self.standards.iter().map(|s| s.name.as_str()).collect();
+        let standards: Vec<Arc<Standard>> = self
+            .standards
             .iter()
-            .enumerate()
-            .for_each(|(index, element)| search_index.insert(&index, element));
-        let results = search_index.search(search_str);
+            .chain(
+                other
+                    .standards
+                    .iter()
+                    .filter(|standard| !names.contains(standard.name.as_str())),
+            )
+            .cloned()
+            .collect();
+        self.with_standards(standards)
+    }
+
+    /// Returns standards that are in both filters
+    pub fn intersection(&self, other: &StandardsFilter) -> Self {
+        let names: HashSet<&str> = other.standards.iter().map(|s| s.name.as_str()).collect();
+        self.matching(|standard| names.contains(standard.name.as_str()))
+    }
 
-        let mut standards: Vec<Standard> = Vec::new();
-        for index in results {
-            standards.push(self.standards[*index].clone());
+    /// Returns standards that aren't in the other filter
+    pub fn difference(&self, other: &StandardsFilter) -> Self {
+        let names: HashSet<&str> = other.standards.iter().map(|s| s.name.as_str()).collect();
+        self.matching(|standard| !names.contains(standard.name.as_str()))
+    }
+
+    /// Sort standards by a field, breaking ties by name
+    ///
+    /// Standards without a value for the field sort first,
+    /// or last when descending.
+    pub fn sort_by(&self, key: SortKey, descending: bool) -> Self {
+        let mut standards = self.standards.clone();
+        standards.sort_by(|a, b| {
+            key.value(a)
+                .cmp(key.value(b))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        if descending {
+            standards.reverse();
+        }
+        StandardsFilter {
+            standards,
+            search_index: self.search_index.clone(),
+        }
+    }
+
+    /// Returns a page of standards, skipping `offset` standards
+    /// and returning at most `limit`
+    pub fn paginate(&self, offset: usize, limit: usize) -> Self {
+        StandardsFilter {
+            standards: self
+                .standards
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+            search_index: self.search_index.clone(),
         }
+    }
+
+    /// Returns standards that match a search pattern, most relevant first
+    ///
+    /// Filters from a library query the library's shared search index,
+    /// otherwise a temporary index is built over the filtered standards.
+    /// At most 100 standards are returned, see [`StandardsFilter::search_all`] for every match.
+    pub fn search(&self, search_str: &str) -> Self {
+        self.ranked_filter(self.search_ranked(search_str))
+    }
+
+    /// Returns every standard that matches a search pattern, most relevant first
+    ///
+    /// Unlike [`StandardsFilter::search`], the results aren't limited,
+    /// so they can be paged through with [`StandardsFilter::paginate`].
+    pub fn search_all(&self, search_str: &str) -> Self {
+        self.ranked_filter(rank(search_str, &self.search_matches(search_str)))
+    }
+
+    /// Keep the ranked order, rather than sorting by name
+    fn ranked_filter(&self, results: Vec<SearchResult>) -> Self {
+        StandardsFilter {
+            standards: results.into_iter().map(|result| result.standard).collect(),
+            search_index: self.search_index.clone(),
+        }
+    }
+
+    /// Returns standards that match a search pattern, with their scores and which fields matched
+    ///
+    /// At most the 100 most relevant standards are returned.
+    pub fn search_ranked(&self, search_str: &str) -> Vec<SearchResult> {
+        let mut results = rank(search_str, &self.search_matches(search_str));
+        results.truncate(MAX_SEARCH_RESULTS);
+        results
+    }
+
+    /// Every standard that matches a search pattern, unranked and without a limit
+    fn search_matches(&self, search_str: &str) -> Vec<Arc<Standard>> {
+        match &self.search_index {
+            Some(search_index) => {
+                let matches: HashSet<&str> = search_index.search(search_str).into_iter().collect();
+                self.standards
+                    .iter()
+                    .filter(|standard| matches.contains(standard.name.as_str()))
+                    .cloned()
+                    .collect()
+            }
+            None => {
+                let mut search_index: SearchIndex<usize> = SearchIndex::default();
+                self.standards
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, element)| search_index.insert(&index, element.as_ref()));
+
+                search_index
+                    .search_with(&SearchType::Live, &self.standards.len(), search_str)
+                    .into_iter()
+                    .map(|index| self.standards[*index].clone())
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns every standard that matches a search pattern, sorted by name like other filters
+    ///
+    /// The matches aren't limited, so they can be combined with other filters without losing any.
+    pub(crate) fn search_unranked(&self, search_str: &str) -> Self {
+        let standards = self.search_matches(search_str);
+        self.with_standards(standards)
+    }
+
+    /// NetCDF CDL skeleton with a variable for each standard, along an unlimited time dimension
+    pub fn to_cdl(&self, dataset_name: &str, cf_version: Option<&str>) -> String {
+        crate::cdl::dataset(
+            dataset_name,
+            self.standards.iter().map(|standard| standard.as_ref()),
+            cf_version,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::StandardsLibrary;
+
+    fn names(filter: &StandardsFilter) -> Vec<&str> {
+        filter.standards.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn filter() -> StandardsFilter {
+        let standard = |name: &str, unit: &str| Standard {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            ..Default::default()
+        };
+
+        StandardsFilter::new(vec![
+            Arc::new(Standard {
+                aliases: vec!["air_pressure_at_sea_level".to_string()],
+                ioos_category: Some("Meteorology".to_string()),
+                related_standards: vec!["air_pressure".to_string()],
+                ..standard("air_pressure_at_mean_sea_level", "Pa")
+            }),
+            Arc::new(standard("air_pressure", "Pa")),
+            Arc::new(Standard {
+                sibling_standards: vec!["sea_water_practical_salinity".to_string()],
+                extra_attrs: BTreeMap::from([("positive".to_string(), "down".into())]),
+                ..standard("sea_water_temperature", "K")
+            }),
+            Arc::new(Standard {
+                sibling_standards: vec!["sea_water_temperature".to_string()],
+                ..standard("sea_water_practical_salinity", "1")
+            }),
+        ])
+    }
+
+    #[test]
+    fn narrows_by_predicates() {
+        let filter = filter();
+
+        assert_eq!(
+            names(&filter.by_extra_attr("positive", None)),
+            vec!["sea_water_temperature"]
+        );
+        assert!(filter
+            .by_extra_attr("positive", Some("up"))
+            .standards
+            .is_empty());
+        assert_eq!(
+            names(&filter.by_sibling("sea_water_temperature")),
+            vec!["sea_water_practical_salinity"]
+        );
+        assert_eq!(
+            names(&filter.by_related("air_pressure")),
+            vec!["air_pressure_at_mean_sea_level"]
+        );
+        assert_eq!(names(&filter.has_knowledge()).len(), 3);
+        assert_eq!(
+            names(&filter.by_inferred_ioos_category("meteorology", 0.5)),
+            vec!["air_pressure", "air_pressure_at_mean_sea_level"]
+        );
+        assert_eq!(
+            names(&filter.by_inferred_ioos_category("Salinity", 0.5)),
+            vec!["sea_water_practical_salinity"]
+        );
+        assert!(filter
+            .by_inferred_ioos_category("Salinity", 0.9)
+            .standards
+            .is_empty());
+        assert_eq!(
+            names(&filter.by_alias("air_pressure_at_sea_level")),
+            vec!["air_pressure_at_mean_sea_level"]
+        );
+        assert!(filter
+            .by_alias("air_pressure_at_mean_sea_level")
+            .standards
+            .is_empty());
+        assert_eq!(names(&filter.by_name_prefix("sea_water_")).len(), 2);
+        assert_eq!(
+            names(&filter.by_name_suffix("_level")),
+            vec!["air_pressure_at_mean_sea_level"]
+        );
+        assert_eq!(
+            names(&filter.by_name_regex("^air_pressure$").unwrap()),
+            vec!["air_pressure"]
+        );
+        assert!(matches!(
+            filter.by_name_regex("air_(pressure"),
+            Err(Error::InvalidArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn combines_filters() {
+        let filter = filter();
+        let pascals = filter.by_unit("Pa");
+        let sea_water = filter.by_name_prefix("sea_water_");
+        let pressure = filter.by_name_regex("pressure").unwrap();
+
+        assert_eq!(pascals.union(&sea_water).standards.len(), 4);
+        assert_eq!(
+            names(&pascals.intersection(&filter.by_related("air_pressure"))),
+            vec!["air_pressure_at_mean_sea_level"]
+        );
+        assert_eq!(
+            names(&filter.difference(&pressure)),
+            vec!["sea_water_practical_salinity", "sea_water_temperature"]
+        );
+    }
+
+    #[test]
+    fn sorts_and_paginates() {
+        let filter = filter();
+
+        let by_unit = filter.sort_by(SortKey::Unit, false);
+        assert_eq!(
+            names(&by_unit),
+            vec![
+                "sea_water_practical_salinity",
+                "sea_water_temperature",
+                "air_pressure",
+                "air_pressure_at_mean_sea_level",
+            ]
+        );
+        assert_eq!(
+            names(&by_unit.paginate(1, 2)),
+            vec!["sea_water_temperature", "air_pressure"]
+        );
+        assert!(by_unit.paginate(10, 2).standards.is_empty());
+
+        let descending = filter.sort_by(SortKey::Name, true);
+        assert_eq!(descending.standards[0].name, "sea_water_temperature");
+
+        assert_eq!("category".parse::<SortKey>(), Ok(SortKey::IoosCategory));
+        assert!("colour".parse::<SortKey>().is_err());
+    }
+
+    #[test]
+    fn narrows_by_qartod_test_type() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library.load_test_suites();
+
+        let spike: QartodTestTypes = "spike_test".parse().unwrap();
+        let filtered = library.filter().by_qartod_test_type(&spike);
+        assert!(names(&filtered).contains(&"sea_surface_height_above_geopotential_datum"));
+        assert!(filtered.standards.iter().all(|standard| {
+            standard
+                .qartod
+                .iter()
+                .any(|suite| suite.info().test_types.contains(&QartodTestTypes::Spike))
+        }));
+
+        assert!(library
+            .filter()
+            .by_qartod_test_type(&QartodTestTypes::DensityInversion)
+            .standards
+            .is_empty());
+        assert!("not_a_test".parse::<QartodTestTypes>().is_err());
+    }
+
+    #[test]
+    fn search_all_pages_past_the_limit() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+
+        let top = library.filter().search("temperature");
+        assert_eq!(top.standards.len(), MAX_SEARCH_RESULTS);
 
-        standards.sort_by_key(|s| s.name.clone());
+        let all = library.filter().search_all("temperature");
+        assert!(all.standards.len() > MAX_SEARCH_RESULTS);
+        assert_eq!(names(&all)[..MAX_SEARCH_RESULTS], names(&top)[..]);
 
-        StandardsFilter { standards }
+        let page = all.paginate(MAX_SEARCH_RESULTS, 10);
+        assert_eq!(page.standards.len(), 10);
     }
 }
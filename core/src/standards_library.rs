@@ -1,39 +1,219 @@
-use std::collections::{HashMap, HashSet};
-
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::attr_value::AttrValue;
+use crate::cf::CfTable;
+use crate::check::{self, VariableCheck};
+use crate::diff::{diff_libraries, LibraryDiff};
+use crate::erddap::{DatasetMetadata, ErddapDataset, ErddapDatasetType};
+use crate::error::{Error, Result};
+use crate::grammar::{ComposedName, NameParts};
+use crate::graph::StandardsGraph;
+use crate::header::NetcdfHeader;
+use crate::ioos_profile::{self, ProfileIssue};
+use crate::knowledge_rules::KnowledgeRule;
 use crate::qartod::StaticQcTestSuite;
+use crate::search_index::StandardsSearchIndex;
 use crate::standards_filter::StandardsFilter;
+use crate::suggest::{suggest, UnknownStandard};
+use crate::upgrade::{upgrade_cdl, ResolvedStandard, StandardNameUpgrade};
 use crate::{standard::Standard, Knowledge};
 
+/// Version of the snapshot format, changed when older snapshots can't be read
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Fully built library as it is serialized
+#[derive(Serialize, Deserialize)]
+struct LibrarySnapshot {
+    snapshot_version: u32,
+    cf_version: Option<String>,
+    cf_last_modified: Option<String>,
+    /// Standards with knowledge, rules, and test suites already applied, sorted by name
+    standards: Vec<Arc<Standard>>,
+    rules: Vec<KnowledgeRule>,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct StandardsLibrary {
-    pub standards: HashMap<String, Standard>,
+    /// Standards by name
+    ///
+    /// Standards are shared with filters and lookups rather than copied,
+    /// and are only changed through `insert` and the load and apply methods,
+    /// which keep the alias and search indexes up to date.
+    standards: HashMap<String, Arc<Standard>>,
+
+    /// Canonical standard name by alias
+    aliases: HashMap<String, String>,
+
+    /// Search index shared with filters, built on the first search
+    search_index: Arc<StandardsSearchIndex>,
+
+    /// Rules that fill in default knowledge for families of standards
+    rules: Vec<KnowledgeRule>,
+
+    /// Version number of the loaded CF standard name table
+    pub cf_version: Option<String>,
+
+    /// When the loaded CF standard name table was last modified
+    pub cf_last_modified: Option<String>,
 }
 
 impl StandardsLibrary {
     /// Load CF standards from library
-    pub fn load_cf_standards(&mut self) {
-        use crate::cf::cf_standards;
+    pub fn load_cf_standards(&mut self) -> Result<()> {
+        use crate::cf::builtin_cf_table;
 
-        self.standards.extend(cf_standards());
+        self.load_cf_table(builtin_cf_table()?);
+        Ok(())
     }
 
+    /// Load CF standards from the official CF standard name table XML
+    pub fn load_cf_standards_from_xml(&mut self, xml: &str) -> Result<()> {
+        let table = crate::cf::cf_table_from_xml(xml)?;
+        self.load_cf_table(table);
+        Ok(())
+    }
+
+    /// Load CF standards from the YAML format used by `standards/_cf_standards.yaml`
+    pub fn load_cf_standards_from_yaml(&mut self, yaml: &str) -> Result<()> {
+        let table = crate::cf::cf_table_from_yaml(yaml)?;
+        self.load_cf_table(table);
+        Ok(())
+    }
+
+    /// Load CF standards from a local XML or YAML standard name table
+    pub fn load_cf_standards_from_path(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let table = crate::cf::cf_table_from_path(path)?;
+        self.load_cf_table(table);
+        Ok(())
+    }
+
+    /// Load a CF standard name table and record its version
+    ///
+    /// Replaces any standards that are already loaded, so that only the names and aliases
+    /// of that version of the table are known. Load knowledge and test suites after the table,
+    /// as they are replaced too. Rules that are already loaded are applied to the new standards.
+    pub fn load_cf_table(&mut self, table: CfTable) {
+        self.cf_version = table.version_number;
+        self.cf_last_modified = table.last_modified;
+        self.standards.clear();
+        self.aliases.clear();
+        self.search_index = Arc::default();
+        for standard in table.standards.into_values() {
+            self.insert(standard);
+        }
+    }
+
+    /// Standards by name
+    pub fn standards(&self) -> &HashMap<String, Arc<Standard>> {
+        &self.standards
+    }
+
+    /// Add or replace a standard, filling in rule defaults
+    /// and indexing its aliases and search terms
+    pub fn insert(&mut self, mut standard: Standard) {
+        let name = standard.name.clone();
+        for rule in self.rules.iter().filter(|rule| rule.matches(&name)) {
+            rule.fill(&mut standard);
+        }
+        for alias in &standard.aliases {
+            self.aliases.insert(alias.clone(), name.clone());
+        }
+        let standard = Arc::new(standard);
+        // Only copies the index if a filter from before this change is still around
+        Arc::make_mut(&mut self.search_index).insert(standard.clone());
+        self.standards.insert(name, standard);
+    }
+
+    /// Return a filter over all standards in the library
+    ///
+    /// The filter shares standards and the search index with the library,
+    /// so it is cheap to create.
     pub fn filter(&self) -> StandardsFilter {
         StandardsFilter {
             standards: self.standards.values().cloned().collect(),
+            search_index: Some(self.search_index.clone()),
+        }
+    }
+
+    /// Return the canonical name for a standard name or alias, if it is known
+    pub fn canonical_name<'a>(&'a self, standard_name_or_alias: &'a str) -> Option<&'a str> {
+        if self.standards.contains_key(standard_name_or_alias) {
+            return Some(standard_name_or_alias);
         }
+        self.aliases
+            .get(standard_name_or_alias)
+            .map(String::as_str)
+            .filter(|name| self.standards.contains_key(*name))
+    }
+
+    /// Standard by name or alias, without suggestions when it isn't known
+    ///
+    /// For callers that expect misses, as suggestions score every standard name.
+    pub(crate) fn lookup(&self, standard_name_or_alias: &str) -> Option<Arc<Standard>> {
+        self.canonical_name(standard_name_or_alias)
+            .and_then(|name| self.standards.get(name))
+            .cloned()
     }
 
     /// Return a standard by name or alias
-    pub fn get(&self, standard_name_or_alias: &str) -> Result<Standard, &'static str> {
-        let filter = self.filter();
-        let standard = filter.get(standard_name_or_alias)?;
-        Ok(standard.clone())
+    pub fn get(&self, standard_name_or_alias: &str) -> Result<Arc<Standard>> {
+        self.lookup(standard_name_or_alias).ok_or_else(|| {
+            UnknownStandard::new(
+                standard_name_or_alias,
+                self.standards.values().map(Arc::as_ref),
+            )
+            .into()
+        })
+    }
+
+    /// Return a standard by name or alias, noting if a superseded alias was used
+    pub fn resolve(&self, standard_name_or_alias: &str) -> Result<ResolvedStandard> {
+        let standard = self.get(standard_name_or_alias)?;
+        let alias =
+            (standard.name != standard_name_or_alias).then(|| standard_name_or_alias.to_string());
+        let table_version = alias.as_ref().and(self.cf_version.clone());
+
+        Ok(ResolvedStandard {
+            standard,
+            alias,
+            table_version,
+        })
+    }
+
+    /// Suggest standard names that are similar to a name that may have a typo
+    pub fn suggest(&self, standard_name_or_alias: &str, limit: usize) -> Vec<String> {
+        suggest(
+            standard_name_or_alias,
+            self.standards.values().map(Arc::as_ref),
+            limit,
+        )
+    }
+
+    /// Return the current standard if the name is a superseded alias
+    pub fn upgrade_standard_name(&self, standard_name: &str) -> Option<ResolvedStandard> {
+        let canonical = self.canonical_name(standard_name)?;
+        if canonical == standard_name {
+            return None;
+        }
+        self.resolve(standard_name).ok()
+    }
+
+    /// Rewrite a CDL header to use current canonical standard names
+    pub fn upgrade_cdl(&self, cdl: &str) -> (String, Vec<StandardNameUpgrade>) {
+        upgrade_cdl(self, cdl)
     }
 
     /// Update the loaded standards with knowledge
     pub fn apply_knowledge(&mut self, knowledge: Vec<Knowledge>) {
         for know in knowledge {
             if let Some(standard) = self.standards.get(&know.name) {
+                // Rule defaults are filled in again around the knowledge when inserted
+                let standard = &standard.without_rule_defaults();
                 let mut common_variable_names = standard.common_variable_names.clone();
                 common_variable_names.append(&mut know.common_variable_names.clone());
 
@@ -72,15 +252,52 @@ impl StandardsLibrary {
                     ..standard.clone()
                 };
 
-                self.standards.insert(know.name, new_standard);
+                self.insert(new_standard);
             }
         }
     }
 
-    /// Load community knowledge
-    pub fn load_knowledge(&mut self) {
-        let knowledge = crate::library_knowledge::load_knowledge();
+    /// Fill in default knowledge for the standards that rules match
+    ///
+    /// Rules are kept, so they also fill in standards or knowledge loaded later,
+    /// but never override knowledge for a specific standard or an earlier rule.
+    pub fn apply_rules(&mut self, rules: Vec<KnowledgeRule>) {
+        let matched: Vec<Arc<Standard>> = self
+            .standards
+            .values()
+            .filter(|standard| rules.iter().any(|rule| rule.matches(&standard.name)))
+            .cloned()
+            .collect();
+
+        self.rules.extend(rules);
+
+        for standard in matched {
+            self.insert(Standard::clone(&standard));
+        }
+    }
+
+    /// Rules that have been applied
+    pub fn rules(&self) -> &[KnowledgeRule] {
+        &self.rules
+    }
+
+    /// Rules that apply to a standard, in the order they take precedence
+    pub fn rules_for(&self, standard_name_or_alias: &str) -> Result<Vec<&KnowledgeRule>> {
+        let standard = self.get(standard_name_or_alias)?;
+        Ok(self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(&standard.name))
+            .collect())
+    }
+
+    /// Load community knowledge, and the rules for families of standards
+    pub fn load_knowledge(&mut self) -> Result<()> {
+        let knowledge = crate::library_knowledge::load_knowledge()?;
         self.apply_knowledge(knowledge);
+        let rules = crate::library_knowledge::load_rules()?;
+        self.apply_rules(rules);
+        Ok(())
     }
 
     /// Load test suites
@@ -92,13 +309,80 @@ impl StandardsLibrary {
             if let Some(standard) = self.standards.get(&name) {
                 let new_standard = Standard {
                     qartod: suite,
-                    ..standard.clone()
+                    ..Standard::clone(standard)
                 };
-                self.standards.insert(name, new_standard);
+                self.insert(new_standard);
             }
         }
     }
 
+    /// Compare this library with another, such as a newer CF table version or a changed knowledge set
+    pub fn diff(&self, other: &StandardsLibrary) -> LibraryDiff {
+        diff_libraries(self, other)
+    }
+
+    /// Graph of the related and sibling links between standards
+    pub fn graph(&self) -> StandardsGraph {
+        StandardsGraph::new(self)
+    }
+
+    /// Compose a standard name from its parts, and check if it is in the library
+    pub fn check_name(&self, parts: &NameParts) -> Result<ComposedName> {
+        ComposedName::check(parts, self)
+    }
+
+    /// ERDDAP `<dataset>` skeleton for source columns, resolved to standards by name, alias,
+    /// or common variable name
+    pub fn erddap_dataset(
+        &self,
+        columns: &[impl AsRef<str>],
+        dataset_type: ErddapDatasetType,
+        metadata: DatasetMetadata,
+    ) -> Result<ErddapDataset> {
+        ErddapDataset::new(self, columns, dataset_type, metadata)
+    }
+
+    /// Check the variables of a NetCDF header against the standards and their knowledge
+    pub fn check_header(&self, header: &NetcdfHeader) -> Vec<VariableCheck> {
+        check::check_header(self, header)
+    }
+
+    /// Parse a CDL header, such as from `ncdump -h`, and check its variables
+    pub fn check_cdl(&self, cdl: &str) -> Result<Vec<VariableCheck>> {
+        Ok(self.check_header(&NetcdfHeader::from_cdl(cdl)?))
+    }
+
+    /// Read the header of a NetCDF-3 file, or CDL, and check its variables
+    pub fn check_netcdf(&self, bytes: &[u8]) -> Result<Vec<VariableCheck>> {
+        Ok(self.check_header(&NetcdfHeader::from_bytes(bytes)?))
+    }
+
+    /// Check a variable's attributes against the IOOS Metadata Profile 1.2,
+    /// returning missing or invalid attributes, required ones first
+    pub fn check_ioos_profile(&self, attrs: &BTreeMap<String, AttrValue>) -> Vec<ProfileIssue> {
+        ioos_profile::check(self, attrs)
+    }
+
+    /// Fill in a variable's missing attributes from the knowledge for its `standard_name`,
+    /// returning the names of the attributes that were filled in
+    pub fn fill_ioos_profile_defaults(
+        &self,
+        attrs: &mut BTreeMap<String, AttrValue>,
+    ) -> Vec<String> {
+        ioos_profile::fill_defaults(self, attrs)
+    }
+
+    /// Snapshot of the fully built library as JSON,
+    /// that can be reloaded with `from_snapshot` without loading or merging knowledge again
+    pub fn to_snapshot(&self) -> String {
+        serde_json::to_string(self).expect("standards always serialize")
+    }
+
+    /// Reload a library from a snapshot made by `to_snapshot`
+    pub fn from_snapshot(snapshot: &str) -> Result<Self> {
+        serde_json::from_str(snapshot).map_err(|e| Error::parse("library snapshot", e))
+    }
+
     /// Return a set of all known IOOS categories
     pub fn known_ioos_categories(&self) -> HashSet<String> {
         self.standards
@@ -108,6 +392,47 @@ impl StandardsLibrary {
     }
 }
 
+impl Serialize for StandardsLibrary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut standards: Vec<Arc<Standard>> = self.standards.values().cloned().collect();
+        standards.sort_by(|a, b| a.name.cmp(&b.name));
+
+        LibrarySnapshot {
+            snapshot_version: SNAPSHOT_VERSION,
+            cf_version: self.cf_version.clone(),
+            cf_last_modified: self.cf_last_modified.clone(),
+            standards,
+            rules: self.rules.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StandardsLibrary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let snapshot = LibrarySnapshot::deserialize(deserializer)?;
+        if snapshot.snapshot_version != SNAPSHOT_VERSION {
+            return Err(D::Error::custom(format!(
+                "snapshot version {} can't be read, expected version {SNAPSHOT_VERSION}",
+                snapshot.snapshot_version
+            )));
+        }
+
+        let mut library = StandardsLibrary {
+            cf_version: snapshot.cf_version,
+            cf_last_modified: snapshot.cf_last_modified,
+            ..Default::default()
+        };
+        // Rules were already applied when the snapshot was made, so they are restored afterwards
+        for standard in snapshot.standards {
+            let standard = Arc::unwrap_or_clone(standard);
+            library.insert(standard);
+        }
+        library.rules = snapshot.rules;
+        Ok(library)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,13 +440,70 @@ mod tests {
     #[test]
     fn can_load_standards() {
         let mut library = StandardsLibrary::default();
-        library.load_cf_standards();
+        library.load_cf_standards().unwrap();
+    }
+
+    #[test]
+    fn can_load_standards_from_xml() {
+        let xml = r#"<?xml version="1.0"?>
+<standard_name_table>
+   <version_number>12</version_number>
+   <last_modified>2009-10-19T12:00:00Z</last_modified>
+   <entry id="sea_water_temperature">
+      <canonical_units>K</canonical_units>
+      <description>Sea water temperature is the in situ temperature of the sea water.</description>
+   </entry>
+</standard_name_table>
+"#;
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards_from_xml(xml).unwrap();
+
+        assert_eq!(library.standards.len(), 1);
+        assert_eq!(library.cf_version.as_deref(), Some("12"));
+        assert_eq!(
+            library.cf_last_modified.as_deref(),
+            Some("2009-10-19T12:00:00Z")
+        );
+        assert!(library.get("air_pressure_at_mean_sea_level").is_err());
+        assert_eq!(library.get("sea_water_temperature").unwrap().unit, "K");
+    }
+
+    #[test]
+    fn loading_a_table_replaces_the_last() {
+        let xml = r#"<?xml version="1.0"?>
+<standard_name_table>
+   <version_number>12</version_number>
+   <entry id="sea_water_temperature">
+      <canonical_units>K</canonical_units>
+   </entry>
+   <alias id="sea_water_temp">
+      <entry_id>sea_water_temperature</entry_id>
+   </alias>
+</standard_name_table>
+"#;
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library.load_cf_standards_from_xml(xml).unwrap();
+
+        assert_eq!(library.standards.len(), 1);
+        assert!(library.get("air_pressure_at_sea_level").is_err());
+        assert!(library.get("air_pressure_at_mean_sea_level").is_err());
+        assert_eq!(
+            library.get("sea_water_temp").unwrap().name,
+            "sea_water_temperature"
+        );
+        assert_eq!(library.filter().search("pressure").standards.len(), 0);
+
+        library.load_cf_standards().unwrap();
+        assert!(library.get("sea_water_temp").is_err());
+        assert!(library.standards.len() > 1);
     }
 
     #[test]
     fn can_get_standard() {
         let mut library = StandardsLibrary::default();
-        library.load_cf_standards();
+        library.load_cf_standards().unwrap();
         let pressure = library.get("air_pressure_at_mean_sea_level").unwrap();
         assert_eq!(pressure.name, "air_pressure_at_mean_sea_level");
     }
@@ -129,15 +511,63 @@ mod tests {
     #[test]
     fn can_get_standard_by_alias() {
         let mut library = StandardsLibrary::default();
-        library.load_cf_standards();
+        library.load_cf_standards().unwrap();
         let pressure = library.get("air_pressure_at_sea_level").unwrap();
         assert_eq!(pressure.name, "air_pressure_at_mean_sea_level");
     }
 
+    #[test]
+    fn inserted_standards_are_indexed() {
+        let mut library = StandardsLibrary::default();
+        library.insert(Standard {
+            name: "sea_water_glow".to_string(),
+            aliases: vec!["sea_glow".to_string()],
+            description: "Glow of the sea".to_string(),
+            ..Default::default()
+        });
+
+        assert_eq!(library.standards().len(), 1);
+        assert_eq!(library.get("sea_glow").unwrap().name, "sea_water_glow");
+        assert_eq!(library.filter().search("glow").standards.len(), 1);
+    }
+
+    #[test]
+    fn lookups_share_standards() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+
+        let by_name = library.get("air_pressure_at_mean_sea_level").unwrap();
+        let by_alias = library.get("air_pressure_at_sea_level").unwrap();
+        assert!(Arc::ptr_eq(&by_name, &by_alias));
+
+        let looked_up = library.lookup("air_pressure_at_sea_level").unwrap();
+        assert!(Arc::ptr_eq(&by_name, &looked_up));
+        assert!(library.lookup("air_pressure_at_sea_levle").is_none());
+
+        let filtered = library.filter().by_unit("Pa");
+        let from_filter = filtered.get("air_pressure_at_mean_sea_level").unwrap();
+        assert!(Arc::ptr_eq(&by_name, from_filter));
+    }
+
+    #[test]
+    fn alias_index_survives_knowledge() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+
+        assert_eq!(
+            library.canonical_name("air_pressure_at_sea_level"),
+            Some("air_pressure_at_mean_sea_level")
+        );
+        let pressure = library.get("air_pressure_at_sea_level").unwrap();
+        assert!(pressure.long_name.is_some());
+        assert_eq!(library.canonical_name("not_a_standard"), None);
+    }
+
     #[test]
     fn can_apply_knowledge() {
         let mut library = StandardsLibrary::default();
-        library.load_cf_standards();
+        library.load_cf_standards().unwrap();
         let pressure = library.get("air_pressure_at_mean_sea_level").unwrap();
         assert_eq!(pressure.name, "air_pressure_at_mean_sea_level");
         assert_eq!(pressure.long_name, None);
@@ -160,10 +590,169 @@ mod tests {
         assert_ne!(pressure, updated_pressure);
     }
 
+    #[test]
+    fn knowledge_overrides_rules() {
+        use crate::knowledge_rules::NamePattern;
+
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+
+        let mut rule = KnowledgeRule::new(NamePattern::glob("sea_surface_wave_*"));
+        rule.ioos_category = Some("Other".to_string());
+        rule.other_units = vec!["ft".to_string()];
+        library.apply_rules(vec![rule]);
+
+        let period = library.get("sea_surface_wave_mean_period").unwrap();
+        assert_eq!(period.ioos_category.as_deref(), Some("Other"));
+        assert_eq!(
+            library
+                .rules_for("sea_surface_wave_mean_period")
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(library.rules_for("air_temperature").unwrap().is_empty());
+
+        // Knowledge applied after the rules still takes precedence,
+        // and the rules fill in anything it leaves out
+        library.apply_knowledge(vec![Knowledge {
+            name: "sea_surface_wave_mean_period".to_string(),
+            ioos_category: Some("Surface Waves".to_string()),
+            other_units: vec!["min".to_string()],
+            ..Default::default()
+        }]);
+        let period = library.get("sea_surface_wave_mean_period").unwrap();
+        assert_eq!(period.ioos_category.as_deref(), Some("Surface Waves"));
+        assert_eq!(period.other_units, vec!["min", "ft"]);
+        assert!(!period.rule_defaults.ioos_category);
+        assert_eq!(period.rule_defaults.other_units, vec!["ft"]);
+    }
+
+    #[test]
+    fn builtin_rules_categorize_families() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+
+        let swell = library.get("sea_surface_swell_wave_period").unwrap();
+        assert_eq!(swell.ioos_category.as_deref(), Some("Surface Waves"));
+
+        let categorized = library
+            .standards
+            .values()
+            .filter(|standard| standard.ioos_category.is_some())
+            .count();
+        assert!(categorized > 100, "{categorized}");
+
+        // Rule defaults aren't curated knowledge
+        assert!(!swell.has_knowledge());
+        let with_knowledge = library.filter().has_knowledge().standards.len();
+        assert!(with_knowledge <= 41, "{with_knowledge}");
+
+        let categories = crate::ioos_categories::erddap_categories();
+        for rule in library.rules() {
+            if let Some(category) = &rule.ioos_category {
+                assert!(categories.contains(category.as_str()), "{category}");
+            }
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.load_knowledge().unwrap();
+        library.load_test_suites();
+
+        let reloaded = StandardsLibrary::from_snapshot(&library.to_snapshot()).unwrap();
+        assert_eq!(reloaded.standards.len(), library.standards.len());
+        assert_eq!(reloaded.rules().len(), library.rules().len());
+        assert_eq!(
+            reloaded.get("air_pressure_at_sea_level").unwrap().name,
+            "air_pressure_at_mean_sea_level"
+        );
+
+        for name in [
+            "air_pressure",
+            "sea_surface_height_above_geopotential_datum",
+        ] {
+            let original = library.get(name).unwrap();
+            let standard = reloaded.get(name).unwrap();
+            assert_eq!(standard, original);
+            let slugs = |standard: &Standard| -> Vec<String> {
+                standard
+                    .qartod
+                    .iter()
+                    .map(|suite| suite.info().slug)
+                    .collect()
+            };
+            assert_eq!(slugs(&standard), slugs(&original));
+            assert!(!standard.qartod.is_empty(), "{name}");
+        }
+
+        // Static suites keep their configuration
+        let glos = |library: &StandardsLibrary| {
+            let standard = library.get("air_pressure").unwrap();
+            let suite = standard
+                .qartod
+                .iter()
+                .find(|suite| suite.info().slug == "glos")
+                .unwrap()
+                .clone();
+            suite.scaffold(Default::default()).unwrap()
+        };
+        assert_eq!(glos(&reloaded), glos(&library));
+
+        let old =
+            library
+                .to_snapshot()
+                .replacen("\"snapshot_version\":1", "\"snapshot_version\":0", 1);
+        assert!(matches!(
+            StandardsLibrary::from_snapshot(&old),
+            Err(Error::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn snapshot_keeps_non_finite_floats() {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library.apply_knowledge(vec![Knowledge {
+            name: "sea_water_temperature".to_string(),
+            extra_attrs: BTreeMap::from([
+                ("_FillValue".to_string(), AttrValue::Float(f64::NAN)),
+                (
+                    "valid_range".to_string(),
+                    AttrValue::Floats(vec![f64::NEG_INFINITY, f64::INFINITY]),
+                ),
+                ("comment".to_string(), AttrValue::Text("NaN".to_string())),
+            ]),
+            ..Default::default()
+        }]);
+
+        let snapshot = library.to_snapshot();
+        assert!(snapshot.contains(r#""_FillValue":{"float":"NaN"}"#));
+
+        let reloaded = StandardsLibrary::from_snapshot(&snapshot).unwrap();
+        let standard = reloaded.get("sea_water_temperature").unwrap();
+        assert!(matches!(
+            standard.extra_attrs["_FillValue"],
+            AttrValue::Float(fill) if fill.is_nan()
+        ));
+        assert_eq!(
+            standard.extra_attrs["valid_range"],
+            AttrValue::Floats(vec![f64::NEG_INFINITY, f64::INFINITY])
+        );
+        assert_eq!(
+            standard.extra_attrs["comment"],
+            AttrValue::Text("NaN".to_string())
+        );
+    }
+
     #[test]
     fn can_find_by_variable_name() {
         let mut library = StandardsLibrary::default();
-        library.load_cf_standards();
+        library.load_cf_standards().unwrap();
         let know = Knowledge {
             name: "air_pressure_at_mean_sea_level".to_string(),
             long_name: Some("Air Pressure at Sea Level".to_string()),
@@ -181,7 +770,7 @@ mod tests {
     #[test]
     fn can_find_by_variable_name_case_insensitive() {
         let mut library = StandardsLibrary::default();
-        library.load_cf_standards();
+        library.load_cf_standards().unwrap();
         let know = Knowledge {
             name: "air_pressure_at_mean_sea_level".to_string(),
             long_name: Some("Air Pressure at Sea Level".to_string()),
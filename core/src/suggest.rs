@@ -0,0 +1,162 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display};
+
+use crate::standard::Standard;
+
+/// How many suggestions to include when a standard isn't found
+pub const DEFAULT_SUGGESTION_LIMIT: usize = 5;
+
+/// Minimum score for a name to be suggested
+const MIN_SCORE: f64 = 0.5;
+
+/// A standard name or alias that could not be found,
+/// along with similar names that the caller may have meant
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownStandard {
+    /// Name or alias that was requested
+    pub name: String,
+
+    /// Similar standard names, best match first
+    pub suggestions: Vec<String>,
+}
+
+impl UnknownStandard {
+    /// Build a not-found error with suggestions from the given standards
+    pub fn new<'a>(name: &str, standards: impl IntoIterator<Item = &'a Standard>) -> Self {
+        Self {
+            name: name.to_string(),
+            suggestions: suggest(name, standards, DEFAULT_SUGGESTION_LIMIT),
+        }
+    }
+}
+
+impl Display for UnknownStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown Standard: {}", self.name)?;
+        if !self.suggestions.is_empty() {
+            write!(f, ". Did you mean: {}?", self.suggestions.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownStandard {}
+
+fn tokens(name: &str) -> BTreeSet<&str> {
+    name.split(['_', ' ', '-'])
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/// Score how similar a candidate name is to what was typed, between 0 and 1
+///
+/// Combines the normalized edit distance, which catches typos,
+/// with how many of the underscore separated words are shared,
+/// which catches words that are out of order or missing.
+fn score(query: &str, query_tokens: &BTreeSet<&str>, candidate: &str) -> f64 {
+    let edit_similarity = strsim::normalized_levenshtein(query, candidate);
+
+    let candidate_tokens = tokens(candidate);
+    let shared = query_tokens.intersection(&candidate_tokens).count();
+    let total = query_tokens.union(&candidate_tokens).count();
+    let token_overlap = if total == 0 {
+        0.0
+    } else {
+        shared as f64 / total as f64
+    };
+
+    0.6 * edit_similarity + 0.4 * token_overlap
+}
+
+/// Suggest standard names similar to one that wasn't found
+///
+/// Both names and aliases are compared, but only canonical names are returned.
+pub fn suggest<'a>(
+    name: &str,
+    standards: impl IntoIterator<Item = &'a Standard>,
+    limit: usize,
+) -> Vec<String> {
+    let query = name.trim().to_lowercase();
+    let query_tokens = tokens(&query);
+
+    let mut best: HashMap<&str, f64> = HashMap::new();
+    for standard in standards {
+        let candidates = std::iter::once(&standard.name).chain(standard.aliases.iter());
+        for candidate in candidates {
+            let candidate_score = score(&query, &query_tokens, candidate);
+            if candidate_score >= MIN_SCORE {
+                let entry = best.entry(standard.name.as_str()).or_default();
+                *entry = entry.max(candidate_score);
+            }
+        }
+    }
+
+    let mut ranked: Vec<(&str, f64)> = best.into_iter().collect();
+    ranked.sort_by(|(a_name, a_score), (b_name, b_score)| {
+        b_score.total_cmp(a_score).then_with(|| a_name.cmp(b_name))
+    });
+
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnknownStandard;
+    use crate::{Error, StandardsLibrary};
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library
+    }
+
+    fn unknown(library: &StandardsLibrary, name: &str) -> UnknownStandard {
+        match library.get(name) {
+            Err(Error::UnknownStandard(unknown)) => unknown,
+            other => panic!("Expected an unknown standard, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn suggests_close_typos() {
+        let library = library();
+
+        let error = unknown(
+            &library,
+            "sea_surface_wave_period_at_variance_spectral_density_maxmum",
+        );
+        assert_eq!(
+            error.suggestions[0],
+            "sea_surface_wave_period_at_variance_spectral_density_maximum"
+        );
+
+        let error = unknown(&library, "sea_water_temprature");
+        assert_eq!(error.suggestions[0], "sea_water_temperature");
+    }
+
+    #[test]
+    fn suggests_reordered_words() {
+        let error = unknown(&library(), "temperature_sea_water");
+        assert!(error
+            .suggestions
+            .contains(&"sea_water_temperature".to_string()));
+    }
+
+    #[test]
+    fn suggests_canonical_name_for_alias_typo() {
+        let error = unknown(&library(), "air_pressure_at_sea_levl");
+        assert_eq!(error.suggestions[0], "air_pressure_at_mean_sea_level");
+    }
+
+    #[test]
+    fn no_suggestions_for_nonsense() {
+        let error = unknown(&library(), "xyzzy");
+        assert!(error.suggestions.is_empty());
+        assert_eq!(error.to_string(), "Unknown Standard: xyzzy");
+        assert_eq!(Error::from(error).to_string(), "Unknown Standard: xyzzy");
+    }
+}
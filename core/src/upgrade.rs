@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use crate::standard::Standard;
+use crate::standards_library::StandardsLibrary;
+
+/// A standard found by name or alias, along with how it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedStandard {
+    pub standard: Arc<Standard>,
+
+    /// The superseded alias that was used to find the standard,
+    /// or `None` if the canonical name was used
+    pub alias: Option<String>,
+
+    /// Version of the loaded CF standard name table, if the standard was found by an alias
+    ///
+    /// This isn't when the name became an alias, which the CF table doesn't record,
+    /// only that the alias is superseded as of this version.
+    pub table_version: Option<String>,
+}
+
+impl ResolvedStandard {
+    /// Was the standard found by a superseded alias
+    pub fn is_alias(&self) -> bool {
+        self.alias.is_some()
+    }
+}
+
+/// A standard name that was rewritten to the current canonical name
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandardNameUpgrade {
+    /// Variable the standard name belongs to, if known
+    pub variable: Option<String>,
+
+    /// Superseded alias
+    pub from: String,
+
+    /// Current canonical standard name
+    pub to: String,
+
+    /// Version of the loaded CF standard name table, which has the alias
+    pub table_version: Option<String>,
+}
+
+/// Rewrite any `standard_name` attributes in a CDL header that use a superseded alias
+pub fn upgrade_cdl(library: &StandardsLibrary, cdl: &str) -> (String, Vec<StandardNameUpgrade>) {
+    let mut upgrades = Vec::new();
+
+    let lines: Vec<String> = cdl
+        .lines()
+        .map(|line| {
+            upgrade_cdl_line(library, line, &mut upgrades).unwrap_or_else(|| line.to_string())
+        })
+        .collect();
+
+    let mut output = lines.join("\n");
+    if cdl.ends_with('\n') {
+        output.push('\n');
+    }
+
+    (output, upgrades)
+}
+
+/// Upgrade a single `variable:standard_name = "..." ;` line, if it uses an alias
+fn upgrade_cdl_line(
+    library: &StandardsLibrary,
+    line: &str,
+    upgrades: &mut Vec<StandardNameUpgrade>,
+) -> Option<String> {
+    let (variable, rest) = line.split_once(":standard_name")?;
+    let rest = rest.trim_start().strip_prefix('=')?;
+
+    let open = line.len() - rest.len() + rest.find('"')? + 1;
+    let close = open + line[open..].find('"')?;
+    let name = &line[open..close];
+
+    let upgrade = library.upgrade_standard_name(name)?;
+    let variable = variable.trim();
+
+    upgrades.push(StandardNameUpgrade {
+        variable: (!variable.is_empty()).then(|| variable.to_string()),
+        from: name.to_string(),
+        to: upgrade.standard.name.clone(),
+        table_version: upgrade.table_version,
+    });
+
+    Some(format!(
+        "{}{}{}",
+        &line[..open],
+        upgrade.standard.name,
+        &line[close..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> StandardsLibrary {
+        let mut library = StandardsLibrary::default();
+        library.load_cf_standards().unwrap();
+        library
+    }
+
+    #[test]
+    fn resolve_reports_alias() {
+        let library = library();
+
+        let resolved = library.resolve("air_pressure_at_sea_level").unwrap();
+        assert_eq!(resolved.standard.name, "air_pressure_at_mean_sea_level");
+        assert_eq!(resolved.alias.as_deref(), Some("air_pressure_at_sea_level"));
+        assert!(resolved.is_alias());
+        assert_eq!(resolved.table_version, library.cf_version);
+
+        let resolved = library.resolve("air_pressure_at_mean_sea_level").unwrap();
+        assert!(!resolved.is_alias());
+        assert_eq!(resolved.table_version, None);
+    }
+
+    #[test]
+    fn upgrade_cdl_header() {
+        let cdl = r#"netcdf example {
+dimensions:
+	time = UNLIMITED ;
+variables:
+	double pressure(time) ;
+		pressure:standard_name = "air_pressure_at_sea_level" ;
+		pressure:units = "Pa" ;
+	double temp(time) ;
+		temp:standard_name = "sea_water_temperature" ;
+}
+"#;
+        let (upgraded, upgrades) = upgrade_cdl(&library(), cdl);
+
+        assert!(upgraded
+            .contains("\t\tpressure:standard_name = \"air_pressure_at_mean_sea_level\" ;\n"));
+        assert!(upgraded.contains("\t\ttemp:standard_name = \"sea_water_temperature\" ;\n"));
+        assert!(upgraded.ends_with("}\n"));
+
+        assert_eq!(upgrades.len(), 1);
+        assert_eq!(upgrades[0].variable.as_deref(), Some("pressure"));
+        assert_eq!(upgrades[0].from, "air_pressure_at_sea_level");
+        assert_eq!(upgrades[0].to, "air_pressure_at_mean_sea_level");
+    }
+}
@@ -1,6 +1,11 @@
-use serde_wasm_bindgen::to_value;
-use standard_knowledge::{Standard, StandardsLibrary};
-use std::collections::HashMap;
+use serde_wasm_bindgen::{from_value, to_value};
+use standard_knowledge::qartod::types::QartodTestTypes;
+use standard_knowledge::{
+    AttrValue, ComposedName, DatasetMetadata, ErddapDataset, ErddapDatasetType, Error,
+    KnowledgeRule, NameParts, Relationship, SortKey, Standard, StandardsGraph, StandardsLibrary,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 use wasm_bindgen::prelude::*;
 
@@ -10,11 +15,47 @@ pub fn start() {
     console_error_panic_hook::set_once();
 }
 
+/// Convert a library error into a JS `Error`, with `name` set to the kind of error
+/// (such as `UnknownStandardError` or `ParseError`) and its context attached as properties
+fn js_error(error: Error) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    js_error.set_name(&format!("{}Error", error.kind()));
+
+    let properties: Vec<(&str, JsValue)> = match error {
+        Error::UnknownStandard(unknown) => vec![
+            ("standardName", unknown.name.into()),
+            (
+                "suggestions",
+                to_value(&unknown.suggestions).unwrap_or(JsValue::NULL),
+            ),
+        ],
+        Error::InvalidArgument { argument, .. } => vec![("argument", argument.into())],
+        Error::MissingArgument { argument, context } => {
+            vec![("argument", argument.into()), ("context", context.into())]
+        }
+        Error::Parse { input, .. } => vec![("input", input.into())],
+        Error::InvalidKnowledge { name, .. } => vec![("standardName", name.into())],
+        Error::UnsupportedSuite { suite, .. } => vec![("suite", suite.into())],
+        Error::Io { path, .. } => vec![("path", path.into())],
+    };
+    for (key, value) in properties {
+        let _ = js_sys::Reflect::set(&js_error, &key.into(), &value);
+    }
+
+    js_error.into()
+}
+
 #[wasm_bindgen(js_name = StandardsLibrary)]
 pub struct StandardsLibraryJS {
     inner: StandardsLibrary,
 }
 
+impl Default for StandardsLibraryJS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen(js_class = StandardsLibrary)]
 impl StandardsLibraryJS {
     #[wasm_bindgen(constructor)]
@@ -24,14 +65,67 @@ impl StandardsLibraryJS {
         }
     }
 
+    /// Snapshot of the fully built library as JSON, to cache instead of loading knowledge again
+    #[wasm_bindgen(js_name = toSnapshot)]
+    pub fn to_snapshot(&self) -> String {
+        self.inner.to_snapshot()
+    }
+
+    /// Reload a library from a snapshot made by `toSnapshot`
+    #[wasm_bindgen(js_name = fromSnapshot)]
+    pub fn from_snapshot(snapshot: &str) -> Result<StandardsLibraryJS, JsValue> {
+        Ok(Self {
+            inner: StandardsLibrary::from_snapshot(snapshot).map_err(js_error)?,
+        })
+    }
+
     #[wasm_bindgen(js_name = loadCfStandards)]
-    pub fn load_cf_standards(&mut self) {
-        self.inner.load_cf_standards();
+    pub fn load_cf_standards(&mut self) -> Result<(), JsValue> {
+        self.inner.load_cf_standards().map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = loadCfStandardsFromXml)]
+    pub fn load_cf_standards_from_xml(&mut self, xml: &str) -> Result<(), JsValue> {
+        self.inner.load_cf_standards_from_xml(xml).map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = loadCfStandardsFromYaml)]
+    pub fn load_cf_standards_from_yaml(&mut self, yaml: &str) -> Result<(), JsValue> {
+        self.inner
+            .load_cf_standards_from_yaml(yaml)
+            .map_err(js_error)
+    }
+
+    #[wasm_bindgen(getter, js_name = cfVersion)]
+    pub fn cf_version(&self) -> Option<String> {
+        self.inner.cf_version.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = cfLastModified)]
+    pub fn cf_last_modified(&self) -> Option<String> {
+        self.inner.cf_last_modified.clone()
     }
 
     #[wasm_bindgen(js_name = loadKnowledge)]
-    pub fn load_knowledge(&mut self) {
-        self.inner.load_knowledge();
+    pub fn load_knowledge(&mut self) -> Result<(), JsValue> {
+        self.inner.load_knowledge().map_err(js_error)
+    }
+
+    /// Apply YAML rules that give families of standards default knowledge
+    #[wasm_bindgen(js_name = applyRulesFromYaml)]
+    pub fn apply_rules_from_yaml(&mut self, yaml: &str) -> Result<(), JsValue> {
+        let rules = KnowledgeRule::from_yaml("rules", yaml).map_err(js_error)?;
+        self.inner.apply_rules(rules);
+        Ok(())
+    }
+
+    /// Patterns of the rules that apply to a standard, in the order they take precedence
+    #[wasm_bindgen(js_name = rulesFor)]
+    pub fn rules_for(&self, name_or_alias: &str) -> Result<Vec<String>, JsValue> {
+        self.inner
+            .rules_for(name_or_alias)
+            .map(|rules| rules.iter().map(|rule| rule.pattern.to_string()).collect())
+            .map_err(js_error)
     }
 
     #[wasm_bindgen(js_name = loadTestSuites)]
@@ -43,10 +137,23 @@ impl StandardsLibraryJS {
     pub fn get(&self, name_or_alias: &str) -> Result<StandardJS, JsValue> {
         match self.inner.get(name_or_alias) {
             Ok(standard) => Ok(StandardJS { inner: standard }),
-            Err(e) => Err(JsValue::from_str(e)),
+            Err(e) => Err(js_error(e)),
         }
     }
 
+    #[wasm_bindgen]
+    pub fn resolve(&self, name_or_alias: &str) -> Result<ResolvedStandardJS, JsValue> {
+        match self.inner.resolve(name_or_alias) {
+            Ok(resolved) => Ok(ResolvedStandardJS { inner: resolved }),
+            Err(e) => Err(js_error(e)),
+        }
+    }
+
+    #[wasm_bindgen(js_name = upgradeCdl)]
+    pub fn upgrade_cdl(&self, cdl: &str) -> String {
+        self.inner.upgrade_cdl(cdl).0
+    }
+
     #[wasm_bindgen]
     pub fn filter(&self) -> StandardsFilterJS {
         StandardsFilterJS {
@@ -54,6 +161,77 @@ impl StandardsLibraryJS {
         }
     }
 
+    #[wasm_bindgen]
+    pub fn graph(&self) -> StandardsGraphJS {
+        StandardsGraphJS {
+            inner: self.inner.graph(),
+        }
+    }
+
+    /// Compose a standard name from parts, in the shape that `parseStandardName` returns,
+    /// and check if it is in the library
+    #[wasm_bindgen(js_name = checkName)]
+    pub fn check_name(&self, parts: JsValue) -> Result<ComposedNameJS, JsValue> {
+        let parts: NameParts = from_value(parts)?;
+        Ok(ComposedNameJS {
+            inner: self.inner.check_name(&parts).map_err(js_error)?,
+        })
+    }
+
+    /// Generate an ERDDAP `<dataset>` for source columns, resolved to standards
+    /// by name, alias, or common variable name.
+    /// `datasetType` is `ascii` or `nc`, and `metadata` has the same snake_case
+    /// keys as `DatasetMetadata`, like `{ dataset_id, title, info_url }`
+    #[wasm_bindgen(js_name = erddapDataset)]
+    pub fn erddap_dataset(
+        &self,
+        columns: Vec<String>,
+        dataset_type: &str,
+        metadata: JsValue,
+    ) -> Result<ErddapDatasetJS, JsValue> {
+        let dataset_type: ErddapDatasetType = dataset_type.parse().map_err(js_error)?;
+        let metadata: DatasetMetadata = from_value(metadata)?;
+        Ok(ErddapDatasetJS {
+            inner: self
+                .inner
+                .erddap_dataset(&columns, dataset_type, metadata)
+                .map_err(js_error)?,
+        })
+    }
+
+    /// Check a variable's attributes against the IOOS Metadata Profile 1.2,
+    /// returning missing or invalid attributes, required ones first
+    #[wasm_bindgen(js_name = checkIoosProfile)]
+    pub fn check_ioos_profile(&self, attrs: JsValue) -> Result<JsValue, JsValue> {
+        let attrs: BTreeMap<String, AttrValue> = from_value(attrs)?;
+        Ok(to_value(&self.inner.check_ioos_profile(&attrs))?)
+    }
+
+    /// Copy of a variable's attributes, with missing ones filled in
+    /// from the knowledge for its `standard_name`
+    #[wasm_bindgen(js_name = fillIoosProfileDefaults)]
+    pub fn fill_ioos_profile_defaults(&self, attrs: JsValue) -> Result<JsValue, JsValue> {
+        let mut attrs: BTreeMap<String, AttrValue> = from_value(attrs)?;
+        self.inner.fill_ioos_profile_defaults(&mut attrs);
+        Ok(to_value(&attrs)?)
+    }
+
+    /// Check the variables of a CDL header, such as from `ncdump -h`, against the standards,
+    /// returning the findings for each variable
+    #[wasm_bindgen(js_name = checkCdl)]
+    pub fn check_cdl(&self, cdl: &str) -> Result<JsValue, JsValue> {
+        let checks = self.inner.check_cdl(cdl).map_err(js_error)?;
+        Ok(to_value(&checks)?)
+    }
+
+    /// Check the variables of a NetCDF-3 classic or 64-bit offset file against the standards,
+    /// from the bytes of at least its header, returning the findings for each variable
+    #[wasm_bindgen(js_name = checkNetcdf)]
+    pub fn check_netcdf(&self, bytes: &[u8]) -> Result<JsValue, JsValue> {
+        let checks = self.inner.check_netcdf(bytes).map_err(js_error)?;
+        Ok(to_value(&checks)?)
+    }
+
     #[wasm_bindgen(js_name = knownIoosCategories)]
     pub fn known_ioos_categories(&self) -> Vec<String> {
         self.inner.known_ioos_categories().into_iter().collect()
@@ -63,7 +241,7 @@ impl StandardsLibraryJS {
 #[derive(Clone)]
 #[wasm_bindgen(js_name = Standard)]
 pub struct StandardJS {
-    inner: Standard,
+    inner: Arc<Standard>,
 }
 
 #[wasm_bindgen(js_class = Standard)]
@@ -93,6 +271,16 @@ impl StandardJS {
         self.inner.aliases.clone()
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn grib(&self) -> Option<String> {
+        self.inner.grib.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn amip(&self) -> Option<String> {
+        self.inner.amip.clone()
+    }
+
     #[wasm_bindgen(getter, js_name = ioosCategory)]
     pub fn ioos_category(&self) -> Option<String> {
         self.inner.ioos_category.clone()
@@ -118,6 +306,13 @@ impl StandardJS {
         self.inner.sibling_standards.clone()
     }
 
+    /// IOOS category inferred from the name and unit, as `{ category, confidence, evidence }`,
+    /// even if there is one from knowledge
+    #[wasm_bindgen(js_name = inferIoosCategory)]
+    pub fn infer_ioos_category(&self) -> JsValue {
+        to_value(&self.inner.infer_ioos_category()).unwrap_or(JsValue::NULL)
+    }
+
     #[wasm_bindgen(getter)]
     pub fn comments(&self) -> Option<String> {
         self.inner.comments.clone()
@@ -125,21 +320,52 @@ impl StandardJS {
 
     #[wasm_bindgen]
     pub fn attrs(&self) -> JsValue {
-        let attrs_map = self.inner.xarray_attrs();
-        let mut js_map = HashMap::new();
-
-        for (key, value) in attrs_map {
-            js_map.insert(key.to_string(), value.to_string());
-        }
+        let js_map: HashMap<&str, AttrValue> = self.inner.xarray_attrs().into_iter().collect();
 
         to_value(&js_map).unwrap_or(JsValue::NULL)
     }
 
+    /// ERDDAP datasets.xml `<dataVariable>` block, with the source name defaulting to the standard name,
+    /// and the destination name to the source name
+    #[wasm_bindgen(js_name = erddapDataVariable)]
+    pub fn erddap_data_variable(
+        &self,
+        source_name: Option<String>,
+        destination_name: Option<String>,
+    ) -> String {
+        self.inner
+            .erddap_data_variable(source_name.as_deref(), destination_name.as_deref())
+    }
+
+    /// NetCDF CDL declaration for a double variable, with the variable name defaulting to
+    /// the standard name, and the dimensions to `["time"]`
+    #[wasm_bindgen(js_name = cdlVariable)]
+    pub fn cdl_variable(
+        &self,
+        variable_name: Option<String>,
+        dimensions: Option<Vec<String>>,
+    ) -> String {
+        let dimensions = dimensions.unwrap_or_else(|| vec!["time".to_string()]);
+        let dimensions: Vec<&str> = dimensions.iter().map(String::as_str).collect();
+        self.inner
+            .cdl_variable(variable_name.as_deref(), &dimensions)
+    }
+
     #[wasm_bindgen]
     pub fn display_short(&self) -> String {
         self.inner.display_short()
     }
 
+    /// CF ancillary QARTOD flag variables, with the parent's `ancillary_variables`
+    /// and the attributes of each flag, or `null` without QARTOD test suites
+    #[wasm_bindgen(js_name = qcFlags)]
+    pub fn qc_flags(&self, variable_name: Option<String>) -> JsValue {
+        self.inner
+            .qc_flags(variable_name.as_deref())
+            .and_then(|flags| to_value(&flags).ok())
+            .unwrap_or(JsValue::NULL)
+    }
+
     #[wasm_bindgen]
     pub fn display_all(&self) -> String {
         self.inner.display_all()
@@ -160,6 +386,232 @@ impl StandardJS {
     }
 }
 
+#[wasm_bindgen(js_name = ResolvedStandard)]
+pub struct ResolvedStandardJS {
+    inner: standard_knowledge::ResolvedStandard,
+}
+
+#[wasm_bindgen(js_class = ResolvedStandard)]
+impl ResolvedStandardJS {
+    #[wasm_bindgen(getter)]
+    pub fn standard(&self) -> StandardJS {
+        StandardJS {
+            inner: self.inner.standard.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn alias(&self) -> Option<String> {
+        self.inner.alias.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = tableVersion)]
+    pub fn table_version(&self) -> Option<String> {
+        self.inner.table_version.clone()
+    }
+
+    #[wasm_bindgen(js_name = isAlias)]
+    pub fn is_alias(&self) -> bool {
+        self.inner.is_alias()
+    }
+}
+
+#[wasm_bindgen(js_name = SearchResult)]
+pub struct SearchResultJS {
+    inner: standard_knowledge::SearchResult,
+}
+
+#[wasm_bindgen(js_class = SearchResult)]
+impl SearchResultJS {
+    #[wasm_bindgen(getter)]
+    pub fn standard(&self) -> StandardJS {
+        StandardJS {
+            inner: self.inner.standard.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f64 {
+        self.inner.score
+    }
+
+    /// Fields that matched as `{ field, value, exact, score }` objects, best first
+    #[wasm_bindgen(getter)]
+    pub fn matches(&self) -> JsValue {
+        to_value(&self.inner.matches).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen]
+    pub fn explain(&self) -> String {
+        self.inner.explain()
+    }
+}
+
+fn parse_relationship(relationship: Option<String>) -> Result<Option<Relationship>, JsValue> {
+    relationship
+        .map(|relationship| relationship.parse().map_err(js_error))
+        .transpose()
+}
+
+#[wasm_bindgen(js_name = ErddapDataset)]
+pub struct ErddapDatasetJS {
+    inner: ErddapDataset,
+}
+
+#[wasm_bindgen(js_class = ErddapDataset)]
+impl ErddapDatasetJS {
+    /// Render the `<dataset>` for `datasets.xml`
+    #[wasm_bindgen(js_name = toXml)]
+    pub fn to_xml(&self) -> String {
+        self.inner.to_xml()
+    }
+
+    /// Columns that no standard was found for
+    #[wasm_bindgen]
+    pub fn unresolved(&self) -> Vec<String> {
+        self.inner
+            .unresolved()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[wasm_bindgen(js_name = StandardsGraph)]
+pub struct StandardsGraphJS {
+    inner: StandardsGraph,
+}
+
+#[wasm_bindgen(js_class = StandardsGraph)]
+impl StandardsGraphJS {
+    #[wasm_bindgen]
+    pub fn nodes(&self) -> Vec<String> {
+        self.inner.nodes().into_iter().map(str::to_string).collect()
+    }
+
+    /// Links as `{ from, to, relationship }` objects
+    #[wasm_bindgen]
+    pub fn edges(&self) -> JsValue {
+        to_value(&self.inner.edges()).unwrap_or(JsValue::NULL)
+    }
+
+    /// Standards within a number of links as `{ name, hops }` objects, closest first
+    #[wasm_bindgen]
+    pub fn within(
+        &self,
+        standard_name_or_alias: &str,
+        hops: usize,
+        relationship: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let reached = self
+            .inner
+            .within(
+                standard_name_or_alias,
+                hops,
+                parse_relationship(relationship)?,
+            )
+            .map_err(js_error)?;
+        Ok(to_value(&reached).unwrap_or(JsValue::NULL))
+    }
+
+    #[wasm_bindgen]
+    pub fn neighborhood(
+        &self,
+        standard_name_or_alias: &str,
+        hops: usize,
+        relationship: Option<String>,
+    ) -> Result<StandardsGraphJS, JsValue> {
+        Ok(StandardsGraphJS {
+            inner: self
+                .inner
+                .neighborhood(
+                    standard_name_or_alias,
+                    hops,
+                    parse_relationship(relationship)?,
+                )
+                .map_err(js_error)?,
+        })
+    }
+
+    #[wasm_bindgen(js_name = siblingGroup)]
+    pub fn sibling_group(&self, standard_name_or_alias: &str) -> Result<Vec<String>, JsValue> {
+        self.inner
+            .sibling_group(standard_name_or_alias)
+            .map_err(js_error)
+    }
+
+    #[wasm_bindgen(js_name = siblingGroups)]
+    pub fn sibling_groups(&self) -> JsValue {
+        to_value(&self.inner.sibling_groups()).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(js_name = missingBackLinks)]
+    pub fn missing_back_links(&self) -> JsValue {
+        to_value(&self.inner.missing_back_links()).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(js_name = unknownLinks)]
+    pub fn unknown_links(&self) -> JsValue {
+        to_value(&self.inner.unknown_links()).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(js_name = toDot)]
+    pub fn to_dot(&self) -> String {
+        self.inner.to_dot()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+}
+
+/// Break a standard name into its parts, as
+/// `{ transformations, leading_surface, component, base, surface, medium, process, condition }`
+#[wasm_bindgen(js_name = parseStandardName)]
+pub fn parse_standard_name(standard_name: &str) -> Result<JsValue, JsValue> {
+    let parts = NameParts::parse(standard_name).map_err(js_error)?;
+    Ok(to_value(&parts).unwrap_or(JsValue::NULL))
+}
+
+/// Build a standard name from parts, in the same shape that `parseStandardName` returns
+#[wasm_bindgen(js_name = composeStandardName)]
+pub fn compose_standard_name(parts: JsValue) -> Result<String, JsValue> {
+    let parts: NameParts = from_value(parts)?;
+    parts.compose().map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = ComposedName)]
+pub struct ComposedNameJS {
+    inner: ComposedName,
+}
+
+#[wasm_bindgen(js_class = ComposedName)]
+impl ComposedNameJS {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.inner.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn standard(&self) -> Option<StandardJS> {
+        self.inner
+            .standard
+            .clone()
+            .map(|inner| StandardJS { inner })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn alternatives(&self) -> Vec<String> {
+        self.inner.alternatives.clone()
+    }
+
+    #[wasm_bindgen(js_name = isKnown)]
+    pub fn is_known(&self) -> bool {
+        self.inner.is_known()
+    }
+}
+
 #[wasm_bindgen]
 pub struct QartodJS {
     name: String,
@@ -190,6 +642,12 @@ pub struct StandardsFilterJS {
 
 #[wasm_bindgen]
 impl StandardsFilterJS {
+    /// NetCDF CDL skeleton with a variable for each standard, along an unlimited time dimension
+    #[wasm_bindgen(js_name = toCdl)]
+    pub fn to_cdl(&self, dataset_name: &str, cf_version: Option<String>) -> String {
+        self.inner.to_cdl(dataset_name, cf_version.as_deref())
+    }
+
     #[wasm_bindgen(js_name = byVariableName)]
     pub fn by_variable_name(&self, variable_name: &str) -> Self {
         StandardsFilterJS {
@@ -204,6 +662,17 @@ impl StandardsFilterJS {
         }
     }
 
+    /// Filter by IOOS category, inferring it for standards without one
+    /// when it is at least `minConfidence` (from 0 to 1)
+    #[wasm_bindgen(js_name = byInferredIoosCategory)]
+    pub fn by_inferred_ioos_category(&self, category: &str, min_confidence: f64) -> Self {
+        StandardsFilterJS {
+            inner: self
+                .inner
+                .by_inferred_ioos_category(category, min_confidence),
+        }
+    }
+
     #[wasm_bindgen(js_name = byUnit)]
     pub fn by_unit(&self, unit: &str) -> Self {
         StandardsFilterJS {
@@ -218,6 +687,113 @@ impl StandardsFilterJS {
         }
     }
 
+    #[wasm_bindgen(js_name = byQartodTestType)]
+    pub fn by_qartod_test_type(&self, test_type: &str) -> Result<StandardsFilterJS, JsValue> {
+        let test_type: QartodTestTypes = test_type.parse().map_err(js_error)?;
+        Ok(StandardsFilterJS {
+            inner: self.inner.by_qartod_test_type(&test_type),
+        })
+    }
+
+    #[wasm_bindgen(js_name = byExtraAttr)]
+    pub fn by_extra_attr(&self, key: &str, value: Option<String>) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.by_extra_attr(key, value.as_deref()),
+        }
+    }
+
+    #[wasm_bindgen(js_name = bySibling)]
+    pub fn by_sibling(&self, standard_name: &str) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.by_sibling(standard_name),
+        }
+    }
+
+    #[wasm_bindgen(js_name = byRelated)]
+    pub fn by_related(&self, standard_name: &str) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.by_related(standard_name),
+        }
+    }
+
+    #[wasm_bindgen(js_name = hasKnowledge)]
+    pub fn has_knowledge(&self) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.has_knowledge(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = byAlias)]
+    pub fn by_alias(&self, alias: &str) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.by_alias(alias),
+        }
+    }
+
+    #[wasm_bindgen(js_name = byNamePrefix)]
+    pub fn by_name_prefix(&self, prefix: &str) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.by_name_prefix(prefix),
+        }
+    }
+
+    #[wasm_bindgen(js_name = byNameSuffix)]
+    pub fn by_name_suffix(&self, suffix: &str) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.by_name_suffix(suffix),
+        }
+    }
+
+    #[wasm_bindgen(js_name = byNameRegex)]
+    pub fn by_name_regex(&self, pattern: &str) -> Result<StandardsFilterJS, JsValue> {
+        Ok(StandardsFilterJS {
+            inner: self.inner.by_name_regex(pattern).map_err(js_error)?,
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn union(&self, other: &StandardsFilterJS) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.union(&other.inner),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn intersection(&self, other: &StandardsFilterJS) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.intersection(&other.inner),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn difference(&self, other: &StandardsFilterJS) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.difference(&other.inner),
+        }
+    }
+
+    #[wasm_bindgen(js_name = sortBy)]
+    pub fn sort_by(&self, key: &str, descending: bool) -> Result<StandardsFilterJS, JsValue> {
+        let key: SortKey = key.parse().map_err(js_error)?;
+        Ok(StandardsFilterJS {
+            inner: self.inner.sort_by(key, descending),
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn paginate(&self, offset: usize, limit: usize) -> Self {
+        StandardsFilterJS {
+            inner: self.inner.paginate(offset, limit),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn query(&self, query: &str) -> Result<StandardsFilterJS, JsValue> {
+        Ok(StandardsFilterJS {
+            inner: self.inner.query(query).map_err(js_error)?,
+        })
+    }
+
     #[wasm_bindgen]
     pub fn search(&self, search_str: &str) -> Self {
         StandardsFilterJS {
@@ -225,6 +801,15 @@ impl StandardsFilterJS {
         }
     }
 
+    #[wasm_bindgen(js_name = searchRanked)]
+    pub fn search_ranked(&self, search_str: &str) -> Vec<SearchResultJS> {
+        self.inner
+            .search_ranked(search_str)
+            .into_iter()
+            .map(|inner| SearchResultJS { inner })
+            .collect()
+    }
+
     #[wasm_bindgen(getter)]
     pub fn standards(&self) -> Vec<StandardJS> {
         self.inner
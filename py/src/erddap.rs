@@ -0,0 +1,50 @@
+use pyo3::prelude::*;
+
+use standard_knowledge::ErddapDataset;
+
+#[pyclass(name = "ErddapDataset")]
+#[derive(Clone)]
+pub struct PyErddapDataset(pub ErddapDataset);
+
+#[pymethods]
+impl PyErddapDataset {
+    /// Render the `<dataset>` for `datasets.xml`
+    fn to_xml(&self) -> String {
+        self.0.to_xml()
+    }
+
+    /// Columns that no standard was found for
+    fn unresolved(&self) -> Vec<String> {
+        self.0
+            .unresolved()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Columns as `(source_name, standard_name, alternatives)` tuples,
+    /// with `None` for columns that no standard was found for
+    fn columns(&self) -> Vec<(String, Option<String>, Vec<String>)> {
+        self.0
+            .columns
+            .iter()
+            .map(|column| {
+                (
+                    column.source_name.clone(),
+                    column
+                        .standard
+                        .as_ref()
+                        .map(|standard| standard.name.clone()),
+                    column.alternatives.clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<ErddapDataset: {} ({})>",
+            self.0.metadata.dataset_id, self.0.dataset_type
+        )
+    }
+}
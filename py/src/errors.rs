@@ -0,0 +1,123 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyKeyError, PyNotImplementedError, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+
+use standard_knowledge::Error;
+
+create_exception!(
+    standard_knowledge,
+    UnknownStandardError,
+    PyKeyError,
+    "A standard name or alias could not be found. Similar names are in `suggestions`."
+);
+create_exception!(
+    standard_knowledge,
+    InvalidArgumentError,
+    PyValueError,
+    "An argument was given, but its value can't be used."
+);
+create_exception!(
+    standard_knowledge,
+    MissingArgumentError,
+    PyValueError,
+    "A required argument was not given."
+);
+create_exception!(
+    standard_knowledge,
+    ParseError,
+    PyValueError,
+    "Input, such as a CF standard name table, could not be parsed."
+);
+create_exception!(
+    standard_knowledge,
+    InvalidKnowledgeError,
+    PyValueError,
+    "Knowledge could not be loaded or applied."
+);
+create_exception!(
+    standard_knowledge,
+    UnsupportedSuiteError,
+    PyNotImplementedError,
+    "A test suite can't do what was asked of it."
+);
+
+/// Register the exception classes with the module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add(
+        "UnknownStandardError",
+        py.get_type::<UnknownStandardError>(),
+    )?;
+    m.add(
+        "InvalidArgumentError",
+        py.get_type::<InvalidArgumentError>(),
+    )?;
+    m.add(
+        "MissingArgumentError",
+        py.get_type::<MissingArgumentError>(),
+    )?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add(
+        "InvalidKnowledgeError",
+        py.get_type::<InvalidKnowledgeError>(),
+    )?;
+    m.add(
+        "UnsupportedSuiteError",
+        py.get_type::<UnsupportedSuiteError>(),
+    )?;
+    Ok(())
+}
+
+/// Convert a library error into the matching Python exception,
+/// with the error's context attached as attributes
+pub fn to_py_err(py: Python, error: Error) -> PyErr {
+    let message = error.to_string();
+    let (err, attrs): (PyErr, Vec<(&str, Py<PyAny>)>) = match error {
+        Error::UnknownStandard(unknown) => (
+            UnknownStandardError::new_err(message),
+            vec![
+                ("standard_name", into_py(py, unknown.name)),
+                ("suggestions", into_py(py, unknown.suggestions)),
+            ],
+        ),
+        Error::InvalidArgument { argument, .. } => (
+            InvalidArgumentError::new_err(message),
+            vec![("argument", into_py(py, argument))],
+        ),
+        Error::MissingArgument { argument, context } => (
+            MissingArgumentError::new_err(message),
+            vec![
+                ("argument", into_py(py, argument)),
+                ("context", into_py(py, context)),
+            ],
+        ),
+        Error::Parse { input, .. } => (
+            ParseError::new_err(message),
+            vec![("input", into_py(py, input))],
+        ),
+        Error::InvalidKnowledge { name, .. } => (
+            InvalidKnowledgeError::new_err(message),
+            vec![("standard_name", into_py(py, name))],
+        ),
+        Error::UnsupportedSuite { suite, .. } => (
+            UnsupportedSuiteError::new_err(message),
+            vec![("suite", into_py(py, suite))],
+        ),
+        Error::Io { path, .. } => (
+            PyOSError::new_err(message),
+            vec![("filename", into_py(py, path))],
+        ),
+    };
+
+    let value = err.value(py);
+    for (name, attr) in attrs {
+        // Setting attributes on a freshly created exception can't reasonably fail
+        let _ = value.setattr(name, attr);
+    }
+    err
+}
+
+fn into_py<'py, T: IntoPyObject<'py>>(py: Python<'py>, value: T) -> Py<PyAny> {
+    value.into_py_any(py).unwrap_or_else(|_| py.None())
+}
@@ -0,0 +1,161 @@
+use pyo3::prelude::*;
+
+use crate::errors::to_py_err;
+use crate::PyStandard;
+use standard_knowledge::{ComposedName, NameParts, Transformation};
+
+/// Write a transformation as `tendency_of`, or `derivative_of:depth` with what it is with respect to
+fn transformation_str(transformation: &Transformation) -> String {
+    match &transformation.wrt {
+        Some(wrt) => format!("{}:{wrt}", transformation.operator),
+        None => transformation.operator.clone(),
+    }
+}
+
+#[pyclass(name = "NameParts")]
+#[derive(Clone)]
+pub struct PyNameParts(pub NameParts);
+
+#[pymethods]
+impl PyNameParts {
+    /// Parts of a standard name, to compose into a name with `compose`
+    ///
+    /// Transformations are outermost first, like `tendency_of`,
+    /// or `derivative_of:depth` for what a derivative is with respect to.
+    #[new]
+    #[pyo3(signature = (base, transformations=Vec::new(), leading_surface=None, component=None, surface=None, medium=None, process=None, condition=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python,
+        base: &str,
+        transformations: Vec<String>,
+        leading_surface: Option<String>,
+        component: Option<String>,
+        surface: Option<String>,
+        medium: Option<String>,
+        process: Option<String>,
+        condition: Option<String>,
+    ) -> PyResult<Self> {
+        let transformations = transformations
+            .iter()
+            .map(|transformation| transformation.parse())
+            .collect::<Result<Vec<Transformation>, _>>()
+            .map_err(|e| to_py_err(py, e))?;
+
+        Ok(PyNameParts(NameParts {
+            transformations,
+            leading_surface,
+            component,
+            surface,
+            medium,
+            process,
+            condition,
+            ..NameParts::new(base)
+        }))
+    }
+
+    /// Break a standard name into its parts
+    #[staticmethod]
+    fn parse(py: Python, standard_name: &str) -> PyResult<Self> {
+        NameParts::parse(standard_name)
+            .map(PyNameParts)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Build the standard name from its parts
+    fn compose(&self, py: Python) -> PyResult<String> {
+        self.0.compose().map_err(|e| to_py_err(py, e))
+    }
+
+    #[getter]
+    fn transformations(&self) -> Vec<String> {
+        self.0
+            .transformations
+            .iter()
+            .map(transformation_str)
+            .collect()
+    }
+
+    #[getter]
+    fn leading_surface(&self) -> Option<String> {
+        self.0.leading_surface.clone()
+    }
+
+    #[getter]
+    fn component(&self) -> Option<String> {
+        self.0.component.clone()
+    }
+
+    #[getter]
+    fn base(&self) -> String {
+        self.0.base.clone()
+    }
+
+    #[getter]
+    fn surface(&self) -> Option<String> {
+        self.0.surface.clone()
+    }
+
+    #[getter]
+    fn medium(&self) -> Option<String> {
+        self.0.medium.clone()
+    }
+
+    #[getter]
+    fn process(&self) -> Option<String> {
+        self.0.process.clone()
+    }
+
+    #[getter]
+    fn condition(&self) -> Option<String> {
+        self.0.condition.clone()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        match self.0.compose() {
+            Ok(name) => format!("<NameParts: {name}>"),
+            Err(_) => format!("<NameParts: {}>", self.0.base),
+        }
+    }
+}
+
+#[pyclass(name = "ComposedName")]
+pub struct PyComposedName(pub ComposedName);
+
+#[pymethods]
+impl PyComposedName {
+    #[getter]
+    fn name(&self) -> String {
+        self.0.name.clone()
+    }
+
+    /// The standard, if the name is in the library
+    #[getter]
+    fn standard(&self) -> Option<PyStandard> {
+        self.0.standard.clone().map(PyStandard)
+    }
+
+    /// Closest known names, if the name isn't in the library
+    #[getter]
+    fn alternatives(&self) -> Vec<String> {
+        self.0.alternatives.clone()
+    }
+
+    /// Is the composed name in the library
+    fn is_known(&self) -> bool {
+        self.0.is_known()
+    }
+
+    fn __repr__(&self) -> String {
+        let known = if self.0.is_known() {
+            "known"
+        } else {
+            "unknown"
+        };
+        format!("<ComposedName: {} ({known})>", self.0.name)
+    }
+}
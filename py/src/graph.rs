@@ -0,0 +1,118 @@
+use pyo3::prelude::*;
+
+use crate::errors::to_py_err;
+use standard_knowledge::{Edge, Relationship, StandardsGraph};
+
+#[pyclass(name = "StandardsGraph")]
+#[derive(Clone)]
+pub struct PyStandardsGraph(pub StandardsGraph);
+
+fn parse_relationship(py: Python, relationship: Option<&str>) -> PyResult<Option<Relationship>> {
+    relationship
+        .map(|relationship| relationship.parse().map_err(|e| to_py_err(py, e)))
+        .transpose()
+}
+
+/// Links as `(from, to, relationship)` tuples
+fn edge_tuples<'a>(edges: impl IntoIterator<Item = &'a Edge>) -> Vec<(String, String, String)> {
+    edges
+        .into_iter()
+        .map(|edge| {
+            (
+                edge.from.clone(),
+                edge.to.clone(),
+                edge.relationship.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[pymethods]
+impl PyStandardsGraph {
+    /// Names of all standards in the graph
+    fn nodes(&self) -> Vec<String> {
+        self.0.nodes().into_iter().map(str::to_string).collect()
+    }
+
+    /// All links as `(from, to, relationship)` tuples
+    fn edges(&self) -> Vec<(String, String, String)> {
+        edge_tuples(self.0.edges())
+    }
+
+    /// Standards within a number of links of a standard as `(name, hops)` tuples, closest first
+    #[pyo3(signature = (standard_name_or_alias, hops=1, relationship=None))]
+    fn within(
+        &self,
+        py: Python,
+        standard_name_or_alias: &str,
+        hops: usize,
+        relationship: Option<&str>,
+    ) -> PyResult<Vec<(String, usize)>> {
+        let relationship = parse_relationship(py, relationship)?;
+        let reached = self
+            .0
+            .within(standard_name_or_alias, hops, relationship)
+            .map_err(|e| to_py_err(py, e))?;
+        Ok(reached
+            .into_iter()
+            .map(|reached| (reached.name, reached.hops))
+            .collect())
+    }
+
+    /// Subgraph of a standard and everything within a number of links of it
+    #[pyo3(signature = (standard_name_or_alias, hops=1, relationship=None))]
+    fn neighborhood(
+        &self,
+        py: Python,
+        standard_name_or_alias: &str,
+        hops: usize,
+        relationship: Option<&str>,
+    ) -> PyResult<PyStandardsGraph> {
+        let relationship = parse_relationship(py, relationship)?;
+        self.0
+            .neighborhood(standard_name_or_alias, hops, relationship)
+            .map(PyStandardsGraph)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Standards connected to a standard by sibling links, including itself
+    fn sibling_group(&self, py: Python, standard_name_or_alias: &str) -> PyResult<Vec<String>> {
+        self.0
+            .sibling_group(standard_name_or_alias)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Every group of two or more standards connected by sibling links
+    fn sibling_groups(&self) -> Vec<Vec<String>> {
+        self.0.sibling_groups()
+    }
+
+    /// Links that should be added so that linked standards link back,
+    /// as `(from, to, relationship)` tuples
+    fn missing_back_links(&self) -> Vec<(String, String, String)> {
+        edge_tuples(&self.0.missing_back_links())
+    }
+
+    /// Links to standards that aren't in the library, as `(from, to, relationship)` tuples
+    fn unknown_links(&self) -> Vec<(String, String, String)> {
+        edge_tuples(self.0.unknown_links())
+    }
+
+    /// Export to Graphviz DOT
+    fn to_dot(&self) -> String {
+        self.0.to_dot()
+    }
+
+    /// Export to JSON
+    fn to_json(&self) -> String {
+        self.0.to_json()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<StandardsGraph: {} standards, {} links>",
+            self.0.nodes().len(),
+            self.0.edges().len()
+        )
+    }
+}
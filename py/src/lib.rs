@@ -1,9 +1,16 @@
+pub mod erddap;
+pub mod errors;
+pub mod grammar;
+pub mod graph;
 pub mod standard;
 pub mod standards_filter;
 pub mod standards_library;
 pub mod test_suite;
 
-pub use standard::PyStandard;
+pub use erddap::PyErddapDataset;
+pub use grammar::{PyComposedName, PyNameParts};
+pub use graph::PyStandardsGraph;
+pub use standard::{PyFieldMatch, PyResolvedStandard, PySearchResult, PyStandard};
 pub use standards_filter::PyStandardsFilter;
 pub use standards_library::PyStandardsLibrary;
 pub use test_suite::PyTestSuite;
@@ -14,8 +21,16 @@ use pyo3::prelude::*;
 #[pymodule]
 fn _standard_knowledge_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyStandard>()?;
+    m.add_class::<PyResolvedStandard>()?;
+    m.add_class::<PySearchResult>()?;
+    m.add_class::<PyFieldMatch>()?;
     m.add_class::<PyStandardsLibrary>()?;
     m.add_class::<PyStandardsFilter>()?;
+    m.add_class::<PyStandardsGraph>()?;
+    m.add_class::<PyNameParts>()?;
+    m.add_class::<PyComposedName>()?;
     m.add_class::<PyTestSuite>()?;
+    m.add_class::<PyErddapDataset>()?;
+    errors::register(m)?;
     Ok(())
 }
@@ -1,15 +1,19 @@
 use std::collections::BTreeMap;
 use std::convert::From;
+use std::sync::Arc;
 
 use dyn_clone;
 use pyo3::prelude::*;
-use standard_knowledge::Standard;
+use pyo3::types::PyDict;
+use standard_knowledge::qartod::QcFlags;
+use standard_knowledge::{AttrValue, Error, FieldMatch, ResolvedStandard, SearchResult, Standard};
 
+use crate::errors::to_py_err;
 use crate::test_suite::PyTestSuite;
 
 #[pyclass(name = "Standard")]
 #[derive(Clone)]
-pub struct PyStandard(pub Standard);
+pub struct PyStandard(pub Arc<Standard>);
 
 /// A CF compatible standard
 #[pymethods]
@@ -18,6 +22,33 @@ impl PyStandard {
         Ok(format!("<Standard: {}>", self.0.name))
     }
 
+    /// Pickle by way of JSON
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<(Bound<'py, PyAny>, (String,))> {
+        let from_json = py.get_type::<PyStandard>().getattr("from_json")?;
+        Ok((from_json, (self.to_json(),)))
+    }
+
+    /// Serialize the standard, including its test suites, as JSON
+    fn to_json(&self) -> String {
+        serde_json::to_string(self.0.as_ref()).expect("standards always serialize")
+    }
+
+    /// Load a standard serialized with `to_json`
+    #[staticmethod]
+    fn from_json(py: Python, json: &str) -> PyResult<Self> {
+        serde_json::from_str(json)
+            .map(|standard: Standard| PyStandard(Arc::new(standard)))
+            .map_err(|e| {
+                to_py_err(
+                    py,
+                    Error::Parse {
+                        input: "standard".to_string(),
+                        message: e.to_string(),
+                    },
+                )
+            })
+    }
+
     #[getter]
     fn name(&self) -> PyResult<String> {
         Ok(self.0.name.clone())
@@ -43,11 +74,31 @@ impl PyStandard {
         Ok(self.0.aliases.clone())
     }
 
+    /// GRIB parameter code from the CF standard name table
+    #[getter]
+    fn grib(&self) -> PyResult<Option<String>> {
+        Ok(self.0.grib.clone())
+    }
+
+    /// AMIP variable name from the CF standard name table
+    #[getter]
+    fn amip(&self) -> PyResult<Option<String>> {
+        Ok(self.0.amip.clone())
+    }
+
     #[getter]
     fn ioos_category(&self) -> PyResult<Option<String>> {
         Ok(self.0.ioos_category.clone())
     }
 
+    /// IOOS category inferred from the name and unit, with how confident it is from 0 to 1
+    ///
+    /// Unlike `ioos_category`, this isn't from knowledge, and is inferred even if there is one.
+    fn infer_ioos_category(&self) -> (String, f64) {
+        let inferred = self.0.infer_ioos_category();
+        (inferred.category, inferred.confidence)
+    }
+
     #[getter]
     fn common_variable_names(&self) -> PyResult<Vec<String>> {
         Ok(self.0.common_variable_names.clone())
@@ -87,15 +138,208 @@ impl PyStandard {
     }
 
     /// Return a dictionary of Xarray attributes
-    fn attrs(&self) -> PyResult<BTreeMap<&str, &str>> {
-        let map = self.0.xarray_attrs();
+    ///
+    /// Numeric attributes are ints, floats, or lists of numbers rather than strings.
+    fn attrs<'py>(&self, py: Python<'py>) -> PyResult<BTreeMap<&str, Bound<'py, PyAny>>> {
+        self.0
+            .xarray_attrs()
+            .into_iter()
+            .map(|(key, value)| Ok((key, attr_value_to_py(py, value)?)))
+            .collect()
+    }
+
+    /// Return the CF ancillary QARTOD flag variables for a variable with this standard,
+    /// or `None` if it doesn't have QARTOD test suites
+    ///
+    /// The dict has the parent variable's `ancillary_variables`,
+    /// and the attributes of each flag `variables` by name.
+    /// The variable name defaults to the standard name.
+    #[pyo3(signature = (variable_name=None))]
+    fn qc_flags<'py>(
+        &self,
+        py: Python<'py>,
+        variable_name: Option<&str>,
+    ) -> PyResult<Option<Bound<'py, PyDict>>> {
+        self.0
+            .qc_flags(variable_name)
+            .map(|flags| qc_flags_to_py(py, &flags))
+            .transpose()
+    }
 
-        Ok(map)
+    /// Return an ERDDAP datasets.xml `<dataVariable>` block
+    ///
+    /// The source name defaults to the standard name, and the destination name to the source name.
+    #[pyo3(signature = (source_name=None, destination_name=None))]
+    fn erddap(&self, source_name: Option<&str>, destination_name: Option<&str>) -> String {
+        self.0.erddap_data_variable(source_name, destination_name)
+    }
+
+    /// Return a NetCDF CDL declaration for a double variable with the standard's attributes
+    ///
+    /// The variable name defaults to the standard name.
+    #[pyo3(signature = (variable_name=None, dimensions=vec!["time".to_string()]))]
+    fn cdl(&self, variable_name: Option<&str>, dimensions: Vec<String>) -> String {
+        let dimensions: Vec<&str> = dimensions.iter().map(String::as_str).collect();
+        self.0.cdl_variable(variable_name, &dimensions)
     }
 }
 
 impl From<Standard> for PyStandard {
     fn from(standard: Standard) -> Self {
+        PyStandard(Arc::new(standard))
+    }
+}
+
+impl From<Arc<Standard>> for PyStandard {
+    fn from(standard: Arc<Standard>) -> Self {
         PyStandard(standard)
     }
 }
+
+/// A standard found by name or alias, along with the alias that matched
+#[pyclass(name = "ResolvedStandard")]
+#[derive(Clone)]
+pub struct PyResolvedStandard(pub ResolvedStandard);
+
+#[pymethods]
+impl PyResolvedStandard {
+    fn __repr__(&self) -> PyResult<String> {
+        match &self.0.alias {
+            Some(alias) => Ok(format!(
+                "<ResolvedStandard: {} (alias {alias})>",
+                self.0.standard.name
+            )),
+            None => Ok(format!("<ResolvedStandard: {}>", self.0.standard.name)),
+        }
+    }
+
+    #[getter]
+    fn standard(&self) -> PyResult<PyStandard> {
+        Ok(PyStandard(self.0.standard.clone()))
+    }
+
+    /// The superseded alias that was used to find the standard
+    #[getter]
+    fn alias(&self) -> PyResult<Option<String>> {
+        Ok(self.0.alias.clone())
+    }
+
+    /// Version of the loaded CF standard name table, if the standard was found by an alias
+    #[getter]
+    fn table_version(&self) -> PyResult<Option<String>> {
+        Ok(self.0.table_version.clone())
+    }
+
+    /// Was the standard found by a superseded alias
+    fn is_alias(&self) -> bool {
+        self.0.is_alias()
+    }
+}
+
+/// How a search matched one field of a standard
+#[pyclass(name = "FieldMatch")]
+#[derive(Clone)]
+pub struct PyFieldMatch(pub FieldMatch);
+
+#[pymethods]
+impl PyFieldMatch {
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "<FieldMatch: {} {:?} ({:.1})>",
+            self.0.field, self.0.value, self.0.score
+        ))
+    }
+
+    /// Field that matched, such as `name`, `alias`, or `long_name`
+    #[getter]
+    fn field(&self) -> PyResult<String> {
+        Ok(self.0.field.to_string())
+    }
+
+    /// Value of the field that matched
+    #[getter]
+    fn value(&self) -> PyResult<String> {
+        Ok(self.0.value.clone())
+    }
+
+    /// Did the whole search match the value
+    #[getter]
+    fn exact(&self) -> PyResult<bool> {
+        Ok(self.0.exact)
+    }
+
+    /// How much this match contributed to the score
+    #[getter]
+    fn score(&self) -> PyResult<f64> {
+        Ok(self.0.score)
+    }
+}
+
+/// A standard found by a search, with how relevant it is and why
+#[pyclass(name = "SearchResult")]
+#[derive(Clone)]
+pub struct PySearchResult(pub SearchResult);
+
+#[pymethods]
+impl PySearchResult {
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "<SearchResult: {} ({:.1})>",
+            self.0.standard.name, self.0.score
+        ))
+    }
+
+    #[getter]
+    fn standard(&self) -> PyResult<PyStandard> {
+        Ok(PyStandard(self.0.standard.clone()))
+    }
+
+    /// Relevance, higher is better
+    #[getter]
+    fn score(&self) -> PyResult<f64> {
+        Ok(self.0.score)
+    }
+
+    /// Fields that matched, best first
+    #[getter]
+    fn matches(&self) -> PyResult<Vec<PyFieldMatch>> {
+        Ok(self.0.matches.iter().cloned().map(PyFieldMatch).collect())
+    }
+
+    /// Describe which fields matched
+    fn explain(&self) -> String {
+        self.0.explain()
+    }
+}
+
+/// QARTOD flags as a dict with the parent's `ancillary_variables`,
+/// and the attributes of each flag variable by name
+pub(crate) fn qc_flags_to_py<'py>(
+    py: Python<'py>,
+    flags: &QcFlags,
+) -> PyResult<Bound<'py, PyDict>> {
+    let variables = PyDict::new(py);
+    for variable in &flags.variables {
+        let attrs = PyDict::new(py);
+        for (name, value) in &variable.attrs {
+            attrs.set_item(name, attr_value_to_py(py, value.clone())?)?;
+        }
+        variables.set_item(&variable.name, attrs)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("ancillary_variables", &flags.ancillary_variables)?;
+    dict.set_item("variables", variables)?;
+    Ok(dict)
+}
+
+/// Attribute value as a native Python str, int, float, or list
+pub(crate) fn attr_value_to_py(py: Python<'_>, value: AttrValue) -> PyResult<Bound<'_, PyAny>> {
+    Ok(match value {
+        AttrValue::Text(text) => text.into_pyobject(py)?.into_any(),
+        AttrValue::Int(int) => int.into_pyobject(py)?.into_any(),
+        AttrValue::Float(float) => float.into_pyobject(py)?.into_any(),
+        AttrValue::Ints(ints) => ints.into_pyobject(py)?.into_any(),
+        AttrValue::Floats(floats) => floats.into_pyobject(py)?.into_any(),
+    })
+}
@@ -1,10 +1,12 @@
 use std::convert::From;
 
-use pyo3::exceptions::{PyIndexError, PyKeyError};
+use pyo3::exceptions::PyIndexError;
 use pyo3::prelude::*;
 
-use crate::PyStandard;
-use standard_knowledge::standards_filter::StandardsFilter;
+use crate::errors::to_py_err;
+use crate::{PySearchResult, PyStandard};
+use standard_knowledge::qartod::types::QartodTestTypes;
+use standard_knowledge::standards_filter::{SortKey, StandardsFilter};
 
 #[pyclass(name = "StandardsFilter")]
 #[derive(Clone)]
@@ -32,6 +34,20 @@ impl PyStandardsFilter {
         Py::new(py, PyStandardsFilter { inner: filtered })
     }
 
+    /// Return standards by IOOS category, inferring it for standards without one
+    #[pyo3(signature = (category, min_confidence=0.5))]
+    fn by_inferred_ioos_category(
+        &self,
+        py: Python,
+        category: &str,
+        min_confidence: f64,
+    ) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self
+            .inner
+            .by_inferred_ioos_category(category, min_confidence);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
     /// Return standards for a given unit
     fn by_unit(&self, py: Python, unit: &str) -> PyResult<Py<PyStandardsFilter>> {
         let filtered = self.inner.by_unit(unit);
@@ -44,12 +60,147 @@ impl PyStandardsFilter {
         Py::new(py, PyStandardsFilter { inner: filtered })
     }
 
-    /// Return standards that match a search pattern
+    /// Return standards with a QARTOD test suite that includes a type of test, like `spike`
+    fn by_qartod_test_type(&self, py: Python, test_type: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let test_type: QartodTestTypes = test_type.parse().map_err(|e| to_py_err(py, e))?;
+        let filtered = self.inner.by_qartod_test_type(&test_type);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards with an extra attribute, optionally with a specific value
+    #[pyo3(signature = (key, value=None))]
+    fn by_extra_attr(
+        &self,
+        py: Python,
+        key: &str,
+        value: Option<&str>,
+    ) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.by_extra_attr(key, value);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that list another standard as a sibling
+    fn by_sibling(&self, py: Python, standard_name: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.by_sibling(standard_name);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that list another standard as related
+    fn by_related(&self, py: Python, standard_name: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.by_related(standard_name);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that have IOOS knowledge beyond the CF standard name table
+    fn has_knowledge(&self, py: Python) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.has_knowledge();
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards with an alias, without matching standard names
+    fn by_alias(&self, py: Python, alias: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.by_alias(alias);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards whose name starts with a prefix
+    fn by_name_prefix(&self, py: Python, prefix: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.by_name_prefix(prefix);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards whose name ends with a suffix
+    fn by_name_suffix(&self, py: Python, suffix: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.by_name_suffix(suffix);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards whose name matches a regular expression
+    fn by_name_regex(&self, py: Python, pattern: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self
+            .inner
+            .by_name_regex(pattern)
+            .map_err(|e| to_py_err(py, e))?;
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that are in either filter, also available as `a | b`
+    fn union(&self, py: Python, other: &PyStandardsFilter) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.union(&other.inner);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that are in both filters, also available as `a & b`
+    fn intersection(
+        &self,
+        py: Python,
+        other: &PyStandardsFilter,
+    ) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.intersection(&other.inner);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that aren't in the other filter, also available as `a - b`
+    fn difference(&self, py: Python, other: &PyStandardsFilter) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.difference(&other.inner);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    fn __or__(&self, py: Python, other: &PyStandardsFilter) -> PyResult<Py<PyStandardsFilter>> {
+        self.union(py, other)
+    }
+
+    fn __and__(&self, py: Python, other: &PyStandardsFilter) -> PyResult<Py<PyStandardsFilter>> {
+        self.intersection(py, other)
+    }
+
+    fn __sub__(&self, py: Python, other: &PyStandardsFilter) -> PyResult<Py<PyStandardsFilter>> {
+        self.difference(py, other)
+    }
+
+    /// Sort standards by `name`, `unit`, `ioos_category`, or `long_name`
+    #[pyo3(signature = (key="name", descending=false))]
+    fn sort_by(&self, py: Python, key: &str, descending: bool) -> PyResult<Py<PyStandardsFilter>> {
+        let key: SortKey = key.parse().map_err(|e| to_py_err(py, e))?;
+        let filtered = self.inner.sort_by(key, descending);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return a page of standards, skipping `offset` and returning at most `limit`
+    fn paginate(&self, py: Python, offset: usize, limit: usize) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.paginate(offset, limit);
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that match a structured query,
+    /// like `category:Temperature AND unit:K AND NOT name:*_at_sea_floor`
+    fn query(&self, py: Python, query: &str) -> PyResult<Py<PyStandardsFilter>> {
+        let filtered = self.inner.query(query).map_err(|e| to_py_err(py, e))?;
+        Py::new(py, PyStandardsFilter { inner: filtered })
+    }
+
+    /// Return standards that match a search pattern, most relevant first
     fn search(&self, py: Python, search_str: &str) -> PyResult<Py<PyStandardsFilter>> {
         let filtered = self.inner.search(search_str);
         Py::new(py, PyStandardsFilter { inner: filtered })
     }
 
+    /// Return a NetCDF CDL skeleton with a variable for each standard,
+    /// along an unlimited time dimension
+    #[pyo3(signature = (dataset_name, cf_version=None))]
+    fn to_cdl(&self, dataset_name: &str, cf_version: Option<&str>) -> String {
+        self.inner.to_cdl(dataset_name, cf_version)
+    }
+
+    /// Return search results with their scores and which fields matched, most relevant first
+    fn search_ranked(&self, search_str: &str) -> Vec<PySearchResult> {
+        self.inner
+            .search_ranked(search_str)
+            .into_iter()
+            .map(PySearchResult)
+            .collect()
+    }
+
     /// Get a specific standard by name or alias
     fn get(&self, py: Python, standard_name_or_alias: &str) -> PyResult<Py<PyStandard>> {
         match self.inner.get(standard_name_or_alias) {
@@ -57,7 +208,7 @@ impl PyStandardsFilter {
                 let py_standard = PyStandard(standard.clone());
                 Py::new(py, py_standard)
             }
-            Err(_) => Err(PyKeyError::new_err("Unknown Standard")),
+            Err(e) => Err(to_py_err(py, e)),
         }
     }
 
@@ -1,10 +1,18 @@
 use std::collections::{BTreeMap, HashMap};
 
-use pyo3::{exceptions::PyKeyError, prelude::*, types::PyDict};
-
-use crate::standard::PyStandard;
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+
+use crate::errors::to_py_err;
+use crate::standard::{PyResolvedStandard, PyStandard};
 use standard_knowledge::qartod::static_qc::StaticQc;
-use standard_knowledge::{Knowledge, StandardsLibrary};
+use standard_knowledge::{
+    AttrValue, DatasetMetadata, ErddapDatasetType, Knowledge, KnowledgeRule, ProfileProblem,
+    StandardsLibrary, VariableCheck,
+};
 
 #[pyclass(name = "StandardsLibrary")]
 #[derive(Clone)]
@@ -15,15 +23,37 @@ pub struct PyStandardsLibrary(pub StandardsLibrary);
 impl PyStandardsLibrary {
     #[new]
     fn new() -> Self {
-        Self(StandardsLibrary {
-            standards: HashMap::new(),
-        })
+        Self(StandardsLibrary::default())
+    }
+
+    /// Pickle as a snapshot, so knowledge isn't loaded and merged again
+    fn __getstate__(&self) -> String {
+        self.0.to_snapshot()
+    }
+
+    fn __setstate__(&mut self, py: Python, state: &str) -> PyResult<()> {
+        self.0 = StandardsLibrary::from_snapshot(state).map_err(|e| to_py_err(py, e))?;
+        Ok(())
+    }
+
+    /// Snapshot of the fully built library as JSON,
+    /// that can be reloaded with `from_snapshot` without loading or merging knowledge again
+    fn to_snapshot(&self) -> String {
+        self.0.to_snapshot()
+    }
+
+    /// Reload a library from a snapshot made by `to_snapshot`
+    #[staticmethod]
+    fn from_snapshot(py: Python, snapshot: &str) -> PyResult<Self> {
+        StandardsLibrary::from_snapshot(snapshot)
+            .map(Self)
+            .map_err(|e| to_py_err(py, e))
     }
 
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!(
             "<StandardsLibrary: {} standards>",
-            self.0.standards.len()
+            self.0.standards().len()
         ))
     }
 
@@ -31,15 +61,52 @@ impl PyStandardsLibrary {
     #[getter]
     fn standards(&self) -> PyResult<HashMap<String, crate::standard::PyStandard>> {
         let mut py_standards = HashMap::new();
-        for (key, standard) in &self.0.standards {
+        for (key, standard) in self.0.standards() {
             py_standards.insert(key.clone(), crate::standard::PyStandard(standard.clone()));
         }
         Ok(py_standards)
     }
 
     /// Load CF standards into library
-    fn load_cf_standards(&mut self) {
-        self.0.load_cf_standards();
+    fn load_cf_standards(&mut self, py: Python) -> PyResult<()> {
+        self.0.load_cf_standards().map_err(|e| to_py_err(py, e))
+    }
+
+    /// Load CF standards from the official CF standard name table XML
+    fn load_cf_standards_from_xml(&mut self, py: Python, xml: &str) -> PyResult<()> {
+        self.0
+            .load_cf_standards_from_xml(xml)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Load CF standards from the YAML standard name table format
+    fn load_cf_standards_from_yaml(&mut self, py: Python, yaml: &str) -> PyResult<()> {
+        self.0
+            .load_cf_standards_from_yaml(yaml)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Load CF standards from a local XML or YAML standard name table
+    fn load_cf_standards_from_path(
+        &mut self,
+        py: Python,
+        path: std::path::PathBuf,
+    ) -> PyResult<()> {
+        self.0
+            .load_cf_standards_from_path(path)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Version number of the loaded CF standard name table
+    #[getter]
+    fn cf_version(&self) -> Option<String> {
+        self.0.cf_version.clone()
+    }
+
+    /// When the loaded CF standard name table was last modified
+    #[getter]
+    fn cf_last_modified(&self) -> Option<String> {
+        self.0.cf_last_modified.clone()
     }
 
     /// Get a standard by standard name or aliases
@@ -49,10 +116,23 @@ impl PyStandardsLibrary {
                 let py_standard = PyStandard(standard);
                 Py::new(py, py_standard)
             }
-            Err(e) => Err(PyKeyError::new_err(e.to_string())),
+            Err(e) => Err(to_py_err(py, e)),
+        }
+    }
+
+    /// Get a standard by standard name or alias, along with the alias that matched
+    fn resolve(&self, py: Python, name_or_alias: &str) -> PyResult<Py<PyResolvedStandard>> {
+        match self.0.resolve(name_or_alias) {
+            Ok(resolved) => Py::new(py, PyResolvedStandard(resolved)),
+            Err(e) => Err(to_py_err(py, e)),
         }
     }
 
+    /// Rewrite a CDL header to use current canonical standard names
+    fn upgrade_cdl(&self, cdl: &str) -> String {
+        self.0.upgrade_cdl(cdl).0
+    }
+
     /// Apply knowledge to loaded standards
     fn apply_knowledge(
         &mut self,
@@ -106,8 +186,32 @@ impl PyStandardsLibrary {
     }
 
     /// Load community knowledge baked into the library
-    fn load_knowledge(&mut self) {
-        self.0.load_knowledge();
+    fn load_knowledge(&mut self, py: Python) -> PyResult<()> {
+        self.0.load_knowledge().map_err(|e| to_py_err(py, e))
+    }
+
+    /// Apply rules that give families of standards default knowledge
+    ///
+    /// The YAML is a list of rules, each with either a `match` glob or a `regex`,
+    /// and the `ioos_category`, `extra_attrs`, and `other_units` to fill in.
+    /// Rules never override knowledge for a specific standard.
+    fn apply_rules_from_yaml(&mut self, py: Python, yaml: &str) -> PyResult<()> {
+        let rules = KnowledgeRule::from_yaml("rules", yaml).map_err(|e| to_py_err(py, e))?;
+        self.0.apply_rules(rules);
+        Ok(())
+    }
+
+    /// Patterns of the rules that apply to a standard, in the order they take precedence
+    fn rules_for(&self, py: Python, name_or_alias: &str) -> PyResult<Vec<String>> {
+        self.0
+            .rules_for(name_or_alias)
+            .map(|rules| rules.iter().map(|rule| rule.pattern.to_string()).collect())
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Load QARTOD test suites that are implemented in the library
+    fn load_test_suites(&mut self) {
+        self.0.load_test_suites();
     }
 
     /// Return a standards filter for chaining operations
@@ -117,17 +221,190 @@ impl PyStandardsLibrary {
         Py::new(py, py_filter)
     }
 
+    /// Return a graph of the related and sibling links between standards
+    fn graph(&self) -> crate::PyStandardsGraph {
+        crate::PyStandardsGraph(self.0.graph())
+    }
+
+    /// Compose a standard name from its parts, and check if it is in the library
+    fn check_name(
+        &self,
+        py: Python,
+        parts: &crate::PyNameParts,
+    ) -> PyResult<crate::PyComposedName> {
+        self.0
+            .check_name(&parts.0)
+            .map(crate::PyComposedName)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Generate an ERDDAP `<dataset>` for source columns, resolved to standards
+    /// by name, alias, or common variable name
+    ///
+    /// `dataset_type` is `ascii` for EDDTableFromAsciiFiles or `nc` for EDDTableFromNcFiles.
+    /// Metadata that isn't given is written as `???` to be filled in.
+    #[pyo3(signature = (
+        columns,
+        dataset_id,
+        dataset_type="ascii",
+        *,
+        title=None,
+        summary=None,
+        institution=None,
+        info_url=None,
+        license=None,
+        creator_name=None,
+        creator_email=None,
+        creator_url=None,
+        cdm_data_type=None,
+        file_dir=None,
+        file_name_regex=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn erddap_dataset(
+        &self,
+        py: Python,
+        columns: Vec<String>,
+        dataset_id: &str,
+        dataset_type: &str,
+        title: Option<String>,
+        summary: Option<String>,
+        institution: Option<String>,
+        info_url: Option<String>,
+        license: Option<String>,
+        creator_name: Option<String>,
+        creator_email: Option<String>,
+        creator_url: Option<String>,
+        cdm_data_type: Option<String>,
+        file_dir: Option<String>,
+        file_name_regex: Option<String>,
+    ) -> PyResult<crate::PyErddapDataset> {
+        let dataset_type: ErddapDatasetType = dataset_type.parse().map_err(|e| to_py_err(py, e))?;
+        let metadata = DatasetMetadata {
+            title,
+            summary,
+            institution,
+            info_url,
+            license,
+            creator_name,
+            creator_email,
+            creator_url,
+            cdm_data_type,
+            file_dir,
+            file_name_regex,
+            ..DatasetMetadata::new(dataset_id)
+        };
+        self.0
+            .erddap_dataset(&columns, dataset_type, metadata)
+            .map(crate::PyErddapDataset)
+            .map_err(|e| to_py_err(py, e))
+    }
+
+    /// Check a variable's attributes against the IOOS Metadata Profile 1.2
+    ///
+    /// Returns a dict for each missing or invalid attribute, required ones first,
+    /// with the `attribute`, its `requirement`, the `problem` (`missing` or `invalid`),
+    /// and a `message`.
+    fn check_ioos_profile<'py>(
+        &self,
+        py: Python<'py>,
+        attrs: &Bound<'py, PyAny>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let attrs = attrs_argument(attrs)?;
+        self.0
+            .check_ioos_profile(&attrs)
+            .into_iter()
+            .map(|issue| {
+                let dict = PyDict::new(py);
+                dict.set_item("attribute", &issue.attribute)?;
+                dict.set_item("requirement", issue.requirement.to_string())?;
+                dict.set_item(
+                    "problem",
+                    match issue.problem {
+                        ProfileProblem::Missing => "missing",
+                        ProfileProblem::Invalid(_) => "invalid",
+                    },
+                )?;
+                dict.set_item("message", issue.to_string())?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// Return a copy of a variable's attributes, with missing ones filled in
+    /// from the knowledge for its `standard_name`
+    fn fill_ioos_profile_defaults<'py>(
+        &self,
+        py: Python<'py>,
+        attrs: &Bound<'py, PyAny>,
+    ) -> PyResult<BTreeMap<String, Bound<'py, PyAny>>> {
+        let mut attrs = attrs_argument(attrs)?;
+        self.0.fill_ioos_profile_defaults(&mut attrs);
+        attrs
+            .into_iter()
+            .map(|(name, value)| Ok((name, crate::standard::attr_value_to_py(py, value)?)))
+            .collect()
+    }
+
+    /// Check the variables of a CDL header, such as from `ncdump -h`, against the standards
+    ///
+    /// Returns a dict for each variable with its `variable` name, its current `standard_name`
+    /// if it is known, and its `findings`, most serious first.
+    /// Each finding has a `kind`, a `severity` (`error`, `warning`, or `info`), and a `message`.
+    fn check_cdl<'py>(&self, py: Python<'py>, cdl: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let checks = self.0.check_cdl(cdl).map_err(|e| to_py_err(py, e))?;
+        checks_to_py(py, checks)
+    }
+
+    /// Check the variables of a NetCDF-3 classic or 64-bit offset file against the standards,
+    /// from the bytes of the file, returning the same as `check_cdl`
+    ///
+    /// Only the bytes up to the end of the header are needed. CDL text is also accepted.
+    fn check_netcdf<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let checks = self.0.check_netcdf(data).map_err(|e| to_py_err(py, e))?;
+        checks_to_py(py, checks)
+    }
+
     /// Return known IOOS Categories
     fn known_ioos_categories(&self) -> Vec<String> {
         self.0.known_ioos_categories().into_iter().collect()
     }
 }
 
+/// Findings for each variable of a header, as dicts
+fn checks_to_py(py: Python<'_>, checks: Vec<VariableCheck>) -> PyResult<Vec<Bound<'_, PyDict>>> {
+    checks
+        .into_iter()
+        .map(|check| {
+            let findings = check
+                .findings
+                .iter()
+                .map(|finding| {
+                    let dict = PyDict::new(py);
+                    let kind = serde_json::to_value(finding)
+                        .ok()
+                        .and_then(|value| value["kind"].as_str().map(str::to_string));
+                    dict.set_item("kind", kind)?;
+                    dict.set_item("severity", finding.severity().to_string())?;
+                    dict.set_item("message", finding.to_string())?;
+                    Ok(dict)
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+
+            let dict = PyDict::new(py);
+            dict.set_item("variable", &check.variable)?;
+            dict.set_item("standard_name", &check.standard_name)?;
+            dict.set_item("findings", findings)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 enum KnowledgeValues {
     String(String),
     List(Vec<String>),
-    Dict(BTreeMap<String, String>),
+    Dict(BTreeMap<String, AttrValue>),
     QC(BTreeMap<String, StaticQc>),
 }
 
@@ -145,8 +422,8 @@ impl<'a, 'py> FromPyObject<'a, 'py> for KnowledgeValues {
             return Ok(KnowledgeValues::List(list));
         }
 
-        // Try to extract as dict of strings
-        if let Ok(dict) = ob.extract::<BTreeMap<String, String>>() {
+        // Try to extract as dict of attribute values
+        if let Some(dict) = extract_attrs(&ob) {
             return Ok(KnowledgeValues::Dict(dict));
         }
 
@@ -241,14 +518,47 @@ fn get_list_field(
     }
 }
 
+/// Dict of strings, ints, floats, or lists of numbers,
+/// or `None` if any of the values is something else
+fn extract_attrs(ob: &Bound<'_, PyAny>) -> Option<BTreeMap<String, AttrValue>> {
+    let dict = ob.cast::<PyDict>().ok()?;
+    let mut attrs = BTreeMap::new();
+
+    for (key, value) in dict.iter() {
+        let value = if let Ok(text) = value.extract::<String>() {
+            AttrValue::Text(text)
+        } else if let Ok(int) = value.extract::<i64>() {
+            AttrValue::Int(int)
+        } else if let Ok(float) = value.extract::<f64>() {
+            AttrValue::Float(float)
+        } else if let Ok(ints) = value.extract::<Vec<i64>>() {
+            AttrValue::Ints(ints)
+        } else if let Ok(floats) = value.extract::<Vec<f64>>() {
+            AttrValue::Floats(floats)
+        } else {
+            return None;
+        };
+        attrs.insert(key.extract().ok()?, value);
+    }
+
+    Some(attrs)
+}
+
+/// Variable attributes passed from Python
+fn attrs_argument(attrs: &Bound<'_, PyAny>) -> PyResult<BTreeMap<String, AttrValue>> {
+    extract_attrs(attrs).ok_or_else(|| {
+        PyValueError::new_err("Attributes must be a dict of strings, numbers, or lists of numbers")
+    })
+}
+
 fn get_dict_field(
     knowledge: &HashMap<String, KnowledgeValues>,
     key: &str,
-) -> PyResult<BTreeMap<String, String>> {
+) -> PyResult<BTreeMap<String, AttrValue>> {
     match knowledge.get(key) {
         Some(KnowledgeValues::Dict(dict_value)) => Ok(dict_value.clone()),
         Some(_) => Err(PyKeyError::new_err(format!(
-            "`{key}` must be a dictionary of strings"
+            "`{key}` must be a dictionary of strings, numbers, or lists of numbers"
         ))),
         None => Ok(BTreeMap::new()),
     }
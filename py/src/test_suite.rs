@@ -3,6 +3,8 @@ use pyo3::types::PyDict;
 use standard_knowledge::qartod::{types::ArgumentValue, TestSuite};
 use std::collections::HashMap;
 
+use crate::errors::to_py_err;
+
 #[pyclass(name = "TestSuite")]
 #[derive(Clone)]
 pub struct PyTestSuite {
@@ -35,6 +37,23 @@ impl PyTestSuite {
         Ok(dict.into())
     }
 
+    /// Return the CF ancillary flag variables for the results of the suite's tests on a variable,
+    /// as a dict with the parent's `ancillary_variables` and the attributes of each flag `variables`
+    ///
+    /// The long name defaults to the variable name.
+    #[pyo3(signature = (variable_name, long_name=None))]
+    fn qc_flags<'py>(
+        &self,
+        py: Python<'py>,
+        variable_name: &str,
+        long_name: Option<&str>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let flags = self
+            .test_suite
+            .qc_flags(variable_name, long_name.unwrap_or(variable_name));
+        crate::standard::qc_flags_to_py(py, &flags)
+    }
+
     /// Generate a configuration for the test suite
     fn scaffold(
         &self,
@@ -102,7 +121,7 @@ impl PyTestSuite {
 
                 Ok(result.into())
             }
-            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e)),
+            Err(e) => Err(to_py_err(py, e)),
         }
     }
 }
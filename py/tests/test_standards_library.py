@@ -1,3 +1,6 @@
+import pickle
+import struct
+
 import pytest
 
 import standard_knowledge
@@ -95,11 +98,132 @@ def test_search_standard(library):
     standards = library.filter().search("pressure")
 
     assert len(standards) > 0
-    # search is a bit fuzzier, so harder to tell where the expected pressure will end up
-    # pressure = standards[0]
-    # assert pressure.name == KNOWLEDGE["name"], (
-    #     "since there isn't a direct name or alias match, the suggested column should make it first"
-    # )
+    pressure = standards[0]
+    assert pressure.name == KNOWLEDGE["name"], (
+        "since there isn't a direct name or alias match, the suggested column should make it first"
+    )
+
+
+def test_query_standards(library):
+    library.apply_knowledge([KNOWLEDGE])
+
+    standards = library.filter().query(
+        "category:Meteorology AND unit:Pa AND NOT name:*_at_sea_floor"
+    )
+    assert [standard.name for standard in standards] == [KNOWLEDGE["name"]]
+
+    standards = library.filter().query("var:pressure OR name:air_temperature")
+    assert [standard.name for standard in standards] == [
+        KNOWLEDGE["name"],
+        "air_temperature",
+    ]
+
+
+def test_query_parse_error(library):
+    with pytest.raises(standard_knowledge.ParseError) as e:
+        library.filter().query("colour:red")
+
+    assert e.value.input == "query `colour:red`"
+
+
+def test_filter_predicates(library):
+    library.apply_knowledge([KNOWLEDGE])
+
+    assert [s.name for s in library.filter().by_related("air_pressure")] == [
+        KNOWLEDGE["name"]
+    ]
+    assert [s.name for s in library.filter().has_knowledge()] == [KNOWLEDGE["name"]]
+    assert [s.name for s in library.filter().by_alias("air_pressure_at_sea_level")] == [
+        KNOWLEDGE["name"]
+    ]
+    assert all(
+        s.name.startswith("sea_water_")
+        for s in library.filter().by_name_prefix("sea_water_")
+    )
+    assert all(
+        s.name.endswith("_at_sea_floor")
+        for s in library.filter().by_name_suffix("_at_sea_floor")
+    )
+    assert len(library.filter().by_name_regex("^air_pressure$")) == 1
+
+    with pytest.raises(standard_knowledge.InvalidArgumentError):
+        library.filter().by_name_regex("air_(pressure")
+
+    with pytest.raises(standard_knowledge.InvalidArgumentError):
+        library.filter().by_qartod_test_type("not_a_test")
+
+
+def test_filter_set_operations(library):
+    pascals = library.filter().by_unit("Pa")
+    air_pressure = library.filter().by_name_prefix("air_pressure")
+
+    both = pascals & air_pressure
+    assert len(both) == len(pascals.intersection(air_pressure))
+    assert all(s.unit == "Pa" and s.name.startswith("air_pressure") for s in both)
+
+    assert len(pascals | air_pressure) >= len(pascals)
+    assert all(not s.name.startswith("air_pressure") for s in pascals - air_pressure)
+
+
+def test_filter_sort_and_paginate(library):
+    standards = library.filter().by_name_prefix("sea_water_")
+    descending = standards.sort_by("name", descending=True)
+    assert descending[0].name > descending[1].name
+
+    page = descending.paginate(1, 2)
+    assert [s.name for s in page] == [descending[1].name, descending[2].name]
+
+
+def test_graph(library):
+    library.apply_knowledge([KNOWLEDGE])
+    graph = library.graph()
+
+    assert ("air_pressure", 1) in graph.within("air_pressure_at_sea_level")
+    assert graph.missing_back_links() == [
+        ("air_pressure", KNOWLEDGE["name"], "related")
+    ]
+    assert graph.sibling_groups() == []
+
+    neighborhood = graph.neighborhood(KNOWLEDGE["name"], hops=1)
+    assert neighborhood.nodes() == ["air_pressure", KNOWLEDGE["name"]]
+    assert '"air_pressure_at_mean_sea_level" -> "air_pressure"' in neighborhood.to_dot()
+
+    with pytest.raises(standard_knowledge.UnknownStandardError):
+        graph.within("air_presure_at_mean_sea_level")
+
+    with pytest.raises(standard_knowledge.InvalidArgumentError):
+        graph.within(KNOWLEDGE["name"], relationship="cousin")
+
+
+def test_parse_and_compose_names(library):
+    parts = standard_knowledge.NameParts.parse(
+        "tendency_of_sea_water_temperature_due_to_advection"
+    )
+    assert parts.transformations == ["tendency_of"]
+    assert parts.base == "sea_water_temperature"
+    assert parts.process == "advection"
+    assert parts.compose() == "tendency_of_sea_water_temperature_due_to_advection"
+
+    composed = library.check_name(
+        standard_knowledge.NameParts("sea_water_temperature", surface="sea_floor")
+    )
+    assert composed.is_known()
+    assert composed.standard.name == "sea_water_temperature_at_sea_floor"
+
+    composed = library.check_name(
+        standard_knowledge.NameParts(
+            "sea_water_temperature", transformations=["derivative_of:depth"]
+        )
+    )
+    assert composed.name == "derivative_of_sea_water_temperature_wrt_depth"
+    assert not composed.is_known()
+    assert "sea_water_temperature" in composed.alternatives
+
+    with pytest.raises(standard_knowledge.ParseError):
+        standard_knowledge.NameParts.parse("sea water temperature")
+
+    with pytest.raises(standard_knowledge.InvalidArgumentError):
+        standard_knowledge.NameParts("sea_water_temperature", medium="Sea Water").compose()
 
 
 def test_can_apply_and_get_qc(library):
@@ -143,3 +267,314 @@ def test_can_apply_and_get_qc(library):
     config = qc_test.scaffold({})
     assert config["qartod"]["flat_line_test"]["fail_threshold"] == 6
     assert config["qartod"]["spike_test"]["fail_threshold"] == 3.3
+
+    flags = updated_standard.qc_flags("pres")
+    assert flags["ancillary_variables"].split()[0] == "pres_qc_agg"
+    aggregate = flags["variables"]["pres_qc_agg"]
+    assert aggregate["standard_name"] == "aggregate_quality_flag"
+    assert aggregate["flag_values"] == [1, 2, 3, 4, 9]
+    assert aggregate["flag_meanings"] == "PASS NOT_EVALUATED SUSPECT FAIL MISSING"
+    gross_range = flags["variables"]["pres_qartod_gross_range_test"]
+    assert gross_range["standard_name"] == "gross_range_test_quality_flag"
+
+    assert qc_test.qc_flags("pres") == flags
+
+
+CF_XML = """<?xml version="1.0"?>
+<standard_name_table>
+   <version_number>12</version_number>
+   <last_modified>2009-10-19T12:00:00Z</last_modified>
+   <entry id="sea_water_temperature">
+      <canonical_units>K</canonical_units>
+      <grib>80</grib>
+      <description>Sea water temperature is the in situ temperature of the sea water.</description>
+   </entry>
+</standard_name_table>
+"""
+
+
+def test_load_cf_standards_from_xml():
+    library = standard_knowledge.StandardsLibrary()
+    library.load_cf_standards_from_xml(CF_XML)
+
+    assert library.cf_version == "12"
+    assert library.cf_last_modified == "2009-10-19T12:00:00Z"
+    standard = library.get("sea_water_temperature")
+    assert standard.unit == "K"
+    assert standard.grib == "80"
+    assert standard.amip is None
+
+    with pytest.raises(KeyError):
+        library.get("air_pressure_at_mean_sea_level")
+
+
+def test_load_cf_standards_from_invalid_xml():
+    library = standard_knowledge.StandardsLibrary()
+
+    with pytest.raises(ValueError):
+        library.load_cf_standards_from_xml("<not_a_table/>")
+
+    with pytest.raises(standard_knowledge.ParseError) as e:
+        library.load_cf_standards_from_xml("<not_a_table/>")
+
+    assert e.value.input == "CF standard name table XML"
+
+
+def test_resolve_alias(library):
+    resolved = library.resolve("air_pressure_at_sea_level")
+
+    assert resolved.is_alias()
+    assert resolved.alias == "air_pressure_at_sea_level"
+    assert resolved.standard.name == "air_pressure_at_mean_sea_level"
+
+    assert not library.resolve("air_pressure_at_mean_sea_level").is_alias()
+
+
+def test_upgrade_cdl(library):
+    cdl = '\t\tpressure:standard_name = "air_pressure_at_sea_level" ;\n'
+
+    assert (
+        library.upgrade_cdl(cdl)
+        == '\t\tpressure:standard_name = "air_pressure_at_mean_sea_level" ;\n'
+    )
+
+
+def test_unknown_standard_suggestions(library):
+    with pytest.raises(standard_knowledge.UnknownStandardError) as e:
+        library.get("sea_water_temprature")
+
+    assert e.value.standard_name == "sea_water_temprature"
+    assert e.value.suggestions[0] == "sea_water_temperature"
+    assert isinstance(e.value, KeyError)
+
+
+def test_scaffold_errors(library):
+    library.load_test_suites()
+    standard = library.get("sea_surface_height_above_geopotential_datum")
+    suites = {suite.info()["slug"]: suite for suite in standard.qc}
+
+    with pytest.raises(standard_knowledge.MissingArgumentError) as e:
+        suites["gulf_of_maine"].scaffold({"mhhw": 1.0})
+    assert e.value.argument == "mllw"
+
+    with pytest.raises(standard_knowledge.InvalidArgumentError):
+        suites["gulf_of_maine"].scaffold({"mllw": "low", "mhhw": 1.0})
+
+    with pytest.raises(standard_knowledge.UnsupportedSuiteError):
+        suites["long_island_sound"].scaffold({"mllw": 0.2})
+
+
+def test_search_ranked(library):
+    library.load_knowledge()
+
+    results = library.filter().search_ranked("temperature")
+    assert results[0].standard.name == "sea_water_temperature"
+    assert results[0].score >= results[1].score
+
+    fields = [field_match.field for field_match in results[0].matches]
+    assert "name" in fields
+    assert "long_name" in fields
+
+def test_knowledge_rules(library):
+    library.apply_rules_from_yaml(
+        "- match: sea_surface_wave_*\n"
+        "  ioos_category: Surface Waves\n"
+        "  other_units: [ft]\n"
+    )
+    period = library.get("sea_surface_wave_mean_period")
+    assert period.ioos_category == "Surface Waves"
+    assert period.other_units == ["ft"]
+    assert library.rules_for("sea_surface_wave_mean_period") == ["sea_surface_wave_*"]
+    assert library.rules_for("air_temperature") == []
+
+    library.apply_knowledge(
+        [{"name": "sea_surface_wave_mean_period", "ioos_category": "Other"}]
+    )
+    assert library.get("sea_surface_wave_mean_period").ioos_category == "Other"
+
+    with pytest.raises(standard_knowledge.InvalidKnowledgeError):
+        library.apply_rules_from_yaml("- ioos_category: Other\n")
+
+
+def test_infer_ioos_category(library):
+    thickness = library.get("sea_ice_thickness")
+    assert thickness.ioos_category is None
+    category, confidence = thickness.infer_ioos_category()
+    assert category == "Ice Distribution"
+    assert 0 < confidence <= 1
+
+    inferred = library.filter().by_inferred_ioos_category("Ice Distribution")
+    assert "sea_ice_thickness" in [standard.name for standard in inferred.standards]
+    assert len(library.filter().by_ioos_category("Ice Distribution").standards) == 0
+
+    queried = library.filter().query("inferred_category:Salinity")
+    assert "sea_water_absolute_salinity" in [s.name for s in queried.standards]
+
+
+def test_erddap_data_variable(library):
+    standard = library.get("sea_ice_thickness")
+    data_variable = standard.erddap(source_name="ice")
+    assert "<sourceName>ice</sourceName>" in data_variable
+    assert "<destinationName>ice</destinationName>" in data_variable
+    assert '<att name="ioos_category">Ice Distribution</att>' in data_variable
+    assert "inferred" in data_variable
+
+    assert "<sourceName>sea_ice_thickness</sourceName>" in standard.erddap()
+
+
+def test_cdl(library):
+    library.load_knowledge()
+    declaration = library.get("latitude").cdl("lat")
+    assert declaration.startswith("\tdouble lat(time) ;")
+    assert "\t\tlat:valid_min = -90.0 ;" in declaration
+
+    cdl = library.filter().by_name_prefix("sea_water_temperature").to_cdl("temps", "92")
+    assert cdl.startswith("netcdf temps {")
+    assert "\tdouble sea_water_temperature(time) ;" in cdl
+    assert ':standard_name_vocabulary = "CF Standard Name Table v92" ;' in cdl
+
+
+def test_typed_attrs(library):
+    library.load_knowledge()
+    attrs = library.get("latitude").attrs()
+    assert attrs["valid_min"] == -90
+    assert isinstance(attrs["valid_min"], int)
+    assert attrs["axis"] == "Y"
+
+    library.apply_knowledge(
+        [
+            {
+                "name": "sea_water_practical_salinity",
+                "extra_attrs": {
+                    "_FillValue": -9999.0,
+                    "valid_range": [0.0, 42.5],
+                    "flag_values": [1, 2, 3],
+                    "coverage_content_type": "physicalMeasurement",
+                },
+            }
+        ]
+    )
+    attrs = library.get("sea_water_practical_salinity").attrs()
+    assert attrs["_FillValue"] == -9999.0
+    assert attrs["valid_range"] == [0.0, 42.5]
+    assert attrs["flag_values"] == [1, 2, 3]
+    assert attrs["coverage_content_type"] == "physicalMeasurement"
+
+
+def test_ioos_profile(library):
+    library.load_knowledge()
+    issues = library.check_ioos_profile(
+        {"standard_name": "latitude", "coverage_content_type": "measurement"}
+    )
+    assert issues[0]["attribute"] == "units"
+    assert issues[0]["requirement"] == "required"
+    assert issues[0]["problem"] == "missing"
+    assert issues[1]["attribute"] == "coverage_content_type"
+    assert issues[1]["problem"] == "invalid"
+
+    attrs = library.fill_ioos_profile_defaults({"standard_name": "latitude", "units": "degree_north"})
+    assert attrs["units"] == "degree_north"
+    assert attrs["coverage_content_type"] == "coordinate"
+    assert attrs["valid_min"] == -90
+    issues = library.check_ioos_profile(attrs)
+    assert all(issue["requirement"] == "recommended" for issue in issues)
+
+
+def test_check_cdl(library):
+    library.load_knowledge()
+    checks = library.check_cdl(
+        """netcdf buoy {
+dimensions:
+    time = UNLIMITED ;
+variables:
+    float pressure(time) ;
+        pressure:standard_name = "air_pressure_at_sea_level" ;
+        pressure:units = "Pa" ;
+    float wspd(time) ;
+}
+"""
+    )
+    assert checks[0]["variable"] == "pressure"
+    assert checks[0]["standard_name"] == "air_pressure_at_mean_sea_level"
+    assert checks[0]["findings"][0]["kind"] == "alias_standard_name"
+    assert checks[0]["findings"][0]["severity"] == "warning"
+    assert checks[1]["standard_name"] is None
+    assert "wind_speed" in checks[1]["findings"][0]["message"]
+
+    with pytest.raises(standard_knowledge.ParseError):
+        library.check_cdl("not cdl")
+
+def test_check_netcdf(library):
+    def name(text):
+        data = text.encode()
+        return struct.pack(">I", len(data)) + data + b"\0" * (-len(data) % 4)
+
+    def text_attr(attr, text):
+        return name(attr) + struct.pack(">I", 2) + name(text)
+
+    header = (
+        b"CDF\x01"
+        + struct.pack(">III", 0, 0x0A, 1)
+        + name("time")
+        + struct.pack(">III", 0, 0, 0)
+        + struct.pack(">I", 0x0B)
+        + struct.pack(">I", 1)
+        + name("temp")
+        + struct.pack(">IIII", 1, 0, 0x0C, 1)
+        + text_attr("standard_name", "sea_water_temprature")
+        + struct.pack(">III", 5, 4, 0)
+    )
+
+    checks = library.check_netcdf(header)
+    assert checks[0]["variable"] == "temp"
+    assert checks[0]["findings"][0]["kind"] == "unknown_standard_name"
+    assert checks[0]["findings"][0]["severity"] == "error"
+    assert "sea_water_temperature" in checks[0]["findings"][0]["message"]
+
+    with pytest.raises(standard_knowledge.ParseError):
+        library.check_netcdf(header[:-4])
+
+def test_erddap_dataset(library):
+    library.load_knowledge()
+    library.load_test_suites()
+    dataset = library.erddap_dataset(
+        ["time", "latitude", "navd88_meters", "notes"],
+        "buoy_a01",
+        "nc",
+        title="Buoy A01",
+    )
+    assert dataset.unresolved() == ["notes"]
+    assert dataset.columns()[2][1] == "sea_surface_height_above_geopotential_datum"
+
+    xml = dataset.to_xml()
+    assert '<dataset type="EDDTableFromNcFiles" datasetID="buoy_a01" active="true">' in xml
+    assert '<att name="title">Buoy A01</att>' in xml
+    assert "<destinationName>navd88_meters_qc_agg</destinationName>" in xml
+
+    with pytest.raises(standard_knowledge.InvalidArgumentError):
+        library.erddap_dataset(["time"], "buoy-a01")
+
+    with pytest.raises(standard_knowledge.MissingArgumentError) as e:
+        library.erddap_dataset([], "buoy_a01")
+    assert e.value.argument == "columns"
+
+
+def test_pickle_and_snapshot(library):
+    library.load_knowledge()
+    library.load_test_suites()
+
+    standard = library.get("air_pressure")
+    unpickled = pickle.loads(pickle.dumps(standard))
+    assert unpickled.name == "air_pressure"
+    assert unpickled.long_name == standard.long_name
+    assert len(unpickled.qc) == len(standard.qc)
+
+    reloaded = pickle.loads(pickle.dumps(library))
+    assert len(reloaded.standards) == len(library.standards)
+    assert reloaded.get("sea_surface_height_above_geopotential_datum").qc
+
+    from_snapshot = standard_knowledge.StandardsLibrary.from_snapshot(library.to_snapshot())
+    assert from_snapshot.get("air_pressure_at_sea_level").name == "air_pressure_at_mean_sea_level"
+
+    with pytest.raises(standard_knowledge.ParseError):
+        standard_knowledge.StandardsLibrary.from_snapshot("{}")